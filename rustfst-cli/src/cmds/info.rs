@@ -0,0 +1,71 @@
+use anyhow::{bail, Context, Result};
+
+use rustfst::prelude::*;
+use rustfst::FstHeader;
+
+pub struct InfoCommand {
+    path_in: String,
+}
+
+impl InfoCommand {
+    pub fn new(path_in: &str) -> Self {
+        Self {
+            path_in: path_in.to_string(),
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let data = std::fs::read(self.path_in.as_str())
+            .with_context(|| format!("Can't open Fst binary file : {:?}", self.path_in))?;
+        let header = FstHeader::load(&data)
+            .with_context(|| format!("Can't read the header of {:?}", self.path_in))?;
+        match header.tr_type() {
+            "standard" => self.print_info_of_fst_type::<TropicalWeight>(&header, &data),
+            "log" => self.print_info_of_fst_type::<LogWeight>(&header, &data),
+            "probability" => self.print_info_of_fst_type::<ProbabilityWeight>(&header, &data),
+            tr_type => bail!(
+                "Unsupported fst type {:?} with arc type {:?}",
+                header.fst_type(),
+                tr_type
+            ),
+        }
+    }
+
+    fn print_info_of_fst_type<W: SerializableSemiring>(
+        &self,
+        header: &FstHeader,
+        data: &[u8],
+    ) -> Result<()> {
+        match header.fst_type() {
+            "vector" => self.print_info::<W, VectorFst<_>>(data),
+            "const" => self.print_info::<W, ConstFst<_>>(data),
+            "compact_string" => self.print_info::<W, CompactFst<_, StringCompactor>>(data),
+            "compact_weighted_string" => {
+                self.print_info::<W, CompactFst<_, WeightedStringCompactor>>(data)
+            }
+            "compact_acceptor" => self.print_info::<W, CompactFst<_, AcceptorCompactor>>(data),
+            "compact_unweighted" => self.print_info::<W, CompactFst<_, UnweightedCompactor>>(data),
+            "compact_unweighted_acceptor" => {
+                self.print_info::<W, CompactFst<_, UnweightedAcceptorCompactor>>(data)
+            }
+            fst_type => bail!(
+                "Unsupported fst type {:?} with arc type {:?}",
+                fst_type,
+                header.tr_type()
+            ),
+        }
+    }
+
+    fn print_info<W, F>(&self, data: &[u8]) -> Result<()>
+    where
+        W: SerializableSemiring,
+        F: SerializableFst<W> + ExpandedFst<W>,
+    {
+        let fst = F::load(data)?;
+        let info = fst_info(&fst)?;
+        println!("{:<50}{}", "fst type", F::fst_type());
+        println!("{:<50}{}", "weight type", W::weight_type());
+        print!("{}", info);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 pub mod compose;
 pub mod connect;
 pub mod determinize;
+pub mod info;
 pub mod invert;
 pub mod map;
 pub mod minimize;
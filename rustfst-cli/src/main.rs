@@ -8,6 +8,7 @@ use crate::binary_fst_algorithm::BinaryFstAlgorithm;
 use crate::cmds::compose::ComposeAlgorithm;
 use crate::cmds::connect::ConnectAlgorithm;
 use crate::cmds::determinize::DeterminizeAlgorithm;
+use crate::cmds::info::InfoCommand;
 use crate::cmds::invert::InvertAlgorithm;
 use crate::cmds::map::MapAlgorithm;
 use crate::cmds::minimize::MinimizeAlgorithm;
@@ -173,6 +174,18 @@ fn main() {
         );
     app = app.subcommand(two_in_one_out_options(compose_cmd));
 
+    // Info
+    let info_cmd = SubCommand::with_name("info")
+        .about("Prints out information about an FST.")
+        .version("1.0")
+        .author("Alexandre Caulier <alexandre.caulier@protonmail.com>")
+        .arg(
+            Arg::with_name("in.fst")
+                .help("Path to input fst file.")
+                .required(true),
+        );
+    app = app.subcommand(info_cmd);
+
     let matches = app.get_matches();
 
     let env = env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "debug");
@@ -275,6 +288,7 @@ fn handle(matches: clap::ArgMatches) -> Result<()> {
             m.value_of("compose_type").unwrap(),
         )
         .run_cli_or_bench(m),
+        ("info", Some(m)) => InfoCommand::new(m.value_of("in.fst").unwrap()).run(),
         (s, _) => Err(format_err!("Unknown subcommand {}.", s)),
     }
 }
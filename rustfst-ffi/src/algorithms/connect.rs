@@ -1,20 +1,21 @@
 use anyhow::anyhow;
 
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, CFst};
 use crate::{get_mut, wrap, RUSTFST_FFI_RESULT};
 
 use rustfst::algorithms::connect;
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 #[no_mangle]
 pub extern "C" fn fst_connect(ptr: *mut CFst) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, ptr);
-        let vec_fst: &mut VectorFst<TropicalWeight> = fst
-            .downcast_mut()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
-        connect(vec_fst)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &mut VectorFst<W> = fst
+                .downcast_mut()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+            connect(vec_fst)?;
+        });
         Ok(())
     })
 }
@@ -1,15 +1,14 @@
 use anyhow::{anyhow, Result};
 
 use super::EnumConversionError;
-use crate::fst::CFst;
-use crate::{get, wrap, RUSTFST_FFI_RESULT};
+use crate::fst::{with_weight_type, CFst};
+use crate::{get, wrap, CLabel, RUSTFST_FFI_RESULT};
 
 use ffi_convert::*;
 use rustfst::algorithms::determinize::{
     determinize, determinize_with_config, DeterminizeConfig, DeterminizeType,
 };
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 #[derive(RawPointerConverter)]
 pub struct CDeterminizeType(usize);
@@ -42,23 +41,64 @@ impl CReprOf<DeterminizeType> for CDeterminizeType {
     }
 }
 
-#[derive(AsRust, CReprOf, CDrop, RawPointerConverter)]
-#[target_type(DeterminizeConfig)]
+#[derive(RawPointerConverter)]
 pub struct CDeterminizeConfig {
     delta: f32,
     det_type: CDeterminizeType,
+    max_states: Option<usize>,
+    max_trs: Option<usize>,
+    subsequential_label: CLabel,
+    increment_subsequential_label: bool,
 }
 
+impl CDrop for CDeterminizeConfig {
+    fn do_drop(&mut self) -> Result<(), CDropError> {
+        Ok(())
+    }
+}
+
+impl AsRust<DeterminizeConfig> for CDeterminizeConfig {
+    fn as_rust(&self) -> Result<DeterminizeConfig, AsRustError> {
+        Ok(DeterminizeConfig::new(self.delta, self.det_type.as_rust()?)
+            .with_max_states(self.max_states)
+            .with_max_trs(self.max_trs)
+            .with_subsequential_label(self.subsequential_label.as_rust()?)
+            .with_increment_subsequential_label(self.increment_subsequential_label))
+    }
+}
+
+impl CReprOf<DeterminizeConfig> for CDeterminizeConfig {
+    fn c_repr_of(value: DeterminizeConfig) -> Result<CDeterminizeConfig, CReprOfError> {
+        Ok(CDeterminizeConfig {
+            delta: value.delta,
+            det_type: CDeterminizeType::c_repr_of(value.det_type)?,
+            max_states: value.max_states,
+            max_trs: value.max_trs,
+            subsequential_label: CReprOf::c_repr_of(value.subsequential_label)?,
+            increment_subsequential_label: value.increment_subsequential_label,
+        })
+    }
+}
+
+/// Creates a `DeterminizeConfig`. `max_states` and `max_trs` may be null when there is no limit.
 #[no_mangle]
 pub extern "C" fn fst_determinize_config_new(
     delta: libc::c_float,
     det_type: libc::size_t,
+    max_states: *const libc::size_t,
+    max_trs: *const libc::size_t,
+    subsequential_label: libc::size_t,
+    increment_subsequential_label: bool,
     config: *mut *const CDeterminizeConfig,
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let determinize_config = CDeterminizeConfig {
             delta: delta as f32,
             det_type: CDeterminizeType(det_type as usize),
+            max_states: unsafe { max_states.as_ref() }.map(|v| *v as usize),
+            max_trs: unsafe { max_trs.as_ref() }.map(|v| *v as usize),
+            subsequential_label: subsequential_label as CLabel,
+            increment_subsequential_label,
         };
         unsafe { *config = determinize_config.into_raw_pointer() };
         Ok(())
@@ -72,12 +112,14 @@ pub extern "C" fn fst_determinize(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, ptr);
-        let vec_fst: &VectorFst<TropicalWeight> = fst
-            .downcast_ref()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
-        let fst: VectorFst<TropicalWeight> = determinize(vec_fst)?;
-        let fst_ptr = CFst(Box::new(fst)).into_raw_pointer();
-        unsafe { *det_fst = fst_ptr };
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &VectorFst<W> = fst
+                .downcast_ref()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+            let fst: VectorFst<W> = determinize(vec_fst)?;
+            let fst_ptr = CFst(Box::new(fst)).into_raw_pointer();
+            unsafe { *det_fst = fst_ptr };
+        });
         Ok(())
     })
 }
@@ -90,17 +132,19 @@ pub extern "C" fn fst_determinize_with_config(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, ptr);
-        let vec_fst: &VectorFst<TropicalWeight> = fst
-            .downcast_ref()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &VectorFst<W> = fst
+                .downcast_ref()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
 
-        let det_config = unsafe {
-            <CDeterminizeConfig as ffi_convert::RawBorrow<CDeterminizeConfig>>::raw_borrow(config)?
-        };
-        let fst: VectorFst<TropicalWeight> =
-            determinize_with_config(vec_fst, det_config.as_rust()?)?;
-        let fst_ptr = CFst(Box::new(fst)).into_raw_pointer();
-        unsafe { *det_fst = fst_ptr };
+            let det_config = unsafe {
+                <CDeterminizeConfig as ffi_convert::RawBorrow<CDeterminizeConfig>>::raw_borrow(config)?
+            };
+            let fst: VectorFst<W> =
+                determinize_with_config(vec_fst, det_config.as_rust()?)?;
+            let fst_ptr = CFst(Box::new(fst)).into_raw_pointer();
+            unsafe { *det_fst = fst_ptr };
+        });
         Ok(())
     })
 }
@@ -1,11 +1,10 @@
 use anyhow::anyhow;
 
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, CFst};
 use crate::{get, wrap, RUSTFST_FFI_RESULT};
 
 use rustfst::algorithms::isomorphic;
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 #[no_mangle]
 pub fn fst_isomorphic(
@@ -16,14 +15,16 @@ pub fn fst_isomorphic(
     wrap(|| {
         let fst = get!(CFst, fst);
         let other_fst = get!(CFst, other_fst);
-        let vec_fst: &VectorFst<TropicalWeight> = fst
-            .downcast_ref()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
-        let other_vec_fst: &VectorFst<TropicalWeight> = other_fst
-            .downcast_ref()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
-        let res = isomorphic(vec_fst, other_vec_fst)?;
-        unsafe { *is_isomorphic = res as usize }
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &VectorFst<W> = fst
+                .downcast_ref()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+            let other_vec_fst: &VectorFst<W> = other_fst
+                .downcast_ref()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+            let res = isomorphic(vec_fst, other_vec_fst)?;
+            unsafe { *is_isomorphic = res as usize }
+        });
         Ok(())
     })
 }
@@ -14,6 +14,7 @@ pub mod top_sort;
 pub mod tr_sort;
 pub mod tr_unique;
 pub mod union;
+pub mod weight_convert;
 
 #[derive(Debug)]
 pub struct EnumConversionError {}
@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, CFst};
 use crate::{get, get_mut, wrap, RUSTFST_FFI_RESULT};
 
 use ffi_convert::RawPointerConverter;
@@ -14,10 +14,12 @@ use rustfst::semirings::TropicalWeight;
 pub extern "C" fn fst_optimize(ptr: *mut CFst) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, ptr);
-        let vec_fst: &mut VectorFst<TropicalWeight> = fst
-            .downcast_mut()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
-        optimize(vec_fst)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &mut VectorFst<W> = fst
+                .downcast_mut()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+            optimize(vec_fst)?;
+        });
         Ok(())
     })
 }
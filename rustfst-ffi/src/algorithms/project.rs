@@ -1,13 +1,12 @@
 use anyhow::{anyhow, Result};
 
 use super::EnumConversionError;
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, CFst};
 use crate::{get_mut, wrap, RUSTFST_FFI_RESULT};
 
 use ffi_convert::*;
 use rustfst::algorithms::{project, ProjectType};
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 #[derive(RawPointerConverter)]
 pub struct CProjectType(usize);
@@ -54,14 +53,16 @@ pub extern "C" fn fst_project_type_new(
 pub extern "C" fn fst_project(ptr: *mut CFst, config: *const CProjectType) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, ptr);
-        let vec_fst: &mut VectorFst<TropicalWeight> = fst
-            .downcast_mut()
-            .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst: &mut VectorFst<W> = fst
+                .downcast_mut()
+                .ok_or_else(|| anyhow!("Could not downcast to vector FST"))?;
 
-        let project_type =
-            unsafe { <CProjectType as ffi_convert::RawBorrow<CProjectType>>::raw_borrow(config)? };
+            let project_type =
+                unsafe { <CProjectType as ffi_convert::RawBorrow<CProjectType>>::raw_borrow(config)? };
 
-        project(vec_fst, project_type.as_rust()?);
+            project(vec_fst, project_type.as_rust()?);
+        });
         Ok(())
     })
 }
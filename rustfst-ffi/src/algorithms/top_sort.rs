@@ -1,19 +1,20 @@
 use anyhow::anyhow;
 
 use crate::fst::as_mut_fst;
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, CFst};
 use crate::{get_mut, wrap, RUSTFST_FFI_RESULT};
 
 use rustfst::algorithms::top_sort;
 use rustfst::fst_impls::VectorFst;
-use rustfst::semirings::TropicalWeight;
 
 #[no_mangle]
 pub extern "C" fn fst_top_sort(ptr: *mut CFst) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, ptr);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        top_sort(vec_fst)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            top_sort(vec_fst)?;
+        });
         Ok(())
     })
 }
@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+
+use crate::fst::{as_fst, with_weight_type, BindableFst, CFst};
+use crate::{get, wrap, RUSTFST_FFI_RESULT};
+
+use ffi_convert::*;
+use rustfst::algorithms::{weight_convert, FinalTr, MapFinalAction, WeightConverter};
+use rustfst::fst_impls::VectorFst;
+use rustfst::fst_properties::FstProperties;
+use rustfst::semirings::{ProbabilityWeight, SerializableSemiring};
+use rustfst::{Semiring, Tr};
+use std::ffi::CStr;
+
+/// Converts the float value of the weights with `convert`. The values of the tropical and
+/// log semirings are negative log probabilities whereas the ones of the probability semiring
+/// are probabilities.
+struct ValueWeightConverter {
+    convert: fn(f32) -> f32,
+}
+
+impl ValueWeightConverter {
+    fn new(weight_type_in: &str, weight_type_out: &str) -> Self {
+        let probability = ProbabilityWeight::weight_type();
+        let convert: fn(f32) -> f32 = match (
+            weight_type_in == probability,
+            weight_type_out == probability,
+        ) {
+            (false, true) => |w| (-w).exp(),
+            (true, false) => |p| -p.ln(),
+            _ => |w| w,
+        };
+        Self { convert }
+    }
+}
+
+impl<SI, SO> WeightConverter<SI, SO> for ValueWeightConverter
+where
+    SI: Semiring<Type = f32>,
+    SO: Semiring<Type = f32>,
+{
+    fn tr_map(&mut self, tr: &Tr<SI>) -> Result<Tr<SO>> {
+        Ok(Tr::new(
+            tr.ilabel,
+            tr.olabel,
+            SO::new((self.convert)(*tr.weight.value())),
+            tr.nextstate,
+        ))
+    }
+
+    fn final_tr_map(&mut self, final_tr: &FinalTr<SI>) -> Result<FinalTr<SO>> {
+        Ok(FinalTr {
+            ilabel: final_tr.ilabel,
+            olabel: final_tr.olabel,
+            weight: SO::new((self.convert)(*final_tr.weight.value())),
+        })
+    }
+
+    fn final_action(&self) -> MapFinalAction {
+        MapFinalAction::MapNoSuperfinal
+    }
+
+    fn properties(&self, inprops: FstProperties) -> FstProperties {
+        inprops
+    }
+}
+
+/// Converts a VectorFst to the semiring whose weight type is passed as parameter.
+/// Conversions from and to the probability semiring exponentiate or take the negative
+/// logarithm of the weights, the other ones keep the weight values.
+#[no_mangle]
+pub extern "C" fn fst_weight_convert(
+    ptr: *const CFst,
+    weight_type: *const libc::c_char,
+    res_ptr: *mut *const CFst,
+) -> RUSTFST_FFI_RESULT {
+    wrap(|| {
+        let fst = get!(CFst, ptr);
+        let weight_type_out: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let mut converter = ValueWeightConverter::new(&fst.fst_weight_type(), &weight_type_out);
+        let res_fst: Box<dyn BindableFst> = with_weight_type!(fst.fst_weight_type(), W1 => {
+            let vec_fst = as_fst!(VectorFst<W1>, fst);
+            with_weight_type!(weight_type_out.clone(), W2 => {
+                let res: VectorFst<W2> = weight_convert(vec_fst, &mut converter)?;
+                Box::new(res)
+            })
+        });
+        unsafe { *res_ptr = CFst(res_fst).into_raw_pointer() };
+        Ok(())
+    })
+}
@@ -1,5 +1,5 @@
 use super::*;
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use rustfst::DrawingConfig;
 use std::ffi::CString;
 
@@ -7,7 +7,11 @@ use std::ffi::CString;
 pub fn const_fst_from_path(ptr: *mut *const CFst, path: *const libc::c_char) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let path = unsafe { CStr::from_ptr(path) }.as_rust()?;
-        let fst = Box::new(ConstFst::<TropicalWeight>::read(&path)?);
+        let data = std::fs::read(&path)
+            .with_context(|| format!("Can't open ConstFst binary file : {:?}", path))?;
+        let fst: Box<dyn BindableFst> = with_header_weight_type!(&data, W => {
+            Box::new(ConstFst::<W>::load(&data)?)
+        });
         let raw_pointer = CFst(fst).into_raw_pointer();
         unsafe { *ptr = raw_pointer };
         Ok(())
@@ -19,8 +23,10 @@ pub fn const_fst_write_file(fst: *const CFst, path: *const libc::c_char) -> RUST
     wrap(|| {
         let fst = get!(CFst, fst);
         let path = unsafe { CStr::from_ptr(path) }.as_rust()?;
-        let const_fst = as_fst!(ConstFst<TropicalWeight>, fst);
-        const_fst.write(&path)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let const_fst = as_fst!(ConstFst<W>, fst);
+            const_fst.write(&path)?;
+        });
         Ok(())
     })
 }
@@ -34,9 +40,12 @@ pub fn const_fst_equals(
     wrap(|| {
         let fst = get!(CFst, fst);
         let other_fst = get!(CFst, other_fst);
-        let const_fst = as_fst!(ConstFst<TropicalWeight>, fst);
-        let other_const_fst = as_fst!(ConstFst<TropicalWeight>, other_fst);
-        let res = const_fst.eq(other_const_fst);
+        let res = fst.fst_weight_type() == other_fst.fst_weight_type()
+            && with_weight_type!(fst.fst_weight_type(), W => {
+                let const_fst = as_fst!(ConstFst<W>, fst);
+                let other_const_fst = as_fst!(ConstFst<W>, other_fst);
+                const_fst.eq(other_const_fst)
+            });
         unsafe { *is_equal = res as usize }
         Ok(())
     })
@@ -49,9 +58,10 @@ pub extern "C" fn const_fst_copy(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst_ptr);
-        let const_fst = as_fst!(ConstFst<TropicalWeight>, fst);
-        let clone = const_fst.clone();
-        unsafe { *clone_ptr = CFst(Box::new(clone)).into_raw_pointer() };
+        let clone: Box<dyn BindableFst> = with_weight_type!(fst.fst_weight_type(), W => {
+            Box::new(as_fst!(ConstFst<W>, fst).clone())
+        });
+        unsafe { *clone_ptr = CFst(clone).into_raw_pointer() };
         Ok(())
     })
 }
@@ -77,16 +87,15 @@ pub fn const_fst_draw(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst_ptr);
-        let const_fst = as_mut_fst!(ConstFst<TropicalWeight>, fst);
 
         if !isyms.is_null() {
             let isymt = get!(CSymbolTable, isyms);
-            const_fst.fst_set_input_symbols(isymt.clone());
+            fst.fst_set_input_symbols(isymt.clone());
         }
 
         if !osyms.is_null() {
             let osymt = get!(CSymbolTable, osyms);
-            const_fst.fst_set_output_symbols(osymt.clone());
+            fst.fst_set_output_symbols(osymt.clone());
         }
 
         let drawing_config = DrawingConfig {
@@ -106,7 +115,11 @@ pub fn const_fst_draw(
             print_weight: print_weight > 0,
         };
 
-        const_fst.draw(unsafe { CStr::from_ptr(fname).as_rust()? }, &drawing_config)?;
+        let fname: String = unsafe { CStr::from_ptr(fname).as_rust()? };
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let const_fst = as_fst!(ConstFst<W>, fst);
+            const_fst.draw(fname, &drawing_config)?;
+        });
 
         Ok(())
     })
@@ -119,8 +132,9 @@ pub extern "C" fn const_fst_display(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst_ptr);
-        let vec_fst = as_fst!(ConstFst<TropicalWeight>, fst);
-        let res = format!("{}", vec_fst);
+        let res = with_weight_type!(fst.fst_weight_type(), W => {
+            format!("{}", as_fst!(ConstFst<W>, fst))
+        });
         unsafe { *s = CString::c_repr_of(res)?.into_raw_pointer() as *const libc::c_char };
         Ok(())
     })
@@ -5,7 +5,7 @@ pub mod vector_fst;
 
 use crate::symbol_table::CSymbolTable;
 use crate::tr::CTr;
-use crate::trs::CTrs;
+use crate::trs::{BindableTrs, CTrs};
 use crate::{get, get_mut, wrap, CStateId, RUSTFST_FFI_RESULT};
 
 use anyhow::Result;
@@ -13,20 +13,28 @@ use downcast_rs::Downcast;
 use ffi_convert::*;
 use rustfst::algorithms::concat::ConcatFst;
 use rustfst::fst_impls::{ConstFst, VectorFst};
-use rustfst::fst_traits::{Fst, MutableFst, SerializableFst};
-use rustfst::semirings::TropicalWeight;
+use rustfst::fst_traits::{CoreFst, Fst, MutableFst, SerializableFst};
+use rustfst::semirings::{SerializableSemiring, TropicalWeight};
 use rustfst::Semiring;
-use rustfst::{StateId, SymbolTable, Trs, TrsVec};
+use rustfst::{StateId, SymbolTable, Trs};
 use std::ffi::CStr;
 use std::sync::Arc;
 
+/// Weights that can be used in the binded FSTs : their value is a float which is the
+/// representation of the weight on the C side.
+pub trait BindableWeight: SerializableSemiring<Type = f32> + 'static {}
+
+impl<W: SerializableSemiring<Type = f32> + 'static> BindableWeight for W {}
+
 /// This trait is an alias for the FST trait.
 /// It makes the FST trait Boxable and downcastable to one of the supported C Fst structs.
 /// This trait allows to share Fst trait methods accross FST types by sharing a common input type in the binded methods.
 /// This generic Fst type can then be downcast to the appropriate Fst type (VectorFst, ConstFst, ..) in order to get access to specific methods (add_tr, ..).
+/// The weights are exposed through their float value, `fst_weight_type` allows to know which semiring they belong to.
 pub trait BindableFst: Downcast {
+    fn fst_weight_type(&self) -> String;
     fn fst_start(&self) -> Option<StateId>;
-    fn fst_final_weight(&self, state: StateId) -> Result<Option<TropicalWeight>>;
+    fn fst_final_weight(&self, state: StateId) -> Result<Option<f32>>;
     fn fst_num_trs(&self, s: StateId) -> Result<usize>;
 
     #[inline]
@@ -40,7 +48,7 @@ pub trait BindableFst: Downcast {
         Some(state_id) == self.fst_start()
     }
 
-    fn fst_get_trs(&self, state_id: StateId) -> Result<TrsVec<TropicalWeight>>;
+    fn fst_get_trs(&self, state_id: StateId) -> Result<Box<dyn BindableTrs>>;
     fn fst_input_symbols(&self) -> Option<Arc<SymbolTable>>;
     fn fst_output_symbols(&self) -> Option<Arc<SymbolTable>>;
     fn fst_set_input_symbols(&mut self, symt: Arc<SymbolTable>);
@@ -51,50 +59,93 @@ pub trait BindableFst: Downcast {
 
 downcast_rs::impl_downcast!(BindableFst);
 
-impl<F: Fst<TropicalWeight> + 'static> BindableFst for F {
-    fn fst_start(&self) -> Option<StateId> {
-        self.start()
-    }
-    fn fst_final_weight(&self, state: StateId) -> Result<Option<TropicalWeight>> {
-        self.final_weight(state)
-    }
-    fn fst_num_trs(&self, s: StateId) -> Result<usize> {
-        self.num_trs(s)
-    }
-    fn fst_get_trs(&self, state_id: StateId) -> Result<TrsVec<TropicalWeight>> {
-        self.get_trs(state_id).map(|it| it.to_trs_vec())
-    }
-    fn fst_input_symbols(&self) -> Option<Arc<SymbolTable>> {
-        self.input_symbols().cloned()
-    }
-    fn fst_output_symbols(&self) -> Option<Arc<SymbolTable>> {
-        self.output_symbols().cloned()
-    }
-    fn fst_set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
-        self.set_input_symbols(symt)
-    }
-    fn fst_set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
-        self.set_output_symbols(symt)
-    }
-    fn fst_take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
-        self.take_input_symbols()
-    }
-    fn fst_take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
-        self.take_output_symbols()
-    }
+macro_rules! impl_bindable_fst {
+    ($fst:ty) => {
+        impl<W: BindableWeight> BindableFst for $fst {
+            fn fst_weight_type(&self) -> String {
+                W::weight_type()
+            }
+            fn fst_start(&self) -> Option<StateId> {
+                self.start()
+            }
+            fn fst_final_weight(&self, state: StateId) -> Result<Option<f32>> {
+                Ok(self.final_weight(state)?.map(|w| w.take_value()))
+            }
+            fn fst_num_trs(&self, s: StateId) -> Result<usize> {
+                self.num_trs(s)
+            }
+            fn fst_get_trs(&self, state_id: StateId) -> Result<Box<dyn BindableTrs>> {
+                Ok(Box::new(self.get_trs(state_id)?.to_trs_vec()))
+            }
+            fn fst_input_symbols(&self) -> Option<Arc<SymbolTable>> {
+                self.input_symbols().cloned()
+            }
+            fn fst_output_symbols(&self) -> Option<Arc<SymbolTable>> {
+                self.output_symbols().cloned()
+            }
+            fn fst_set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+                self.set_input_symbols(symt)
+            }
+            fn fst_set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+                self.set_output_symbols(symt)
+            }
+            fn fst_take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+                self.take_input_symbols()
+            }
+            fn fst_take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+                self.take_output_symbols()
+            }
+        }
+    };
 }
 
-#[derive(RawPointerConverter)]
-pub struct CFst(pub(crate) Box<dyn BindableFst>);
+impl_bindable_fst!(VectorFst<W>);
+impl_bindable_fst!(ConstFst<W>);
+impl_bindable_fst!(ConcatFst<W, VectorFst<W>>);
 
 #[derive(RawPointerConverter)]
-pub struct CVecFst(pub(crate) Box<VectorFst<TropicalWeight>>);
+pub struct CFst(pub(crate) Box<dyn BindableFst>);
 
-#[derive(RawPointerConverter)]
-pub struct CConstFst(pub(crate) Box<ConstFst<TropicalWeight>>);
+/// Runs `$body` with `$W` being an alias to the semiring whose weight type is `$weight_type`.
+/// Supported semirings are `TropicalWeight`, `LogWeight` and `ProbabilityWeight`.
+macro_rules! with_weight_type {
+    ($weight_type:expr, $W:ident => $body:expr) => {{
+        let weight_type: String = $weight_type;
+        if weight_type == <rustfst::semirings::TropicalWeight as rustfst::semirings::SerializableSemiring>::weight_type() {
+            type $W = rustfst::semirings::TropicalWeight;
+            $body
+        } else if weight_type == <rustfst::semirings::LogWeight as rustfst::semirings::SerializableSemiring>::weight_type() {
+            type $W = rustfst::semirings::LogWeight;
+            $body
+        } else if weight_type == <rustfst::semirings::ProbabilityWeight as rustfst::semirings::SerializableSemiring>::weight_type() {
+            type $W = rustfst::semirings::ProbabilityWeight;
+            $body
+        } else {
+            return Err(anyhow::format_err!("Unsupported weight type : {}", weight_type));
+        }
+    }};
+}
 
-#[derive(RawPointerConverter)]
-pub struct CConcatFst(pub(crate) Box<ConcatFst<TropicalWeight, VectorFst<TropicalWeight>>>);
+/// Runs `$body` with `$W` being an alias to the semiring of the binary FST `$data`, found from
+/// the tr type stored in its header. Supported semirings are the ones of `with_weight_type`.
+macro_rules! with_header_weight_type {
+    ($data:expr, $W:ident => $body:expr) => {{
+        let header = rustfst::FstHeader::load($data)?;
+        let tr_type = header.tr_type();
+        if tr_type == rustfst::Tr::<rustfst::semirings::TropicalWeight>::tr_type() {
+            type $W = rustfst::semirings::TropicalWeight;
+            $body
+        } else if tr_type == rustfst::Tr::<rustfst::semirings::LogWeight>::tr_type() {
+            type $W = rustfst::semirings::LogWeight;
+            $body
+        } else if tr_type == rustfst::Tr::<rustfst::semirings::ProbabilityWeight>::tr_type() {
+            type $W = rustfst::semirings::ProbabilityWeight;
+            $body
+        } else {
+            return Err(anyhow::format_err!("Unsupported arc type : {}", tr_type));
+        }
+    }};
+}
 
 macro_rules! as_fst {
     ($typ:ty,$fst:ident) => {{
@@ -112,6 +163,8 @@ macro_rules! as_mut_fst {
 
 pub(crate) use as_fst;
 pub(crate) use as_mut_fst;
+pub(crate) use with_header_weight_type;
+pub(crate) use with_weight_type;
 //macro_rules! as_const_fst {
 //    ($typ:ty,$opaque:ident) => {{
 //        &unsafe { <$typ as ffi_convert::RawBorrow<$typ>>::raw_borrow($opaque) }?.0
@@ -121,6 +174,23 @@ pub(crate) use as_mut_fst;
 /// Core FST methods
 /// As defined in fst_traits
 
+/// Retrieves the type of the weights of the wFST : `tropical`, `log` or `probability`.
+#[no_mangle]
+pub fn fst_weight_type(
+    fst: *const CFst,
+    weight_type: *mut *const libc::c_char,
+) -> RUSTFST_FFI_RESULT {
+    wrap(|| {
+        let fst = get!(CFst, fst);
+        let res = fst.fst_weight_type();
+        unsafe {
+            *weight_type =
+                std::ffi::CString::c_repr_of(res)?.into_raw_pointer() as *const libc::c_char
+        };
+        Ok(())
+    })
+}
+
 /// Returns the ID of the start state of the wFST if it exists else none
 #[no_mangle]
 pub fn fst_start(fst: *const CFst, mut state: *mut CStateId) -> RUSTFST_FFI_RESULT {
@@ -143,7 +213,7 @@ pub fn fst_final_weight(
     wrap(|| {
         let fst = get!(CFst, fst);
         fst.fst_final_weight(state_id)?
-            .map(|it| unsafe { *final_weight = *it.value() })
+            .map(|it| unsafe { *final_weight = it })
             .unwrap_or_else(|| final_weight = std::ptr::null_mut());
         Ok(())
     })
@@ -318,6 +388,34 @@ pub extern "C" fn fst_weight_zero(weight_zero: *mut libc::c_float) -> RUSTFST_FF
     })
 }
 
+/// Computes One() in the semiring whose weight type is passed as parameter.
+#[no_mangle]
+pub extern "C" fn fst_weight_type_one(
+    weight_type: *const libc::c_char,
+    weight_one: *mut libc::c_float,
+) -> RUSTFST_FFI_RESULT {
+    wrap(|| {
+        let weight_type: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let weight = with_weight_type!(weight_type, W => W::one().take_value());
+        unsafe { *weight_one = weight };
+        Ok(())
+    })
+}
+
+/// Computes Zero() in the semiring whose weight type is passed as parameter.
+#[no_mangle]
+pub extern "C" fn fst_weight_type_zero(
+    weight_type: *const libc::c_char,
+    weight_zero: *mut libc::c_float,
+) -> RUSTFST_FFI_RESULT {
+    wrap(|| {
+        let weight_type: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let weight = with_weight_type!(weight_type, W => W::zero().take_value());
+        unsafe { *weight_zero = weight };
+        Ok(())
+    })
+}
+
 /// drop impl
 #[no_mangle]
 pub fn fst_destroy(fst_ptr: *mut CFst) -> RUSTFST_FFI_RESULT {
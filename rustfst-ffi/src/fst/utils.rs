@@ -1,9 +1,9 @@
-use crate::fst::CFst;
+use crate::fst::{with_weight_type, BindableFst, CFst};
 use crate::symbol_table::CSymbolTable;
 use crate::{get_mut, wrap, RUSTFST_FFI_RESULT};
 use anyhow::{anyhow, Context, Result};
 use ffi_convert::*;
-use rustfst::prelude::{Label, Semiring, TropicalWeight, VectorFst};
+use rustfst::prelude::{Label, Semiring, VectorFst};
 use rustfst::utils::{acceptor, transducer};
 use std::ffi::CStr;
 
@@ -12,6 +12,7 @@ pub extern "C" fn utils_string_to_acceptor(
     astring: *const libc::c_char,
     symbol_table: *mut CSymbolTable,
     weight: libc::c_float,
+    weight_type: *const libc::c_char,
     fst_ptr: *mut *const CFst,
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
@@ -24,9 +25,12 @@ pub extern "C" fn utils_string_to_acceptor(
                     .with_context(|| anyhow!("Could not retrieve symbol {:?} in symbol table", sym))
             })
             .collect::<Result<Vec<Label>>>()?;
-        let acceptor_fst: VectorFst<TropicalWeight> =
-            acceptor(labels.as_slice(), TropicalWeight::new(weight as f32));
-        unsafe { *fst_ptr = CFst(Box::new(acceptor_fst)).into_raw_pointer() }
+        let weight_type: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let acceptor_fst: Box<dyn BindableFst> = with_weight_type!(weight_type, W => {
+            let fst: VectorFst<W> = acceptor(labels.as_slice(), W::new(weight as f32));
+            Box::new(fst)
+        });
+        unsafe { *fst_ptr = CFst(acceptor_fst).into_raw_pointer() }
         Ok(())
     })
 }
@@ -38,6 +42,7 @@ pub extern "C" fn utils_string_to_transducer(
     isymt: *mut CSymbolTable,
     osymt: *mut CSymbolTable,
     weight: libc::c_float,
+    weight_type: *const libc::c_char,
     fst_ptr: *mut *const CFst,
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
@@ -61,12 +66,13 @@ pub extern "C" fn utils_string_to_transducer(
                     .with_context(|| anyhow!("Could not retrieve symbol {:?} in symbol table", sym))
             })
             .collect::<Result<Vec<Label>>>()?;
-        let transducer_fst: VectorFst<TropicalWeight> = transducer(
-            ilabels.as_slice(),
-            olabels.as_slice(),
-            TropicalWeight::new(weight as f32),
-        );
-        unsafe { *fst_ptr = CFst(Box::new(transducer_fst)).into_raw_pointer() }
+        let weight_type: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let transducer_fst: Box<dyn BindableFst> = with_weight_type!(weight_type, W => {
+            let fst: VectorFst<W> =
+                transducer(ilabels.as_slice(), olabels.as_slice(), W::new(weight as f32));
+            Box::new(fst)
+        });
+        unsafe { *fst_ptr = CFst(transducer_fst).into_raw_pointer() }
         Ok(())
     })
 }
@@ -1,15 +1,31 @@
 use super::*;
 use crate::get_symt;
-use anyhow::{anyhow, format_err};
+use anyhow::{anyhow, format_err, Context};
 use ffi_convert::CArray;
 use rustfst::fst_traits::ExpandedFst;
-use rustfst::DrawingConfig;
+use rustfst::{DrawingConfig, Tr};
 use std::ffi::CString;
 
 #[no_mangle]
 pub fn vec_fst_new(ptr: *mut *const CFst) -> RUSTFST_FFI_RESULT {
     wrap(|| {
-        let fst = Box::new(VectorFst::new());
+        let fst = Box::new(VectorFst::<TropicalWeight>::new());
+        let raw_pointer = CFst(fst).into_raw_pointer();
+        unsafe { *ptr = raw_pointer };
+        Ok(())
+    })
+}
+
+/// Creates an empty VectorFst whose weights belong to the semiring with the given weight type.
+#[no_mangle]
+pub fn vec_fst_new_with_weight_type(
+    weight_type: *const libc::c_char,
+    ptr: *mut *const CFst,
+) -> RUSTFST_FFI_RESULT {
+    wrap(|| {
+        let weight_type: String = unsafe { CStr::from_ptr(weight_type) }.as_rust()?;
+        let fst: Box<dyn BindableFst> =
+            with_weight_type!(weight_type, W => Box::new(VectorFst::<W>::new()));
         let raw_pointer = CFst(fst).into_raw_pointer();
         unsafe { *ptr = raw_pointer };
         Ok(())
@@ -20,8 +36,10 @@ pub fn vec_fst_new(ptr: *mut *const CFst) -> RUSTFST_FFI_RESULT {
 pub fn vec_fst_set_start(fst: *mut CFst, state: CStateId) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let c_fst = get_mut!(CFst, fst);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, c_fst);
-        vec_fst.set_start(state)?;
+        with_weight_type!(c_fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, c_fst);
+            vec_fst.set_start(state)?;
+        });
         Ok(())
     })
 }
@@ -34,8 +52,10 @@ pub fn vec_fst_set_final(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        vec_fst.set_final(state, TropicalWeight::new(weight as f32))?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            vec_fst.set_final(state, W::new(weight as f32))?;
+        });
         Ok(())
     })
 }
@@ -44,8 +64,9 @@ pub fn vec_fst_set_final(
 pub fn vec_fst_add_state(fst: *mut CFst, state: *mut CStateId) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        let res = vec_fst.add_state();
+        let res = with_weight_type!(fst.fst_weight_type(), W => {
+            as_mut_fst!(VectorFst<W>, fst).add_state()
+        });
         unsafe { *state = res }
         Ok(())
     })
@@ -55,8 +76,9 @@ pub fn vec_fst_add_state(fst: *mut CFst, state: *mut CStateId) -> RUSTFST_FFI_RE
 pub fn vec_fst_delete_states(fst: *mut CFst) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        vec_fst.del_all_states();
+        with_weight_type!(fst.fst_weight_type(), W => {
+            as_mut_fst!(VectorFst<W>, fst).del_all_states();
+        });
         Ok(())
     })
 }
@@ -65,9 +87,12 @@ pub fn vec_fst_delete_states(fst: *mut CFst) -> RUSTFST_FFI_RESULT {
 pub fn vec_fst_add_tr(fst: *mut CFst, state: CStateId, tr: *const CTr) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let tr = unsafe { <CTr as ffi_convert::RawBorrow<CTr>>::raw_borrow(tr)? }.as_rust()?;
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        vec_fst.add_tr(state, tr)?;
+        let tr = unsafe { <CTr as ffi_convert::RawBorrow<CTr>>::raw_borrow(tr)? };
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let tr: Tr<W> = tr.as_rust()?;
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            vec_fst.add_tr(state, tr)?;
+        });
         Ok(())
     })
 }
@@ -76,8 +101,10 @@ pub fn vec_fst_add_tr(fst: *mut CFst, state: CStateId, tr: *const CTr) -> RUSTFS
 pub fn vec_fst_del_final_weight(fst: *mut CFst, state: CStateId) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-        vec_fst.delete_final_weight(state)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            vec_fst.delete_final_weight(state)?;
+        });
 
         Ok(())
     })
@@ -87,7 +114,11 @@ pub fn vec_fst_del_final_weight(fst: *mut CFst, state: CStateId) -> RUSTFST_FFI_
 pub fn vec_fst_from_path(ptr: *mut *const CFst, path: *const libc::c_char) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let path = unsafe { CStr::from_ptr(path) }.as_rust()?;
-        let fst = Box::new(VectorFst::<TropicalWeight>::read(&path)?);
+        let data = std::fs::read(&path)
+            .with_context(|| format!("Can't open VectorFst binary file : {:?}", path))?;
+        let fst: Box<dyn BindableFst> = with_header_weight_type!(&data, W => {
+            Box::new(VectorFst::<W>::load(&data)?)
+        });
         let raw_pointer = CFst(fst).into_raw_pointer();
         unsafe { *ptr = raw_pointer };
         Ok(())
@@ -99,8 +130,10 @@ pub fn vec_fst_write_file(fst: *const CFst, path: *const libc::c_char) -> RUSTFS
     wrap(|| {
         let fst = get!(CFst, fst);
         let path = unsafe { CStr::from_ptr(path) }.as_rust()?;
-        let vec_fst = as_fst!(VectorFst<TropicalWeight>, fst);
-        vec_fst.write(&path)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_fst!(VectorFst<W>, fst);
+            vec_fst.write(&path)?;
+        });
         Ok(())
     })
 }
@@ -117,8 +150,6 @@ pub fn vec_fst_relabel_tables(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst);
-        let vec_fst: &mut VectorFst<_> = as_mut_fst!(VectorFst<TropicalWeight>, fst);
-
         let old_isymbols = get_symt(old_isymbols)?;
         let new_isymbols =
             get_symt(new_isymbols)?.ok_or_else(|| format_err!("New isymbols ptr is null"))?;
@@ -129,14 +160,17 @@ pub fn vec_fst_relabel_tables(
         let attach_new_isymbols = attach_new_isymbols > 0;
         let attach_new_osymbols = attach_new_osymbols > 0;
 
-        vec_fst.relabel_tables(
-            old_isymbols,
-            new_isymbols,
-            attach_new_isymbols,
-            old_osymbols,
-            new_osymbols,
-            attach_new_osymbols,
-        )?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            vec_fst.relabel_tables(
+                old_isymbols,
+                new_isymbols,
+                attach_new_isymbols,
+                old_osymbols,
+                new_osymbols,
+                attach_new_osymbols,
+            )?;
+        });
 
         Ok(())
     })
@@ -163,16 +197,15 @@ pub fn vec_fst_draw(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get_mut!(CFst, fst_ptr);
-        let vec_fst = as_mut_fst!(VectorFst<TropicalWeight>, fst);
 
         if !isyms.is_null() {
             let isymt = get!(CSymbolTable, isyms);
-            vec_fst.fst_set_input_symbols(isymt.clone());
+            fst.fst_set_input_symbols(isymt.clone());
         }
 
         if !osyms.is_null() {
             let osymt = get!(CSymbolTable, osyms);
-            vec_fst.fst_set_output_symbols(osymt.clone());
+            fst.fst_set_output_symbols(osymt.clone());
         }
 
         let drawing_config = DrawingConfig {
@@ -192,7 +225,11 @@ pub fn vec_fst_draw(
             print_weight: print_weight > 0,
         };
 
-        vec_fst.draw(unsafe { CStr::from_ptr(fname).as_rust()? }, &drawing_config)?;
+        let fname: String = unsafe { CStr::from_ptr(fname).as_rust()? };
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_fst!(VectorFst<W>, fst);
+            vec_fst.draw(fname, &drawing_config)?;
+        });
 
         Ok(())
     })
@@ -202,8 +239,9 @@ pub fn vec_fst_draw(
 pub fn vec_fst_num_states(fst: *const CFst, num_states: *mut libc::size_t) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst);
-        let vec_fst = as_fst!(VectorFst<TropicalWeight>, fst);
-        let res = vec_fst.num_states();
+        let res = with_weight_type!(fst.fst_weight_type(), W => {
+            as_fst!(VectorFst<W>, fst).num_states()
+        });
         unsafe { *num_states = res };
         Ok(())
     })
@@ -218,9 +256,12 @@ pub fn vec_fst_equals(
     wrap(|| {
         let fst = get!(CFst, fst);
         let other_fst = get!(CFst, other_fst);
-        let vec_fst = as_fst!(VectorFst<TropicalWeight>, fst);
-        let other_vec_fst = as_fst!(VectorFst<TropicalWeight>, other_fst);
-        let res = vec_fst.eq(other_vec_fst);
+        let res = fst.fst_weight_type() == other_fst.fst_weight_type()
+            && with_weight_type!(fst.fst_weight_type(), W => {
+                let vec_fst = as_fst!(VectorFst<W>, fst);
+                let other_vec_fst = as_fst!(VectorFst<W>, other_fst);
+                vec_fst.eq(other_vec_fst)
+            });
         unsafe { *is_equal = res as usize }
         Ok(())
     })
@@ -233,9 +274,10 @@ pub extern "C" fn vec_fst_copy(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst_ptr);
-        let vec_fst = as_fst!(VectorFst<TropicalWeight>, fst);
-        let clone = vec_fst.clone();
-        unsafe { *clone_ptr = CFst(Box::new(clone)).into_raw_pointer() };
+        let clone: Box<dyn BindableFst> = with_weight_type!(fst.fst_weight_type(), W => {
+            Box::new(as_fst!(VectorFst<W>, fst).clone())
+        });
+        unsafe { *clone_ptr = CFst(clone).into_raw_pointer() };
         Ok(())
     })
 }
@@ -247,8 +289,9 @@ pub extern "C" fn vec_fst_display(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst_ptr);
-        let vec_fst = as_fst!(VectorFst<TropicalWeight>, fst);
-        let res = format!("{}", vec_fst);
+        let res = with_weight_type!(fst.fst_weight_type(), W => {
+            format!("{}", as_fst!(VectorFst<W>, fst))
+        });
         unsafe { *s = CString::c_repr_of(res)?.into_raw_pointer() as *const libc::c_char };
         Ok(())
     })
@@ -261,10 +304,11 @@ pub extern "C" fn vec_fst_to_bytes(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst_ptr);
-        let vec_fst: &VectorFst<_> = as_fst!(VectorFst<TropicalWeight>, fst);
-
         let mut bytes = vec![];
-        vec_fst.store(&mut bytes)?;
+        with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_fst!(VectorFst<W>, fst);
+            vec_fst.store(&mut bytes)?;
+        });
 
         let c_bytes = CArray::<u8>::c_repr_of(bytes)?;
         let raw_pointer = c_bytes.into_raw_pointer();
@@ -282,8 +326,10 @@ pub extern "C" fn vec_fst_from_bytes(
     wrap(|| {
         let bytes = unsafe { CArray::raw_borrow(bytes)? };
         let bytes = bytes.as_rust()?;
-        let fst = VectorFst::load(bytes.as_slice())?;
-        let raw_pointer = CFst(Box::new(fst)).into_raw_pointer();
+        let fst: Box<dyn BindableFst> = with_header_weight_type!(bytes.as_slice(), W => {
+            Box::new(VectorFst::<W>::load(bytes.as_slice())?)
+        });
+        let raw_pointer = CFst(fst).into_raw_pointer();
         unsafe { *ptr = raw_pointer };
         Ok(())
     })
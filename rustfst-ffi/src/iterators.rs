@@ -1,36 +1,34 @@
-use crate::fst::{CFst, CVecFst};
+use crate::fst::{as_fst, as_mut_fst, with_weight_type, BindableWeight, CFst};
 use crate::tr::CTr;
+use crate::trs::BindableTrs;
 use crate::{get, get_mut, wrap, CStateId, RUSTFST_FFI_RESULT};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use ffi_convert::*;
 use rustfst::fst_traits::MutableFst;
-use rustfst::prelude::{StateIterator, Tr, TropicalWeight, TrsVec};
+use rustfst::prelude::{StateIterator, Tr, VectorFst};
 use rustfst::trs_iter_mut::TrsIterMut;
 use std::iter::Peekable;
 use std::ops::Range;
 
 #[derive(Debug)]
 pub struct TrsIterator {
-    trs: TrsVec<TropicalWeight>,
+    trs: Box<dyn BindableTrs>,
     index: usize,
 }
 
 impl TrsIterator {
     fn done(&self) -> bool {
-        self.trs.len() == self.index
+        self.trs.trs_len() == self.index
     }
 
     fn reset(&mut self) {
         self.index = 0
     }
-}
 
-impl Iterator for TrsIterator {
-    type Item = Tr<TropicalWeight>;
-    fn next(&mut self) -> Option<Self::Item> {
-        let item = self.trs.get(self.index).cloned();
+    fn next_tr(&mut self) -> Result<Option<CTr>> {
+        let item = self.trs.trs_get(self.index)?;
         self.index += 1;
-        item
+        Ok(item)
     }
 }
 
@@ -68,9 +66,9 @@ pub extern "C" fn trs_iterator_next(
     wrap(|| {
         let trs_iter = get_mut!(CTrsIterator, iter_ptr);
         trs_iter
-            .next()
+            .next_tr()?
             .map(|tr| {
-                let ctr = Box::into_raw(Box::new(CTr::c_repr_of(tr)?));
+                let ctr = Box::into_raw(Box::new(tr));
                 unsafe { *tr_ptr = ctr };
                 Ok(())
             })
@@ -119,8 +117,33 @@ pub extern "C" fn trs_iterator_destroy(iter_ptr: *mut CTrsIterator) -> RUSTFST_F
     })
 }
 
+/// Mutable access to the trs of a state, whatever the semiring of the FST is.
+trait BindableTrsIterMut {
+    fn len(&self) -> usize;
+    fn get_tr(&self, idx: usize) -> Result<Option<CTr>>;
+    fn set_tr(&mut self, idx: usize, tr: &CTr) -> Result<()>;
+}
+
+impl<'a, W: BindableWeight> BindableTrsIterMut for TrsIterMut<'a, W> {
+    fn len(&self) -> usize {
+        TrsIterMut::len(self)
+    }
+
+    fn get_tr(&self, idx: usize) -> Result<Option<CTr>> {
+        match self.get(idx) {
+            Some(tr) => Ok(Some(CTr::c_repr_of(tr.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn set_tr(&mut self, idx: usize, tr: &CTr) -> Result<()> {
+        let tr: Tr<W> = tr.as_rust()?;
+        TrsIterMut::set_tr(self, idx, tr)
+    }
+}
+
 pub struct MutTrsIterator<'a> {
-    trs: TrsIterMut<'a, TropicalWeight>,
+    trs: Box<dyn BindableTrsIterMut + 'a>,
     index: usize,
 }
 
@@ -133,11 +156,11 @@ impl<'a> MutTrsIterator<'a> {
         self.index += 1
     }
 
-    pub fn value(&self) -> Option<Tr<TropicalWeight>> {
-        self.trs.get(self.index).cloned()
+    pub fn value(&self) -> Result<Option<CTr>> {
+        self.trs.get_tr(self.index)
     }
 
-    pub fn set_value(&mut self, tr: Tr<TropicalWeight>) -> Result<()> {
+    pub fn set_value(&mut self, tr: &CTr) -> Result<()> {
         self.trs.set_tr(self.index, tr)
     }
 
@@ -179,22 +202,27 @@ impl<'a> RawPointerConverter<CMutTrsIterator<'a>> for CMutTrsIterator<'a> {
 
 #[no_mangle]
 pub extern "C" fn mut_trs_iterator_new(
-    fst_ptr: *mut CVecFst,
+    fst_ptr: *mut CFst,
     state_id: CStateId,
     mut iter_ptr: *mut *const CMutTrsIterator,
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
-        let fst = get_mut!(CVecFst, fst_ptr);
-        fst.tr_iter_mut(state_id)
-            .map(|trs| {
-                let raw_ptr = {
-                    let trs_iterator = MutTrsIterator { trs, index: 0 };
-                    CMutTrsIterator(trs_iterator).into_raw_pointer()
-                };
-
-                unsafe { *iter_ptr = raw_ptr };
-            })
-            .unwrap_or_else(|_| iter_ptr = std::ptr::null_mut());
+        let fst = get_mut!(CFst, fst_ptr);
+        let trs: Result<Box<dyn BindableTrsIterMut>> = with_weight_type!(fst.fst_weight_type(), W => {
+            let vec_fst = as_mut_fst!(VectorFst<W>, fst);
+            vec_fst
+                .tr_iter_mut(state_id)
+                .map(|trs| Box::new(trs) as Box<dyn BindableTrsIterMut>)
+        });
+        trs.map(|trs| {
+            let raw_ptr = {
+                let trs_iterator = MutTrsIterator { trs, index: 0 };
+                CMutTrsIterator(trs_iterator).into_raw_pointer()
+            };
+
+            unsafe { *iter_ptr = raw_ptr };
+        })
+        .unwrap_or_else(|_| iter_ptr = std::ptr::null_mut());
 
         Ok(())
     })
@@ -217,9 +245,9 @@ pub extern "C" fn mut_trs_iterator_value(
     wrap(|| {
         let trs_iter = get_mut!(CMutTrsIterator, iter_ptr);
         trs_iter
-            .value()
+            .value()?
             .map(|tr| {
-                let ctr = Box::into_raw(Box::new(CTr::c_repr_of(tr)?));
+                let ctr = Box::into_raw(Box::new(tr));
                 unsafe { *tr_ptr = ctr };
                 Ok(())
             })
@@ -238,7 +266,7 @@ pub extern "C" fn mut_trs_iterator_set_value(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let trs_iter = get_mut!(CMutTrsIterator, iter_ptr);
-        let tr = unsafe { <CTr as ffi_convert::RawBorrow<CTr>>::raw_borrow(tr_ptr)? }.as_rust()?;
+        let tr = unsafe { <CTr as ffi_convert::RawBorrow<CTr>>::raw_borrow(tr_ptr)? };
         trs_iter.set_value(tr)?;
         Ok(())
     })
@@ -285,12 +313,14 @@ pub struct CStateIterator(pub(crate) Peekable<Range<CStateId>>);
 
 #[no_mangle]
 pub extern "C" fn state_iterator_new(
-    fst_ptr: *mut CVecFst,
+    fst_ptr: *mut CFst,
     iter_ptr: *mut *const CStateIterator,
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
-        let fst = get!(CVecFst, fst_ptr);
-        let state_iter = fst.states_iter().peekable();
+        let fst = get!(CFst, fst_ptr);
+        let state_iter = with_weight_type!(fst.fst_weight_type(), W => {
+            as_fst!(VectorFst<W>, fst).states_iter().peekable()
+        });
         let raw_ptr = CStateIterator(state_iter).into_raw_pointer();
         unsafe { *iter_ptr = raw_ptr };
         Ok(())
@@ -1,13 +1,33 @@
+use anyhow::Result;
 use ffi_convert::{CReprOf, RawPointerConverter};
 use std::ffi::CString;
 
-use rustfst::semirings::TropicalWeight;
-use rustfst::{Semiring, StringPath};
+use rustfst::StringPath;
 
+use crate::fst::BindableWeight;
 use crate::{get, wrap, RUSTFST_FFI_RESULT};
 
+/// StringPath methods exposed to the C side, whatever the semiring of the path is.
+pub trait BindableStringPath {
+    fn weight_value(&self) -> f32;
+    fn istring(&self) -> Result<String>;
+    fn ostring(&self) -> Result<String>;
+}
+
+impl<W: BindableWeight> BindableStringPath for StringPath<W> {
+    fn weight_value(&self) -> f32 {
+        *self.weight().value()
+    }
+    fn istring(&self) -> Result<String> {
+        StringPath::istring(self)
+    }
+    fn ostring(&self) -> Result<String> {
+        StringPath::ostring(self)
+    }
+}
+
 #[derive(RawPointerConverter)]
-pub struct CStringPath(pub(crate) StringPath<TropicalWeight>);
+pub struct CStringPath(pub(crate) Box<dyn BindableStringPath>);
 
 #[no_mangle]
 pub extern "C" fn string_path_destroy(iter_ptr: *mut CStringPath) -> RUSTFST_FFI_RESULT {
@@ -30,7 +50,7 @@ pub extern "C" fn string_path_weight(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let string_path = get!(CStringPath, c_string_path);
-        let weight_val = *string_path.weight().value();
+        let weight_val = string_path.weight_value();
         unsafe { *weight = weight_val }
         Ok(())
     })
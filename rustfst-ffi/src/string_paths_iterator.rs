@@ -4,17 +4,15 @@ use anyhow::{anyhow, Result};
 use ffi_convert::{RawPointerConverter, UnexpectedNullPointerError};
 
 use rustfst::fst_impls::VectorFst;
-use rustfst::prelude::{Fst, StringPathsIterator};
-use rustfst::semirings::TropicalWeight;
+use rustfst::prelude::Fst;
 
-use crate::fst::as_fst;
-use crate::fst::CFst;
-use crate::string_path::CStringPath;
+use crate::fst::{as_fst, with_weight_type, CFst};
+use crate::string_path::{BindableStringPath, CStringPath};
 use crate::{get, get_mut, wrap, RUSTFST_FFI_RESULT};
 
-pub struct CStringPathsIterator<'a>(
-    pub(crate) Peekable<StringPathsIterator<'a, TropicalWeight, VectorFst<TropicalWeight>>>,
-);
+type BoxedStringPathsIterator<'a> = Box<dyn Iterator<Item = Box<dyn BindableStringPath>> + 'a>;
+
+pub struct CStringPathsIterator<'a>(pub(crate) Peekable<BoxedStringPathsIterator<'a>>);
 
 impl<'a> RawPointerConverter<CStringPathsIterator<'a>> for CStringPathsIterator<'a> {
     fn into_raw_pointer(self) -> *const CStringPathsIterator<'a> {
@@ -52,8 +50,14 @@ pub extern "C" fn string_paths_iterator_new(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let fst = get!(CFst, fst);
-        let fst: &VectorFst<_> = as_fst!(VectorFst<TropicalWeight>, fst);
-        let it = fst.string_paths_iter()?.peekable();
+        let it: BoxedStringPathsIterator = with_weight_type!(fst.fst_weight_type(), W => {
+            let fst = as_fst!(VectorFst<W>, fst);
+            Box::new(
+                fst.string_paths_iter()?
+                    .map(|p| Box::new(p) as Box<dyn BindableStringPath>),
+            )
+        });
+        let it = it.peekable();
         let raw_pointer = CStringPathsIterator(it).into_raw_pointer();
         unsafe { *res_iterator = raw_pointer };
         Ok(())
@@ -1,21 +1,55 @@
+use crate::fst::BindableWeight;
 use crate::tr::CTr;
 use crate::{get, get_mut, wrap, RUSTFST_FFI_RESULT};
 use std::ffi::CString;
+use std::fmt::Debug;
 
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use ffi_convert::*;
 use rustfst::prelude::TrsVec;
 use rustfst::semirings::TropicalWeight;
 use rustfst::Trs;
 
+/// This trait makes the `TrsVec` of any binded semiring boxable, its transitions being
+/// exchanged as `CTr`.
+pub trait BindableTrs: Debug {
+    fn trs_len(&self) -> usize;
+    fn trs_get(&self, index: usize) -> Result<Option<CTr>>;
+    fn trs_remove(&mut self, index: usize) -> Result<CTr>;
+    fn trs_push(&mut self, tr: &CTr) -> Result<()>;
+    fn trs_shallow_clone(&self) -> Box<dyn BindableTrs>;
+}
+
+impl<W: BindableWeight> BindableTrs for TrsVec<W> {
+    fn trs_len(&self) -> usize {
+        self.len()
+    }
+    fn trs_get(&self, index: usize) -> Result<Option<CTr>> {
+        Ok(self
+            .get(index)
+            .map(|tr| CTr::c_repr_of(tr.clone()))
+            .transpose()?)
+    }
+    fn trs_remove(&mut self, index: usize) -> Result<CTr> {
+        Ok(CTr::c_repr_of(self.remove(index))?)
+    }
+    fn trs_push(&mut self, tr: &CTr) -> Result<()> {
+        self.push(tr.as_rust()?);
+        Ok(())
+    }
+    fn trs_shallow_clone(&self) -> Box<dyn BindableTrs> {
+        Box::new(self.shallow_clone())
+    }
+}
+
 #[derive(RawPointerConverter)]
-pub struct CTrs(pub(crate) TrsVec<TropicalWeight>);
+pub struct CTrs(pub(crate) Box<dyn BindableTrs>);
 
 #[no_mangle]
 pub extern "C" fn trs_vec_new(new_struct: *mut *const CTrs) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let trs: TrsVec<TropicalWeight> = TrsVec::default();
-        let raw_pointer = CTrs(trs).into_raw_pointer();
+        let raw_pointer = CTrs(Box::new(trs)).into_raw_pointer();
         unsafe { *new_struct = raw_pointer };
         Ok(())
     })
@@ -29,8 +63,15 @@ pub extern "C" fn trs_vec_remove(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let trs = get_mut!(CTrs, trs);
-        let removed_tr = trs.remove(index);
-        let ctr = Box::into_raw(Box::new(CTr::c_repr_of(removed_tr)?));
+        if index >= trs.trs_len() {
+            return Err(format_err!(
+                "Index {} is out of range, the number of trs is {}",
+                index,
+                trs.trs_len()
+            ));
+        }
+        let removed_tr = trs.trs_remove(index)?;
+        let ctr = Box::into_raw(Box::new(removed_tr));
         unsafe { *removed_tr_ptr = ctr };
         Ok(())
     })
@@ -41,7 +82,7 @@ pub extern "C" fn trs_vec_push(trs: *mut CTrs, new_tr: *const CTr) -> RUSTFST_FF
     wrap(|| {
         let trs = get_mut!(CTrs, trs);
         let tr = unsafe { <CTr as ffi_convert::RawBorrow<CTr>>::raw_borrow(new_tr)? };
-        trs.push(tr.as_rust()?);
+        trs.trs_push(tr)?;
         Ok(())
     })
 }
@@ -53,7 +94,7 @@ pub extern "C" fn trs_vec_shallow_clone(
 ) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let trs = get!(CTrs, trs);
-        let cloned_trs = trs.shallow_clone();
+        let cloned_trs = trs.trs_shallow_clone();
         let raw_pointer = CTrs(cloned_trs).into_raw_pointer();
         unsafe { *cloned_trs_ptr = raw_pointer };
         Ok(())
@@ -64,7 +105,7 @@ pub extern "C" fn trs_vec_shallow_clone(
 pub extern "C" fn trs_vec_len(trs: *const CTrs, num_trs: *mut libc::size_t) -> RUSTFST_FFI_RESULT {
     wrap(|| {
         let trs = get!(CTrs, trs);
-        unsafe { *num_trs = trs.len() as libc::size_t };
+        unsafe { *num_trs = trs.trs_len() as libc::size_t };
         Ok(())
     })
 }
@@ -14,7 +14,10 @@ from rustfst.ffi_utils import (
 
 
 def acceptor(
-    astring: str, symbol_table: SymbolTable, weight: Optional[float] = None
+    astring: str,
+    symbol_table: SymbolTable,
+    weight: Optional[float] = None,
+    weight_type: str = "tropical",
 ) -> VectorFst:
     """
     Creates an acceptor from a string.
@@ -25,17 +28,19 @@ def acceptor(
       weight: A Weight or weight string indicating the desired path weight. If
         omitted or null, the path weight is set to semiring One.
       symbol_table: SymbolTable to be used to encode the string.
+      weight_type: Weight type of the FST : "tropical", "log" or "probability".
     Returns:
       An FST acceptor.
     """
     if weight is None:
-        weight = weight_one()
+        weight = weight_one(weight_type)
 
     acceptor_fst_ptr = ctypes.pointer(ctypes.c_void_p())
     ret_code = lib.utils_string_to_acceptor(
         astring.encode("utf-8"),
         symbol_table.ptr,
         ctypes.c_float(weight),
+        weight_type.encode("utf-8"),
         ctypes.byref(acceptor_fst_ptr),
     )
     err_msg = "Error creating acceptor FST"
@@ -49,6 +54,7 @@ def transducer(
     isymt: SymbolTable,
     osymt: SymbolTable,
     weight: Optional[float] = None,
+    weight_type: str = "tropical",
 ) -> VectorFst:
     """
     Creates a transducer from a pair of strings or acceptor FSTs.
@@ -60,12 +66,13 @@ def transducer(
       weight: A Weight as float.
       isymt: SymbolTable to be used to encode the string.
       osymt: SymbolTable to be used to encode the string.
+      weight_type: Weight type of the FST : "tropical", "log" or "probability".
     Returns:
       An FST transducer.
     """
 
     if weight is None:
-        weight = weight_one()
+        weight = weight_one(weight_type)
 
     transducer_fst_ptr = ctypes.c_void_p()
     ret_code = lib.utils_string_to_transducer(
@@ -74,6 +81,7 @@ def transducer(
         isymt.ptr,
         osymt.ptr,
         ctypes.c_float(weight),
+        weight_type.encode("utf-8"),
         ctypes.byref(transducer_fst_ptr),
     )
     err_msg = "Error creating tranducer FST"
@@ -82,19 +90,22 @@ def transducer(
     return VectorFst(ptr=transducer_fst_ptr)
 
 
-def epsilon_machine(weight: Optional[float] = None) -> VectorFst:
+def epsilon_machine(
+    weight: Optional[float] = None, weight_type: str = "tropical"
+) -> VectorFst:
     """
     Constructs a single-state, no-arc FST accepting epsilon.
     This function creates an unweighted FST with a single state which is both
     initial and final.
     Args:
       weight: A Weight. Default semiring One.
+      weight_type: Weight type of the FST : "tropical", "log" or "probability".
     Returns:
       An FST.
     """
     if weight is None:
-        weight = weight_one()
-    fst = VectorFst()
+        weight = weight_one(weight_type)
+    fst = VectorFst(weight_type=weight_type)
     state = fst.add_state()
     fst.set_start(state)
     fst.set_final(state, weight)
@@ -38,20 +38,43 @@ class DeterminizeConfig:
     Struct containing the parameters controlling the determinization algorithm.
     """
 
-    def __init__(self, det_type: DeterminizeType, delta: Optional[float] = None):
+    def __init__(
+        self,
+        det_type: DeterminizeType,
+        delta: Optional[float] = None,
+        max_states: Optional[int] = None,
+        max_trs: Optional[int] = None,
+        subsequential_label: int = 0,
+        increment_subsequential_label: bool = False,
+    ):
         """
         Creates the configuration object.
         Args:
             det_type: Type of determinization to perform.
             delta:
+            max_states: Maximum number of states of the determinized Fst. An error is
+                raised when it is exceeded.
+            max_trs: Maximum number of transitions of the determinized Fst. An error is
+                raised when it is exceeded.
+            subsequential_label: Input label of the final transitions emitting the output
+                labels remaining at the end of a path when determinizing a transducer.
+            increment_subsequential_label: Whether to increment `subsequential_label` for
+                each final transition leaving a state.
         """
         if delta is None:
             delta = KDELTA
 
+        def optional_size(value: Optional[int]):
+            return None if value is None else ctypes.byref(ctypes.c_size_t(value))
+
         config = ctypes.pointer(ctypes.c_void_p())
         ret_code = lib.fst_determinize_config_new(
             ctypes.c_float(delta),
             ctypes.c_size_t(det_type.value),
+            optional_size(max_states),
+            optional_size(max_trs),
+            ctypes.c_size_t(subsequential_label),
+            ctypes.c_bool(increment_subsequential_label),
             ctypes.byref(config),
         )
         err_msg = "Error creating DeterminizeConfig"
@@ -0,0 +1,30 @@
+from __future__ import annotations
+import ctypes
+from rustfst.ffi_utils import (
+    lib,
+    check_ffi_error,
+)
+
+from rustfst.fst.vector_fst import VectorFst
+
+
+def weight_convert(fst: VectorFst, weight_type: str) -> VectorFst:
+    """
+    Convert an fst to the semiring with the given weight type.
+    Conversions from and to the probability semiring exponentiate or take
+    the negative logarithm of the weights, the other ones keep the weight values.
+    Args:
+        fst: Fst to convert.
+        weight_type: "tropical", "log" or "probability".
+    Returns:
+        The converted Fst.
+    """
+
+    converted_fst = ctypes.c_void_p()
+    ret_code = lib.fst_weight_convert(
+        fst.ptr, weight_type.encode("utf-8"), ctypes.byref(converted_fst)
+    )
+    err_msg = "Error during weight_convert"
+    check_ffi_error(ret_code, err_msg)
+
+    return VectorFst(ptr=converted_fst)
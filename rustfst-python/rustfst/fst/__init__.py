@@ -20,6 +20,19 @@ class Fst:
         self._input_symbols = isymt
         self._output_symbols = osymt
 
+    def weight_type(self) -> str:
+        """
+        Returns the type of the weights of the Fst.
+        Returns :
+            "tropical", "log" or "probability".
+        """
+        weight_type = ctypes.c_void_p()
+        ret_code = lib.fst_weight_type(self.ptr, ctypes.byref(weight_type))
+        err_msg = "Error getting weight type"
+        check_ffi_error(ret_code, err_msg)
+
+        return ctypes.string_at(weight_type).decode("utf8")
+
     def start(self) -> Optional[int]:
         """
         Returns the start state.
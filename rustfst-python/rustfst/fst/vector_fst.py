@@ -20,9 +20,12 @@ from typing import List
 
 
 class VectorFst(Fst):
-    def __init__(self, ptr=None):
+    def __init__(self, ptr=None, weight_type: str = "tropical"):
         """
         Creates an empty VectorFst.
+        Args:
+            ptr: Pointer to an existing Fst, the weight type is then the one of this Fst.
+            weight_type: Weight type of the empty Fst : "tropical", "log" or "probability".
         """
         self._input_symbols = None
         self._output_symbols = None
@@ -48,7 +51,9 @@ class VectorFst(Fst):
 
         else:
             fst_ptr = ctypes.pointer(ctypes.c_void_p())
-            ret_code = lib.vec_fst_new(ctypes.byref(fst_ptr))
+            ret_code = lib.vec_fst_new_with_weight_type(
+                weight_type.encode("utf-8"), ctypes.byref(fst_ptr)
+            )
 
             err_msg = "Something went wrong when creating the Fst struct"
             check_ffi_error(ret_code, err_msg)
@@ -102,7 +107,7 @@ class VectorFst(Fst):
         See also: `set_start`.
         """
         if weight is None:
-            weight = weight_one()
+            weight = weight_one(self.weight_type())
 
         state = ctypes.c_size_t(state)
         weight = ctypes.c_float(weight)
@@ -535,6 +540,19 @@ class VectorFst(Fst):
         optimize_in_log(self)
         return self
 
+    def weight_convert(self, weight_type: str) -> VectorFst:
+        """
+        Convert the Fst to the Semiring with the given weight type.
+        Args:
+            weight_type: "tropical", "log" or "probability".
+
+        Returns:
+            The converted Fst.
+        """
+        from rustfst.algorithms.weight_convert import weight_convert
+
+        return weight_convert(self, weight_type)
+
     def tr_sort(self, ilabel_cmp: bool = True):
         from rustfst.algorithms.tr_sort import tr_sort
 
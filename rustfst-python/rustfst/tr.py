@@ -30,6 +30,7 @@ class Tr:
         olabel: Optional[int] = None,
         weight: Optional[float] = None,
         nextstate: Optional[int] = None,
+        weight_type: str = "tropical",
     ):
         """
         Create a new transition.
@@ -37,14 +38,16 @@ class Tr:
         Args:
             ilabel: The input label.
             olabel: The outpit label.
-            weight: The transition's weight
+            weight: The transition's weight. Semiring One if omitted.
             nextstate: The destination state for the transition.
+            weight_type: Weight type of the Fst the transition belongs to : "tropical", "log"
+              or "probability". Used to compute semiring One when the weight is omitted.
         """
         if ilabel and olabel is None and weight is None and nextstate is None:
             self._ptr = ilabel
         else:
             if weight is None:
-                weight = weight_one()
+                weight = weight_one(weight_type)
 
             ptr = c_void_p()
             exit_code = lib.tr_new(
@@ -5,27 +5,35 @@ from rustfst.ffi_utils import (
 )
 
 
-def weight_one() -> float:
+def weight_one(weight_type: str = "tropical") -> float:
     """
-    Compute One() in the Tropical Semiring.
+    Compute One() in the Semiring with the given weight type.
+    Args:
+        weight_type: "tropical", "log" or "probability".
     Returns:
-        Float value corresponding to One() in the Tropical Semiring.
+        Float value corresponding to One() in the Semiring.
     """
     weight = ctypes.c_float()
-    ret_code = lib.fst_weight_one(ctypes.byref(weight))
+    ret_code = lib.fst_weight_type_one(
+        weight_type.encode("utf-8"), ctypes.byref(weight)
+    )
     err_msg = "weight_one failed"
     check_ffi_error(ret_code, err_msg)
     return float(weight.value)
 
 
-def weight_zero() -> float:
+def weight_zero(weight_type: str = "tropical") -> float:
     """
-    Compute Zero() in the Tropical Semiring.
+    Compute Zero() in the Semiring with the given weight type.
+    Args:
+        weight_type: "tropical", "log" or "probability".
     Returns:
-        Float value corresponding to Zero() in the Tropical Semiring.
+        Float value corresponding to Zero() in the Semiring.
     """
     weight = ctypes.c_float()
-    ret_code = lib.fst_weight_zero(ctypes.byref(weight))
+    ret_code = lib.fst_weight_type_zero(
+        weight_type.encode("utf-8"), ctypes.byref(weight)
+    )
     err_msg = "weight_zero failed"
     check_ffi_error(ret_code, err_msg)
     return float(weight.value)
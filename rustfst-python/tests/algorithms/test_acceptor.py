@@ -26,3 +26,27 @@ def test_acceptor():
     expected_fst.add_tr(s2, tr2)
 
     assert f == expected_fst
+
+
+def test_acceptor_probability():
+    symt = SymbolTable()
+    symt.add_symbol("hello")
+
+    f = acceptor("hello", symt, weight_type="probability")
+
+    # Expected FST
+    expected_fst = VectorFst(weight_type="probability")
+
+    s1 = expected_fst.add_state()
+    s2 = expected_fst.add_state()
+
+    expected_fst.set_start(s1)
+    expected_fst.set_final(s2)
+
+    tr1 = Tr(1, 1, None, s2, weight_type="probability")
+    expected_fst.add_tr(s1, tr1)
+
+    assert f == expected_fst
+    assert f.weight_type() == "probability"
+    assert f.final(s2) == 1.0
+    assert [tr.weight for tr in f.trs(s1)] == [1.0]
@@ -1,3 +1,5 @@
+import pytest
+
 from rustfst import VectorFst, Tr
 
 from rustfst.algorithms.determinize import DeterminizeConfig, DeterminizeType
@@ -61,3 +63,27 @@ def test_determinize_fst():
     det_fst = fst1.determinize(config)
 
     assert det_fst == expected_fst
+
+
+def test_determinize_config_limits():
+    fst = VectorFst()
+    s1 = fst.add_state()
+    s2 = fst.add_state()
+    s3 = fst.add_state()
+    fst.set_start(s1)
+    fst.set_final(s3)
+    fst.add_tr(s1, Tr(1, 1, 1.0, s2))
+    fst.add_tr(s2, Tr(2, 2, 1.0, s3))
+
+    config = DeterminizeConfig(DeterminizeType.DETERMINIZE_FUNCTIONAL, max_states=2)
+    with pytest.raises(Exception):
+        fst.determinize(config)
+
+    config = DeterminizeConfig(DeterminizeType.DETERMINIZE_FUNCTIONAL, max_trs=1)
+    with pytest.raises(Exception):
+        fst.determinize(config)
+
+    config = DeterminizeConfig(
+        DeterminizeType.DETERMINIZE_FUNCTIONAL, max_states=3, max_trs=2
+    )
+    assert fst.determinize(config) == fst
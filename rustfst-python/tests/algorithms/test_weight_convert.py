@@ -0,0 +1,47 @@
+import math
+
+import pytest
+from rustfst import VectorFst, Tr
+from rustfst.weight import weight_one, weight_zero
+
+
+def test_weight_convert_tropical_to_log():
+    fst = VectorFst()
+    s0 = fst.add_state()
+    s1 = fst.add_state()
+    fst.set_start(s0)
+    fst.set_final(s1, 0.5)
+    fst.add_tr(s0, Tr(1, 2, 1.5, s1))
+
+    log_fst = fst.weight_convert("log")
+
+    assert log_fst.weight_type() == "log"
+    assert log_fst.final(s1) == 0.5
+    tr = next(log_fst.trs(s0))
+    assert tr.weight == 1.5
+
+    assert log_fst.weight_convert("tropical") == fst
+
+
+def test_weight_convert_log_to_probability():
+    fst = VectorFst(weight_type="log")
+    s0 = fst.add_state()
+    s1 = fst.add_state()
+    fst.set_start(s0)
+    fst.set_final(s1)
+    fst.add_tr(s0, Tr(1, 1, -math.log(0.25), s1))
+
+    proba_fst = fst.weight_convert("probability")
+
+    assert proba_fst.weight_type() == "probability"
+    assert proba_fst.final(s1) == weight_one("probability")
+    tr = next(proba_fst.trs(s0))
+    assert pytest.approx(tr.weight) == pytest.approx(0.25)
+
+
+def test_weight_one_zero():
+    assert weight_one() == 0.0
+    assert weight_one("log") == 0.0
+    assert weight_one("probability") == 1.0
+    assert weight_zero("probability") == 0.0
+    assert math.isinf(weight_zero("log"))
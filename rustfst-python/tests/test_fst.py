@@ -284,6 +284,31 @@ def test_fst_io_bytes():
     assert fst_loaded == fst
 
 
+def test_fst_log_read_write():
+    fst = VectorFst(weight_type="log")
+
+    s1 = fst.add_state()
+    s2 = fst.add_state()
+
+    fst.set_start(s1)
+    fst.set_final(s2)
+    fst.add_tr(s1, Tr(3, 5, 10.0, s2))
+
+    assert fst.weight_type() == "log"
+    assert fst.final(s2) == weight_one("log")
+
+    with NamedTemporaryFile() as f:
+        fst.write(f.name)
+        read_fst = VectorFst.read(f.name)
+
+    assert read_fst.weight_type() == "log"
+    assert fst == read_fst
+
+    fst_loaded = VectorFst.from_bytes(fst.to_bytes())
+    assert fst_loaded.weight_type() == "log"
+    assert fst_loaded == fst
+
+
 def test_fst_unset_final():
     fst = VectorFst()
 
@@ -353,3 +378,20 @@ def test_fst_relabel_tables():
     assert fst_3 == fst_ref
     assert fst_3.input_symbols() == new_isymt
     assert fst_3.output_symbols() == new_osymt
+
+
+def test_fst_log_from_truncated_bytes():
+    fst = VectorFst(weight_type="log")
+    s1 = fst.add_state()
+    s2 = fst.add_state()
+    fst.set_start(s1)
+    fst.set_final(s2)
+    fst.add_tr(s1, Tr(3, 5, 10.0, s2))
+
+    with pytest.raises(ValueError) as e:
+        VectorFst.from_bytes(fst.to_bytes()[:-4])
+    assert "binary VectorFst" in str(e.value)
+
+    with pytest.raises(ValueError) as e:
+        VectorFst.from_bytes(b"not an fst")
+    assert "header" in str(e.value)
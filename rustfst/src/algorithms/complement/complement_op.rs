@@ -0,0 +1,109 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::FstOp;
+use crate::fst_properties::mutable_properties::complement_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Tr, Trs, TrsVec};
+
+/// State 0 is the super-final sink state. Each state `s` of the input FST is mapped to `s + 1`.
+pub struct ComplementOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    alphabet: Vec<Label>,
+    properties: FstProperties,
+    w: PhantomData<(W, F)>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> Debug for ComplementOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ComplementOp {{ fst: {:?}, alphabet: {:?} }}",
+            self.fst.borrow(),
+            self.alphabet
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> ComplementOp<W, F, B> {
+    pub fn new(fst: B, props: FstProperties, mut alphabet: Vec<Label>) -> Result<Self> {
+        if !props.contains(
+            FstProperties::ACCEPTOR
+                | FstProperties::UNWEIGHTED
+                | FstProperties::NO_EPSILONS
+                | FstProperties::I_DETERMINISTIC,
+        ) {
+            bail!("ComplementFst : Argument not an unweighted epsilon-free deterministic acceptor")
+        }
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        Ok(Self {
+            fst,
+            alphabet,
+            properties: complement_properties(props),
+            w: PhantomData,
+        })
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for ComplementOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(Some(self.fst.borrow().start().map_or(0, |s| s + 1)))
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<W>> {
+        if id == 0 {
+            let trs: Vec<_> = self
+                .alphabet
+                .iter()
+                .map(|l| Tr::new(*l, *l, W::one(), 0))
+                .collect();
+            return Ok(TrsVec::from(trs));
+        }
+        let mut fst_trs: Vec<_> = self
+            .fst
+            .borrow()
+            .get_trs(id - 1)?
+            .trs()
+            .iter()
+            .map(|tr| Tr::new(tr.ilabel, tr.olabel, W::one(), tr.nextstate + 1))
+            .collect();
+        fst_trs.sort_by_key(|tr| tr.ilabel);
+
+        // Merges the transitions of the input FST with the ones going to the sink state
+        // for each label of the alphabet that can't be read from this state.
+        let mut trs = Vec::with_capacity(self.alphabet.len().max(fst_trs.len()));
+        let mut idx = 0;
+        for label in self.alphabet.iter().cloned() {
+            while idx < fst_trs.len() && fst_trs[idx].ilabel < label {
+                trs.push(fst_trs[idx].clone());
+                idx += 1;
+            }
+            if idx < fst_trs.len() && fst_trs[idx].ilabel == label {
+                trs.push(fst_trs[idx].clone());
+                idx += 1;
+            } else {
+                trs.push(Tr::new(label, label, W::one(), 0));
+            }
+        }
+        trs.extend_from_slice(&fst_trs[idx..]);
+        Ok(TrsVec::from(trs))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        if id == 0 || self.fst.borrow().final_weight(id - 1)?.is_none() {
+            Ok(Some(W::one()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
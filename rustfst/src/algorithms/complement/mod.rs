@@ -0,0 +1,4 @@
+mod complement_fst;
+mod complement_op;
+
+pub use complement_fst::ComplementFst;
@@ -357,9 +357,12 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::algorithms::compose::compose;
     use crate::algorithms::compose::matchers::SortedMatcher;
+    use crate::algorithms::lazy::ShardedHashMapCache;
     use crate::fst_impls::VectorFst;
     use crate::semirings::TropicalWeight;
+    use crate::Tr;
 
     #[test]
     fn test_compose_fst_sync() {
@@ -394,4 +397,81 @@ mod test {
             >,
         >();
     }
+
+    #[test]
+    fn test_compose_fst_send_sync_sharded_cache() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<
+            ComposeFst<
+                TropicalWeight,
+                VectorFst<_>,
+                VectorFst<_>,
+                Arc<_>,
+                Arc<_>,
+                SortedMatcher<_, _, _>,
+                SortedMatcher<_, _, _>,
+                SequenceComposeFilterBuilder<_, _, _, _, _, _, _>,
+                ShardedHashMapCache<TropicalWeight>,
+            >,
+        >();
+    }
+
+    #[test]
+    fn test_compose_fst_shared_between_threads() -> Result<()> {
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        for fst in [&mut fst1, &mut fst2].iter_mut() {
+            let states: Vec<_> = (0..20).map(|_| fst.add_state()).collect();
+            fst.set_start(states[0])?;
+            for (i, s) in states.iter().enumerate() {
+                for l in 1..5 {
+                    let n = states[(i * l + 1) % states.len()];
+                    fst.add_tr(
+                        *s,
+                        Tr::new(l as _, l as _, TropicalWeight::new(l as f32), n),
+                    )?;
+                }
+                fst.set_final(*s, TropicalWeight::new(i as f32))?;
+            }
+        }
+        let fst1 = Arc::new(fst1);
+        let fst2 = Arc::new(fst2);
+        let expected: VectorFst<_> = compose::<_, VectorFst<_>, VectorFst<_>, _, _, _>(
+            Arc::clone(&fst1),
+            Arc::clone(&fst2),
+        )?;
+
+        type M = SortedMatcher<
+            TropicalWeight,
+            VectorFst<TropicalWeight>,
+            Arc<VectorFst<TropicalWeight>>,
+        >;
+        let lazy_fst = Arc::new(ComposeFst::<
+            _,
+            _,
+            _,
+            _,
+            _,
+            M,
+            M,
+            SequenceComposeFilterBuilder<_, _, _, _, _, M, M>,
+            _,
+        >::new_with_options_and_cache(
+            fst1,
+            fst2,
+            ComposeFstOpOptions::default(),
+            ShardedHashMapCache::new(4),
+        )?);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lazy_fst = Arc::clone(&lazy_fst);
+                std::thread::spawn(move || lazy_fst.compute::<VectorFst<_>>())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap()?, expected);
+        }
+        Ok(())
+    }
 }
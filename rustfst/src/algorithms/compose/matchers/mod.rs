@@ -5,6 +5,8 @@ use anyhow::Result;
 use bitflags::bitflags;
 pub use generic_matcher::GenericMatcher;
 pub use multi_eps_matcher::{MultiEpsMatcher, MultiEpsMatcherFlags};
+pub use phi_matcher::PhiMatcher;
+pub use rho_matcher::RhoMatcher;
 pub use sigma_matcher::SigmaMatcher;
 pub use sorted_matcher::SortedMatcher;
 
@@ -16,6 +18,8 @@ use std::borrow::Borrow;
 
 mod generic_matcher;
 mod multi_eps_matcher;
+mod phi_matcher;
+mod rho_matcher;
 mod sigma_matcher;
 mod sorted_matcher;
 
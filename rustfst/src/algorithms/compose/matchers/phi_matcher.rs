@@ -0,0 +1,373 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::matchers::{
+    IterItemMatcher, MatchType, Matcher, MatcherFlags, MatcherRewriteMode, REQUIRE_PRIORITY,
+};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::{Label, Semiring, StateId, Tr, EPS_LABEL, NO_LABEL};
+
+/// Matcher handling phi (failure) transitions: when a label can't be matched
+/// at a state, the phi transition is followed (multiplying its weight) and the
+/// label is looked up again at its destination, e.g. the backoff transitions
+/// of an n-gram language model.
+///
+/// There can be at most one phi transition per state. If `phi_loop` is set,
+/// a phi self-loop matches any label and is returned with `phi_label`
+/// rewritten into the matched label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    match_type: MatchType,
+    w: PhantomData<(W, F, B)>,
+    phi_label: Label,
+    phi_loop: bool,
+    matcher: Arc<M>,
+    rewrite_both: bool,
+}
+
+/// Returns the phi transition leaving `state` if any.
+fn phi_tr<W, F, B, M>(state: StateId, matcher: &Arc<M>, phi_label: Label) -> Result<Option<Tr<W>>>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    if phi_label == NO_LABEL {
+        return Ok(None);
+    }
+    let mut it = matcher.iter(state, phi_label)?;
+    let tr = match it.next() {
+        Some(IterItemMatcher::Tr(tr)) => tr,
+        _ => return Ok(None),
+    };
+    if it.next().is_some() {
+        bail!("PhiMatcher: Phi non-determinism not supported")
+    }
+    Ok(Some(tr))
+}
+
+impl<W, F, B, M> PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    pub fn new(
+        match_type: MatchType,
+        phi_label: Label,
+        phi_loop: bool,
+        rewrite_mode: MatcherRewriteMode,
+        matcher: Arc<M>,
+    ) -> Result<Self> {
+        if match_type == MatchType::MatchBoth {
+            bail!("PhiMatcher: Bad match type")
+        }
+        if phi_label == EPS_LABEL {
+            bail!("PhiMatcher: {} cannot be used as phi_label", EPS_LABEL)
+        }
+        let rewrite_both = match rewrite_mode {
+            MatcherRewriteMode::MatcherRewriteAuto => matcher
+                .fst()
+                .borrow()
+                .properties()
+                .contains(FstProperties::ACCEPTOR),
+            MatcherRewriteMode::MatcherRewriteAlways => true,
+            MatcherRewriteMode::MatcherRewriteNever => false,
+        };
+        Ok(Self {
+            match_type,
+            w: PhantomData,
+            phi_label,
+            phi_loop,
+            matcher,
+            rewrite_both,
+        })
+    }
+
+    pub fn phi_label(&self) -> Label {
+        self.phi_label
+    }
+}
+
+impl<W, F, B, M> Matcher<W, F, B> for PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    type Iter = IteratorPhiMatcher<W, M::Iter>;
+
+    fn new(_fst: B, _match_type: MatchType) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        bail!("This constructor can't be used for initializing PhiMatcher.")
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        if label == self.phi_label && self.phi_label != NO_LABEL {
+            bail!("PhiMatcher::Find: bad label (phi)")
+        }
+        let mut phi_weight = W::one();
+        let mut s = state;
+        loop {
+            let mut it = self.matcher.iter(s, label)?.peekable();
+            if self.phi_label == NO_LABEL
+                || label == EPS_LABEL
+                || label == NO_LABEL
+                || it.peek().is_some()
+            {
+                return Ok(self.iterator(it, s, phi_weight, None));
+            }
+            let tr = match phi_tr(s, &self.matcher, self.phi_label)? {
+                Some(tr) => tr,
+                None => return Ok(self.iterator(it, s, phi_weight, None)),
+            };
+            if self.phi_loop && tr.nextstate == s {
+                let it = self.matcher.iter(s, self.phi_label)?.peekable();
+                return Ok(self.iterator(it, s, phi_weight, Some(label)));
+            }
+            phi_weight.times_assign(&tr.weight)?;
+            s = tr.nextstate;
+        }
+    }
+
+    /// If the state is not final, the final weight is looked up by following
+    /// the phi transitions.
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        let final_weight = self.matcher.final_weight(state)?;
+        if self.phi_label == NO_LABEL || final_weight.is_some() {
+            return Ok(final_weight);
+        }
+        let mut weight = W::one();
+        let mut s = state;
+        loop {
+            if let Some(final_weight) = self.matcher.final_weight(s)? {
+                return Ok(Some(weight.times(final_weight)?));
+            }
+            let tr = match phi_tr(s, &self.matcher, self.phi_label)? {
+                Some(tr) => tr,
+                None => return Ok(None),
+            };
+            // Phi self-loops are not followed.
+            if tr.nextstate == s {
+                return Ok(None);
+            }
+            weight.times_assign(&tr.weight)?;
+            s = tr.nextstate;
+        }
+    }
+
+    fn match_type(&self, test: bool) -> Result<MatchType> {
+        self.matcher.match_type(test)
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        if self.phi_label == NO_LABEL || self.match_type == MatchType::MatchNone {
+            self.matcher.flags()
+        } else {
+            self.matcher.flags() | MatcherFlags::REQUIRE_MATCH
+        }
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        if phi_tr(state, &self.matcher, self.phi_label)?.is_some() {
+            Ok(REQUIRE_PRIORITY)
+        } else {
+            self.matcher.priority(state)
+        }
+    }
+
+    fn fst(&self) -> &B {
+        self.matcher.fst()
+    }
+}
+
+impl<W, F, B, M> PhiMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    fn iterator(
+        &self,
+        matcher_iterator: Peekable<M::Iter>,
+        state: StateId,
+        phi_weight: W,
+        phi_match: Option<Label>,
+    ) -> IteratorPhiMatcher<W, M::Iter> {
+        IteratorPhiMatcher {
+            matcher_iterator,
+            state,
+            phi_label: self.phi_label,
+            phi_weight,
+            phi_match,
+            match_type: self.match_type,
+            rewrite_both: self.rewrite_both,
+        }
+    }
+}
+
+pub struct IteratorPhiMatcher<W: Semiring, I: Iterator<Item = IterItemMatcher<W>>> {
+    matcher_iterator: Peekable<I>,
+    state: StateId,
+    phi_label: Label,
+    phi_weight: W,
+    phi_match: Option<Label>,
+    match_type: MatchType,
+    rewrite_both: bool,
+}
+
+impl<W, I> Iterator for IteratorPhiMatcher<W, I>
+where
+    W: Semiring,
+    I: Iterator<Item = IterItemMatcher<W>>,
+{
+    type Item = IterItemMatcher<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.matcher_iterator.next()?;
+        if self.phi_match.is_none() && self.phi_weight.is_one() {
+            return Some(item);
+        }
+        let mut tr = item.into_tr(self.state, self.match_type).unwrap();
+        tr.weight = self.phi_weight.times(&tr.weight).unwrap();
+        if let Some(phi_match) = self.phi_match {
+            if self.rewrite_both {
+                if tr.ilabel == self.phi_label {
+                    tr.ilabel = phi_match;
+                }
+                if tr.olabel == self.phi_label {
+                    tr.olabel = phi_match;
+                }
+            } else if self.match_type == MatchType::MatchInput {
+                tr.ilabel = phi_match;
+            } else {
+                tr.olabel = phi_match;
+            }
+        }
+        Some(IterItemMatcher::Tr(tr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
+    use crate::algorithms::compose::matchers::SortedMatcher;
+    use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions};
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::tr_sort;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::acceptor;
+    use crate::FstPath;
+
+    const PHI: Label = 100;
+
+    /// Bigram model over the labels 1, 2, 3 where only the bigram `1 2` is
+    /// known. State 0 is the unigram state and has a phi self-loop
+    /// for unknown labels. State 1 is reached after reading `1`.
+    fn backoff_lm() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 2, 2, 3.0, 0)?;
+        fst.emplace_tr(0, 3, 3, 2.0, 0)?;
+        fst.emplace_tr(0, PHI, PHI, 0.7, 0)?;
+        fst.emplace_tr(1, 2, 2, 0.5, 0)?;
+        fst.emplace_tr(1, PHI, PHI, 1.5, 0)?;
+        fst.set_final(0, TropicalWeight::one())?;
+        tr_sort(&mut fst, ILabelCompare {});
+        Ok(fst)
+    }
+
+    fn compose_with_lm(labels: &[Label]) -> Result<Vec<FstPath<TropicalWeight>>> {
+        let query: VectorFst<TropicalWeight> = acceptor(labels, TropicalWeight::one());
+        let lm = backoff_lm()?;
+        let phi_matcher = PhiMatcher::new(
+            MatchType::MatchInput,
+            PHI,
+            true,
+            MatcherRewriteMode::MatcherRewriteAuto,
+            Arc::new(SortedMatcher::new(lm.clone(), MatchType::MatchInput)?),
+        )?;
+        let compose_fst_op_opts = ComposeFstOpOptions::new(None, phi_matcher, None, None);
+        let compose_lazy = ComposeFst::<
+            _,                                                 // W
+            _,                                                 // F1
+            _,                                                 // F2
+            _,                                                 // B1
+            _,                                                 // B2
+            SortedMatcher<_, _, _>,                            // M1
+            PhiMatcher<_, _, _, SortedMatcher<_, _, _>>,       // M2
+            SequenceComposeFilterBuilder<_, _, _, _, _, _, _>, // CFB
+        >::new_with_options(query, lm, compose_fst_op_opts)?;
+        let composed: VectorFst<_> = compose_lazy.compute()?;
+        Ok(composed.paths_iter().collect())
+    }
+
+    #[test]
+    fn test_phi_matcher_known_bigram() -> Result<()> {
+        assert_eq!(
+            compose_with_lm(&[1, 2])?,
+            vec![FstPath::new(
+                vec![1, 2],
+                vec![1, 2],
+                TropicalWeight::new(1.5)
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_phi_matcher_backoff() -> Result<()> {
+        // 1.0 for `1`, 1.5 for the backoff and 2.0 for the unigram `3`.
+        assert_eq!(
+            compose_with_lm(&[1, 3])?,
+            vec![FstPath::new(
+                vec![1, 3],
+                vec![1, 3],
+                TropicalWeight::new(4.5)
+            )]
+        );
+        // The final weight of state 1 is the one of state 0 times the backoff.
+        assert_eq!(
+            compose_with_lm(&[1])?,
+            vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(2.5))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_phi_matcher_phi_loop() -> Result<()> {
+        // The unknown label 9 is matched by the phi self-loop of the unigram state.
+        assert_eq!(
+            compose_with_lm(&[1, 9])?,
+            vec![FstPath::new(
+                vec![1, 9],
+                vec![1, 9],
+                TropicalWeight::new(3.2)
+            )]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::matchers::{
+    IterItemMatcher, MatchType, Matcher, MatcherFlags, MatcherRewriteMode, REQUIRE_PRIORITY,
+};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::{Label, Semiring, StateId, EPS_LABEL, NO_LABEL};
+
+/// Matcher handling rho (rest) transitions: a rho transition matches any
+/// label that isn't matched by another transition leaving the same state.
+/// The rho label of the returned transitions is rewritten into the matched label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RhoMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    match_type: MatchType,
+    w: PhantomData<(W, F, B)>,
+    rho_label: Label,
+    matcher: Arc<M>,
+    rewrite_both: bool,
+}
+
+fn has_rho<W, F, B, M>(state: StateId, matcher: &Arc<M>, rho_label: Label) -> Result<bool>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    if rho_label != NO_LABEL {
+        Ok(matcher.iter(state, rho_label)?.next().is_some())
+    } else {
+        Ok(false)
+    }
+}
+
+impl<W, F, B, M> RhoMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    M: Matcher<W, F, B>,
+{
+    pub fn new(
+        match_type: MatchType,
+        rho_label: Label,
+        rewrite_mode: MatcherRewriteMode,
+        matcher: Arc<M>,
+    ) -> Result<Self> {
+        if match_type == MatchType::MatchBoth {
+            bail!("RhoMatcher: Bad match type")
+        }
+        if rho_label == EPS_LABEL {
+            bail!("RhoMatcher: {} cannot be used as rho_label", EPS_LABEL)
+        }
+        let rewrite_both = match rewrite_mode {
+            MatcherRewriteMode::MatcherRewriteAuto => matcher
+                .fst()
+                .borrow()
+                .properties()
+                .contains(FstProperties::ACCEPTOR),
+            MatcherRewriteMode::MatcherRewriteAlways => true,
+            MatcherRewriteMode::MatcherRewriteNever => false,
+        };
+        Ok(Self {
+            match_type,
+            w: PhantomData,
+            rho_label,
+            matcher,
+            rewrite_both,
+        })
+    }
+
+    pub fn rho_label(&self) -> Label {
+        self.rho_label
+    }
+}
+
+impl<W, F, B, M> Matcher<W, F, B> for RhoMatcher<W, F, B, M>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    M: Matcher<W, F, B>,
+{
+    type Iter = IteratorRhoMatcher<W, M::Iter>;
+
+    fn new(_fst: B, _match_type: MatchType) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        bail!("This constructor can't be used for initializing RhoMatcher.")
+    }
+
+    fn iter(&self, state: StateId, label: Label) -> Result<Self::Iter> {
+        if label == self.rho_label && self.rho_label != NO_LABEL {
+            bail!("RhoMatcher::Find: bad label (rho)")
+        }
+        let mut matcher_iterator = self.matcher.iter(state, label)?.peekable();
+        let mut rho_match = None;
+        if matcher_iterator.peek().is_none()
+            && label != EPS_LABEL
+            && label != NO_LABEL
+            && has_rho(state, &self.matcher, self.rho_label)?
+        {
+            matcher_iterator = self.matcher.iter(state, self.rho_label)?.peekable();
+            rho_match = Some(label);
+        }
+        Ok(IteratorRhoMatcher {
+            matcher_iterator,
+            state,
+            rho_label: self.rho_label,
+            rho_match,
+            match_type: self.match_type,
+            rewrite_both: self.rewrite_both,
+        })
+    }
+
+    fn final_weight(&self, state: StateId) -> Result<Option<W>> {
+        self.matcher.final_weight(state)
+    }
+
+    fn match_type(&self, test: bool) -> Result<MatchType> {
+        self.matcher.match_type(test)
+    }
+
+    fn flags(&self) -> MatcherFlags {
+        if self.rho_label == NO_LABEL || self.match_type == MatchType::MatchNone {
+            self.matcher.flags()
+        } else {
+            self.matcher.flags() | MatcherFlags::REQUIRE_MATCH
+        }
+    }
+
+    fn priority(&self, state: StateId) -> Result<usize> {
+        if has_rho(state, &self.matcher, self.rho_label)? {
+            Ok(REQUIRE_PRIORITY)
+        } else {
+            self.matcher.priority(state)
+        }
+    }
+
+    fn fst(&self) -> &B {
+        self.matcher.fst()
+    }
+}
+
+pub struct IteratorRhoMatcher<W: Semiring, I: Iterator<Item = IterItemMatcher<W>>> {
+    matcher_iterator: Peekable<I>,
+    state: StateId,
+    rho_label: Label,
+    rho_match: Option<Label>,
+    match_type: MatchType,
+    rewrite_both: bool,
+}
+
+impl<W, I> Iterator for IteratorRhoMatcher<W, I>
+where
+    W: Semiring,
+    I: Iterator<Item = IterItemMatcher<W>>,
+{
+    type Item = IterItemMatcher<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.matcher_iterator.next()?;
+        let rho_match = match self.rho_match {
+            Some(rho_match) => rho_match,
+            None => return Some(item),
+        };
+        let mut tr = item.into_tr(self.state, self.match_type).unwrap();
+        if self.rewrite_both {
+            if tr.ilabel == self.rho_label {
+                tr.ilabel = rho_match;
+            }
+            if tr.olabel == self.rho_label {
+                tr.olabel = rho_match;
+            }
+        } else if self.match_type == MatchType::MatchInput {
+            tr.ilabel = rho_match;
+        } else {
+            tr.olabel = rho_match;
+        }
+        Some(IterItemMatcher::Tr(tr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
+    use crate::algorithms::compose::matchers::SortedMatcher;
+    use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions};
+    use crate::algorithms::tr_compares::ILabelCompare;
+    use crate::algorithms::tr_sort;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::utils::acceptor;
+    use crate::FstPath;
+
+    const RHO: Label = 100;
+
+    fn compose_with_rho(
+        labels: &[Label],
+        rho_olabel: Label,
+        rewrite_mode: MatcherRewriteMode,
+    ) -> Result<Vec<FstPath<TropicalWeight>>> {
+        let query: VectorFst<TropicalWeight> = acceptor(labels, TropicalWeight::one());
+
+        // Label 1 costs 1.0 and any other label costs 2.0.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, RHO, rho_olabel, 2.0, 1)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        tr_sort(&mut fst, ILabelCompare {});
+
+        let rho_matcher = RhoMatcher::new(
+            MatchType::MatchInput,
+            RHO,
+            rewrite_mode,
+            Arc::new(SortedMatcher::new(fst.clone(), MatchType::MatchInput)?),
+        )?;
+        let compose_fst_op_opts = ComposeFstOpOptions::new(None, rho_matcher, None, None);
+        let compose_lazy = ComposeFst::<
+            _,                                                 // W
+            _,                                                 // F1
+            _,                                                 // F2
+            _,                                                 // B1
+            _,                                                 // B2
+            SortedMatcher<_, _, _>,                            // M1
+            RhoMatcher<_, _, _, SortedMatcher<_, _, _>>,       // M2
+            SequenceComposeFilterBuilder<_, _, _, _, _, _, _>, // CFB
+        >::new_with_options(query, fst, compose_fst_op_opts)?;
+        let composed: VectorFst<_> = compose_lazy.compute()?;
+        Ok(composed.paths_iter().collect())
+    }
+
+    #[test]
+    fn test_rho_matcher() -> Result<()> {
+        let mode = MatcherRewriteMode::MatcherRewriteAuto;
+        assert_eq!(
+            compose_with_rho(&[1], 7, mode)?,
+            vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0))]
+        );
+        assert_eq!(
+            compose_with_rho(&[5], 7, mode)?,
+            vec![FstPath::new(vec![5], vec![7], TropicalWeight::new(2.0))]
+        );
+        // Acceptor: both sides are rewritten.
+        assert_eq!(
+            compose_with_rho(&[5], RHO, mode)?,
+            vec![FstPath::new(vec![5], vec![5], TropicalWeight::new(2.0))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rho_matcher_rewrite_never() -> Result<()> {
+        // Only the matched side is rewritten.
+        let mode = MatcherRewriteMode::MatcherRewriteNever;
+        assert_eq!(
+            compose_with_rho(&[5], RHO, mode)?,
+            vec![FstPath::new(vec![5], vec![RHO], TropicalWeight::new(2.0))]
+        );
+        Ok(())
+    }
+}
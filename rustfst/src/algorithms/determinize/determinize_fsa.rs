@@ -1,5 +1,5 @@
 use crate::algorithms::determinize::divisors::CommonDivisor;
-use crate::algorithms::determinize::DeterminizeFsaOp;
+use crate::algorithms::determinize::{DeterminizeConfig, DeterminizeFsaOp};
 use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
@@ -62,7 +62,7 @@ where
     }
 
     fn properties(&self) -> FstProperties {
-        unimplemented!()
+        self.0.properties()
     }
 
     fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
@@ -145,10 +145,10 @@ where
     B: Borrow<F> + Debug,
     BT: Borrow<[W]> + PartialEq + Debug,
 {
-    pub fn new(fst: B, in_dist: Option<BT>, delta: f32) -> Result<Self> {
+    pub fn new(fst: B, in_dist: Option<BT>, config: DeterminizeConfig) -> Result<Self> {
         let isymt = fst.borrow().input_symbols().cloned();
         let osymt = fst.borrow().output_symbols().cloned();
-        let fst_op = DeterminizeFsaOp::new(fst, in_dist, delta)?;
+        let fst_op = DeterminizeFsaOp::new(fst, in_dist, config)?;
         let fst_cache = SimpleHashMapCache::default();
         let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
         Ok(DeterminizeFsa(lazy_fst, PhantomData))
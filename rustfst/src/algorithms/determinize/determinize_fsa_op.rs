@@ -1,23 +1,32 @@
 use std::borrow::Borrow;
 use std::collections::btree_map::Entry as EntryBTreeMap;
-use std::collections::hash_map::Entry as EntryHashMap;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
 
 use crate::algorithms::determinize::divisors::CommonDivisor;
 use crate::algorithms::determinize::{
-    DeterminizeElement, DeterminizeStateTable, DeterminizeStateTuple, DeterminizeTr, WeightedSubset,
+    DeterminizeConfig, DeterminizeElement, DeterminizeError, DeterminizeStateTable,
+    DeterminizeStateTuple, DeterminizeTr, WeightedSubset,
 };
 use crate::algorithms::lazy::FstOp;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::Fst;
-use crate::semirings::{DivideType, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::semirings::{WeaklyDivisibleSemiring, WeightQuantize};
 use crate::{Label, Semiring, StateId, Tr, Trs, TrsVec};
 
+/// Number of trs of the determinized FST, used to enforce `max_trs`. The trs of each state
+/// are only counted once, even if they are computed again, e.g. after being evicted from
+/// an `LruCache`.
+#[derive(Debug, Default)]
+struct TrsCount {
+    counted_states: Vec<bool>,
+    num_trs: usize,
+}
+
 #[derive(Debug)]
 pub struct DeterminizeFsaOp<W, F, CD, B, BT>
 where
@@ -30,6 +39,9 @@ where
     fst: B,
     state_table: DeterminizeStateTable<W, BT>,
     delta: f32,
+    max_states: Option<usize>,
+    max_trs: Option<usize>,
+    trs_count: Mutex<TrsCount>,
     ghost: PhantomData<(CD, F)>,
 }
 
@@ -82,7 +94,7 @@ where
         }
 
         for det_tr in label_map.values_mut() {
-            self.norm_tr(det_tr)?;
+            det_tr.normalize::<CD>(self.delta)?;
         }
 
         let mut trs = vec![];
@@ -95,6 +107,21 @@ where
             ));
         }
 
+        if let Some(max_trs) = self.max_trs {
+            let mut trs_count = self.trs_count.lock().unwrap();
+            let idx = state as usize;
+            if trs_count.counted_states.len() <= idx {
+                trs_count.counted_states.resize(idx + 1, false);
+            }
+            if !trs_count.counted_states[idx] {
+                trs_count.counted_states[idx] = true;
+                trs_count.num_trs += trs.len();
+            }
+            if trs_count.num_trs > max_trs {
+                return Err(DeterminizeError::TooManyTrs(max_trs).into());
+            }
+        }
+
         Ok(TrsVec(Arc::new(trs)))
     }
 
@@ -132,55 +159,29 @@ where
     B: Borrow<F> + Debug,
     BT: Borrow<[W]> + Debug + PartialEq,
 {
-    pub fn new(fst: B, in_dist: Option<BT>, delta: f32) -> Result<Self> {
+    pub fn new(fst: B, in_dist: Option<BT>, config: DeterminizeConfig) -> Result<Self> {
         if !fst.borrow().properties().contains(FstProperties::ACCEPTOR) {
             bail!("DeterminizeFsaImpl : expected acceptor as argument");
         }
         Ok(Self {
             fst,
             state_table: DeterminizeStateTable::new(in_dist),
-            delta,
+            delta: config.delta,
+            max_states: config.max_states,
+            max_trs: config.max_trs,
+            trs_count: Mutex::new(TrsCount::default()),
             ghost: PhantomData,
         })
     }
 
-    fn norm_tr(&self, det_tr: &mut DeterminizeTr<W>) -> Result<()> {
-        det_tr
-            .dest_tuple
-            .subset
-            .pairs
-            .sort_by(|a, b| a.state.cmp(&b.state));
-
-        for dest_elt in det_tr.dest_tuple.subset.pairs.iter() {
-            det_tr.weight = CD::common_divisor(&det_tr.weight, &dest_elt.weight)?;
-        }
-
-        let mut new_pairs = HashMap::new();
-        for x in &mut det_tr.dest_tuple.subset.pairs {
-            match new_pairs.entry(x.state) {
-                EntryHashMap::Vacant(e) => {
-                    e.insert(x.clone());
-                }
-                EntryHashMap::Occupied(mut e) => {
-                    e.get_mut().weight.plus_assign(&x.weight)?;
-                }
-            };
-        }
-
-        det_tr.dest_tuple.subset.pairs = new_pairs.values().cloned().collect();
-
-        for dest_elt in det_tr.dest_tuple.subset.pairs.iter_mut() {
-            dest_elt.weight = dest_elt
-                .weight
-                .divide(&det_tr.weight, DivideType::DivideLeft)?;
-            dest_elt.weight.quantize_assign(self.delta)?;
-        }
-
-        Ok(())
-    }
-
     fn find_state(&self, tuple: &DeterminizeStateTuple<W>) -> Result<StateId> {
-        self.state_table.find_id_from_ref(tuple)
+        let state = self.state_table.find_id_from_ref(tuple)?;
+        if let Some(max_states) = self.max_states {
+            if state as usize >= max_states {
+                return Err(DeterminizeError::TooManyStates(max_states).into());
+            }
+        }
+        Ok(state)
     }
 
     pub fn out_dist(self) -> Result<Vec<W>> {
@@ -194,3 +195,30 @@ where
             .collect::<Result<Vec<_>>>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::determinize::DefaultCommonDivisor;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::TropicalWeight;
+
+    #[test]
+    fn test_max_trs_recomputed_states_counted_once() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> =
+            crate::utils::acceptor(&[1, 2, 3], TropicalWeight::one());
+        let config = DeterminizeConfig::default().with_max_trs(Some(3));
+        let op = DeterminizeFsaOp::<_, VectorFst<_>, DefaultCommonDivisor, _, Vec<_>>::new(
+            &fst, None, config,
+        )?;
+        let start = op.compute_start()?.unwrap();
+        // As if the trs were evicted from the cache and computed again.
+        for _ in 0..5 {
+            assert_eq!(op.compute_trs(start)?.len(), 1);
+        }
+        assert_eq!(op.compute_trs(1)?.len(), 1);
+        assert_eq!(op.compute_trs(2)?.len(), 1);
+        assert_eq!(op.compute_trs(start)?.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,325 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::determinize_fst_op::DeterminizeFstOp;
+use crate::algorithms::determinize::DeterminizeConfig;
+use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::{WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{StateId, SymbolTable, TrsVec};
+
+type InnerLazyFst<W, F, B> = LazyFst<W, DeterminizeFstOp<W, F, B>, SimpleHashMapCache<W>>;
+
+/// Determinizes a weighted transducer. The result is an equivalent FST that has the property
+/// that no state has two transitions with the same input label. This version is a delayed FST :
+/// the determinized FST is only expanded when its states are visited, e.g. during a composition.
+///
+/// Acceptors are determinized directly. Transducers are encoded as acceptors over Gallic
+/// weights before being determinized, the type of Gallic weight depending on the
+/// `DeterminizeType` of the config, and decoded back on the fly.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::algorithms::determinize::{DeterminizeConfig, DeterminizeFst, DeterminizeType};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::FstPath;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, 3, 1.0, 1)?;
+/// fst.emplace_tr(0, 1, 4, 2.0, 2)?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let config = DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeDisambiguate);
+/// let determinized_fst = DeterminizeFst::new_with_config(fst, config)?;
+///
+/// // Only the best output is kept for each input.
+/// let paths: Vec<_> = determinized_fst.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1], vec![3], TropicalWeight::new(1.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct DeterminizeFst<W, F, B>(InnerLazyFst<W, F, B>)
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static;
+
+impl<W, F, B> DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    pub fn new(fst: B) -> Result<Self> {
+        Self::new_with_config(fst, DeterminizeConfig::default())
+    }
+
+    pub fn new_with_config(fst: B, config: DeterminizeConfig) -> Result<Self> {
+        let isymt = fst.borrow().input_symbols().cloned();
+        let osymt = fst.borrow().output_symbols().cloned();
+        let fst_op = DeterminizeFstOp::new(fst, config)?;
+        let fst_cache = SimpleHashMapCache::default();
+        let lazy_fst = LazyFst::from_op_and_cache(fst_op, fst_cache, isymt, osymt);
+        Ok(DeterminizeFst(lazy_fst))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F2: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F2> {
+        self.0.compute()
+    }
+}
+
+impl<W, F, B> CoreFst<W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F, B> StateIterator<'a> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    type Iter = <InnerLazyFst<W, F, B> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F, B> FstIterator<'a, W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    type FstIter = <InnerLazyFst<W, F, B> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F, B> Fst<W> for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F, B> Debug for DeterminizeFst<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::determinize::{
+        determinize_with_config, DeterminizeError, DeterminizeType,
+    };
+    use crate::algorithms::isomorphic;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    fn check_lazy_determinize(
+        fst: &VectorFst<TropicalWeight>,
+        det_type: DeterminizeType,
+    ) -> Result<()> {
+        let config = DeterminizeConfig::default().with_det_type(det_type);
+        let static_fst: VectorFst<_> = determinize_with_config(fst, config)?;
+        let lazy_fst: VectorFst<_> =
+            DeterminizeFst::new_with_config(fst.clone(), config)?.compute()?;
+        assert!(isomorphic(&static_fst, &lazy_fst)?);
+        Ok(())
+    }
+
+    /// Transducer whose output labels are delayed until the final states.
+    fn delayed_outputs() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 3, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 4, 2.0, 2)?;
+        fst.emplace_tr(1, 2, 0, 1.0, 3)?;
+        fst.emplace_tr(2, 2, 0, 3.0, 3)?;
+        fst.set_final(3, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_determinize_fst_functional() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 3, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 3, 2.0, 2)?;
+        fst.emplace_tr(1, 2, 4, 1.0, 3)?;
+        fst.emplace_tr(2, 2, 4, 0.5, 3)?;
+        fst.emplace_tr(2, 3, 5, 0.5, 3)?;
+        fst.set_final(3, TropicalWeight::one())?;
+        check_lazy_determinize(&fst, DeterminizeType::DeterminizeFunctional)
+    }
+
+    #[test]
+    fn test_determinize_fst_non_functional() -> Result<()> {
+        let fst = delayed_outputs()?;
+        check_lazy_determinize(&fst, DeterminizeType::DeterminizeNonFunctional)?;
+
+        // The output labels are emitted on the final transitions to a super-final state.
+        let lazy_fst = DeterminizeFst::new_with_config(
+            fst,
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeNonFunctional),
+        )?;
+        let result: VectorFst<_> = lazy_fst.compute()?;
+        assert!(result.states_iter().any(|s| result
+            .get_trs(s)
+            .unwrap()
+            .iter()
+            .any(|tr| tr.ilabel == 0 && tr.olabel != 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_fst_disambiguate() -> Result<()> {
+        let fst = delayed_outputs()?;
+        check_lazy_determinize(&fst, DeterminizeType::DeterminizeDisambiguate)?;
+
+        let lazy_fst = DeterminizeFst::new_with_config(
+            fst,
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeDisambiguate),
+        )?;
+        let result: VectorFst<_> = lazy_fst.compute()?;
+        let paths: Vec<_> = result.paths_iter().collect();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].olabels, vec![3]);
+        assert_eq!(paths[0].weight, TropicalWeight::new(2.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_fst_acceptor() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 1, 2.0, 2)?;
+        fst.emplace_tr(1, 2, 2, 1.0, 2)?;
+        fst.set_final(2, TropicalWeight::one())?;
+        check_lazy_determinize(&fst, DeterminizeType::DeterminizeFunctional)
+    }
+
+    #[test]
+    fn test_determinize_fst_max_states() -> Result<()> {
+        let fst = delayed_outputs()?;
+        let config = DeterminizeConfig::default()
+            .with_det_type(DeterminizeType::DeterminizeNonFunctional)
+            .with_max_states(Some(2));
+        let lazy_fst = DeterminizeFst::new_with_config(fst, config)?;
+        let err = lazy_fst.compute::<VectorFst<_>>().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeterminizeError>(),
+            Some(&DeterminizeError::TooManyStates(2))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_fst_is_lazy() -> Result<()> {
+        let fst = delayed_outputs()?;
+        let lazy_fst = DeterminizeFst::new(fst)?;
+        let start = lazy_fst.start().unwrap();
+        let trs = lazy_fst.get_trs(start)?;
+        assert_eq!(trs.len(), 1);
+        assert_eq!(trs[0].ilabel, 1);
+        assert!(lazy_fst
+            .properties()
+            .contains(FstProperties::I_DETERMINISTIC));
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::divisors::CommonDivisor;
+use crate::algorithms::determinize::{
+    DefaultCommonDivisor, DeterminizeConfig, DeterminizeFsa, DeterminizeType, GallicCommonDivisor,
+};
+use crate::algorithms::factor_weight::factor_iterators::{
+    GallicFactor, GallicFactorMin, GallicFactorRestrict,
+};
+use crate::algorithms::factor_weight::{FactorIterator, FactorWeightFst};
+use crate::algorithms::lazy::{FstOp, LazyFst, SimpleHashMapCache, StateTable};
+use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
+use crate::algorithms::{FinalTr, WeightConverter};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{CoreFst, Fst};
+use crate::semirings::{
+    GallicWeight, GallicWeightMin, GallicWeightRestrict, Semiring, SemiringProperties,
+    WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::{Label, StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+/// Converts the trs of an FST into Gallic weights when they are requested.
+#[derive(Debug)]
+pub struct ToGallicOp<W, F, B, G>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+{
+    fst: B,
+    properties: FstProperties,
+    ghost: PhantomData<(W, F, G)>,
+}
+
+impl<W, F, B, G> ToGallicOp<W, F, B, G>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    G: Semiring,
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    fn new(fst: B) -> Self {
+        // All the trs are turned into acceptor trs.
+        let properties =
+            ToGallicConverter {}.properties(fst.borrow().properties()) | FstProperties::ACCEPTOR;
+        Self {
+            fst,
+            properties,
+            ghost: PhantomData,
+        }
+    }
+}
+
+impl<W, F, B, G> FstOp<G> for ToGallicOp<W, F, B, G>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F> + Debug,
+    G: Semiring,
+    ToGallicConverter: WeightConverter<W, G>,
+{
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start())
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<G>> {
+        let mut converter = ToGallicConverter {};
+        let trs = self
+            .fst
+            .borrow()
+            .get_trs(id)?
+            .trs()
+            .iter()
+            .map(|tr| converter.tr_map(tr))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<G>> {
+        match self.fst.borrow().final_weight(id)? {
+            Some(weight) => {
+                let final_tr = FinalTr {
+                    ilabel: EPS_LABEL,
+                    olabel: EPS_LABEL,
+                    weight,
+                };
+                Ok(Some(ToGallicConverter {}.final_tr_map(&final_tr)?.weight))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
+
+type ToGallicFst<W, F, B, G> = LazyFst<G, ToGallicOp<W, F, B, G>, SimpleHashMapCache<G>>;
+type GallicDeterminizeFsa<W, F, B, G> = DeterminizeFsa<
+    G,
+    ToGallicFst<W, F, B, G>,
+    GallicCommonDivisor,
+    ToGallicFst<W, F, B, G>,
+    Vec<G>,
+>;
+type FactoredGallicFsa<W, F, B, G, FI> =
+    FactorWeightFst<G, GallicDeterminizeFsa<W, F, B, G>, GallicDeterminizeFsa<W, F, B, G>, FI>;
+
+/// Determinized FSA from which the result is read : the input FST itself if it is an
+/// acceptor, otherwise its Gallic encoding with the final weights factored.
+#[derive(Debug)]
+enum DeterminizedFsa<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    Acceptor(DeterminizeFsa<W, F, DefaultCommonDivisor, B, Vec<W>>),
+    Functional(FactoredGallicFsa<W, F, B, GallicWeightRestrict<W>, GallicFactorRestrict<W>>),
+    NonFunctional(FactoredGallicFsa<W, F, B, GallicWeight<W>, GallicFactor<W>>),
+    Disambiguate(FactoredGallicFsa<W, F, B, GallicWeightMin<W>, GallicFactorMin<W>>),
+}
+
+fn factored_gallic_fsa<W, F, B, G, FI>(
+    fst: B,
+    config: DeterminizeConfig,
+) -> Result<FactoredGallicFsa<W, F, B, G, FI>>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+    G: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    FI: FactorIterator<G>,
+    ToGallicConverter: WeightConverter<W, G>,
+    GallicCommonDivisor: CommonDivisor<G>,
+{
+    let (isymt, osymt) = (
+        fst.borrow().input_symbols().cloned(),
+        fst.borrow().output_symbols().cloned(),
+    );
+    let fsa = LazyFst::from_op_and_cache(
+        ToGallicOp::new(fst),
+        SimpleHashMapCache::default(),
+        isymt,
+        osymt,
+    );
+    let determinized_fsa = DeterminizeFsa::new(fsa, None, config)?;
+    FactorWeightFst::new(determinized_fsa, config.factor_weight_options())
+}
+
+#[derive(Debug)]
+pub struct DeterminizeFstOp<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    fsa: DeterminizedFsa<W, F, B>,
+    /// States of the determinized FSA. `None` is the super-final state added when a final
+    /// weight still carries an output label once factored.
+    state_table: StateTable<Option<StateId>>,
+    superfinal_label: Label,
+    properties: FstProperties,
+}
+
+impl<W, F, B> DeterminizeFstOp<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    pub fn new(fst: B, config: DeterminizeConfig) -> Result<Self> {
+        if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
+            bail!("DeterminizeFst : weight must be left distributive")
+        }
+        let iprops = fst.borrow().properties();
+        let fsa = if iprops.contains(FstProperties::ACCEPTOR) {
+            DeterminizedFsa::Acceptor(DeterminizeFsa::new(fst, None, config)?)
+        } else {
+            match config.det_type {
+                DeterminizeType::DeterminizeFunctional => {
+                    DeterminizedFsa::Functional(factored_gallic_fsa(fst, config)?)
+                }
+                DeterminizeType::DeterminizeNonFunctional => {
+                    DeterminizedFsa::NonFunctional(factored_gallic_fsa(fst, config)?)
+                }
+                DeterminizeType::DeterminizeDisambiguate => {
+                    if !W::properties().contains(SemiringProperties::PATH) {
+                        bail!("DeterminizeFst : weight needs to have the path property to disambiguate output")
+                    }
+                    DeterminizedFsa::Disambiguate(factored_gallic_fsa(fst, config)?)
+                }
+            }
+        };
+        Ok(Self {
+            fsa,
+            state_table: StateTable::new(),
+            superfinal_label: config.subsequential_label,
+            properties: config.properties(iprops),
+        })
+    }
+
+    fn find_state(&self, state: Option<StateId>) -> StateId {
+        self.state_table.find_id(state)
+    }
+
+    /// Converts the trs of a Gallic FSA state back to the semiring `W`, with a transition to
+    /// the super-final state if the final weight can't be represented as is.
+    fn gallic_trs<G, F2>(&self, fsa: &F2, state: StateId) -> Result<Vec<Tr<W>>>
+    where
+        G: Semiring,
+        F2: Fst<G>,
+        FromGallicConverter: WeightConverter<G, W>,
+    {
+        let mut converter = FromGallicConverter {
+            superfinal_label: self.superfinal_label,
+        };
+        let mut trs = vec![];
+        for tr in fsa.get_trs(state)?.trs() {
+            let mut tr = converter.tr_map(tr)?;
+            tr.nextstate = self.find_state(Some(tr.nextstate));
+            trs.push(tr);
+        }
+        if let Some(final_tr) = self.gallic_final_tr(fsa, state)? {
+            if final_tr.ilabel != EPS_LABEL || final_tr.olabel != EPS_LABEL {
+                trs.push(Tr::new(
+                    final_tr.ilabel,
+                    final_tr.olabel,
+                    final_tr.weight,
+                    self.find_state(None),
+                ));
+            }
+        }
+        Ok(trs)
+    }
+
+    fn gallic_final_tr<G, F2>(&self, fsa: &F2, state: StateId) -> Result<Option<FinalTr<W>>>
+    where
+        G: Semiring,
+        F2: Fst<G>,
+        FromGallicConverter: WeightConverter<G, W>,
+    {
+        let mut converter = FromGallicConverter {
+            superfinal_label: self.superfinal_label,
+        };
+        match fsa.final_weight(state)? {
+            Some(weight) => Ok(Some(converter.final_tr_map(&FinalTr {
+                ilabel: EPS_LABEL,
+                olabel: EPS_LABEL,
+                weight,
+            })?)),
+            None => Ok(None),
+        }
+    }
+
+    fn gallic_final_weight<G, F2>(&self, fsa: &F2, state: StateId) -> Result<Option<W>>
+    where
+        G: Semiring,
+        F2: Fst<G>,
+        FromGallicConverter: WeightConverter<G, W>,
+    {
+        Ok(self
+            .gallic_final_tr(fsa, state)?
+            .filter(|final_tr| final_tr.ilabel == EPS_LABEL && final_tr.olabel == EPS_LABEL)
+            .map(|final_tr| final_tr.weight))
+    }
+}
+
+impl<W, F, B> FstOp<W> for DeterminizeFstOp<W, F, B>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+    F: Fst<W> + 'static,
+    B: Borrow<F> + Debug + 'static,
+{
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        let start = match &self.fsa {
+            DeterminizedFsa::Acceptor(fsa) => fsa.start(),
+            DeterminizedFsa::Functional(fsa) => fsa.start(),
+            DeterminizedFsa::NonFunctional(fsa) => fsa.start(),
+            DeterminizedFsa::Disambiguate(fsa) => fsa.start(),
+        };
+        Ok(start.map(|s| self.find_state(Some(s))))
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<W>> {
+        let state = match self.state_table.find_tuple(id) {
+            Some(state) => state,
+            None => return Ok(TrsVec::default()),
+        };
+        let trs = match &self.fsa {
+            DeterminizedFsa::Acceptor(fsa) => fsa
+                .get_trs(state)?
+                .trs()
+                .iter()
+                .map(|tr| {
+                    let mut tr = tr.clone();
+                    tr.nextstate = self.find_state(Some(tr.nextstate));
+                    tr
+                })
+                .collect(),
+            DeterminizedFsa::Functional(fsa) => self.gallic_trs(fsa, state)?,
+            DeterminizedFsa::NonFunctional(fsa) => self.gallic_trs(fsa, state)?,
+            DeterminizedFsa::Disambiguate(fsa) => self.gallic_trs(fsa, state)?,
+        };
+        Ok(TrsVec(Arc::new(trs)))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        let state = match self.state_table.find_tuple(id) {
+            Some(state) => state,
+            None => return Ok(Some(W::one())),
+        };
+        match &self.fsa {
+            DeterminizedFsa::Acceptor(fsa) => fsa.final_weight(state),
+            DeterminizedFsa::Functional(fsa) => self.gallic_final_weight(fsa, state),
+            DeterminizedFsa::NonFunctional(fsa) => self.gallic_final_weight(fsa, state),
+            DeterminizedFsa::Disambiguate(fsa) => self.gallic_final_weight(fsa, state),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
@@ -19,7 +19,7 @@ use crate::semirings::SemiringProperties;
 use crate::semirings::{
     GallicWeight, GallicWeightMin, GallicWeightRestrict, WeaklyDivisibleSemiring, WeightQuantize,
 };
-use crate::{EPS_LABEL, KDELTA};
+use crate::{Label, EPS_LABEL, KDELTA};
 
 pub fn determinize_with_distance<W, F1, F2>(
     ifst: &F1,
@@ -34,11 +34,13 @@ where
     if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
         bail!("determinize_fsa : weight must be left distributive")
     }
-    let fst = DeterminizeFsa::<_, F1, DefaultCommonDivisor, _, _>::new(ifst, Some(in_dist), delta)?;
+    let config = DeterminizeConfig::default().with_delta(delta);
+    let fst =
+        DeterminizeFsa::<_, F1, DefaultCommonDivisor, _, _>::new(ifst, Some(in_dist), config)?;
     fst.compute_with_distance()
 }
 
-pub fn determinize_fsa<W, F1, F2, CD>(fst_in: &F1, delta: f32) -> Result<F2>
+pub fn determinize_fsa<W, F1, F2, CD>(fst_in: &F1, config: DeterminizeConfig) -> Result<F2>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize,
     F1: Fst<W>,
@@ -48,11 +50,11 @@ where
     if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
         bail!("determinize_fsa : weight must be left distributive")
     }
-    let det_fsa: DeterminizeFsa<W, F1, CD, _, Vec<W>> = DeterminizeFsa::new(fst_in, None, delta)?;
+    let det_fsa: DeterminizeFsa<W, F1, CD, _, Vec<W>> = DeterminizeFsa::new(fst_in, None, config)?;
     det_fsa.compute()
 }
 
-pub fn determinize_fst<W, F1, F2>(fst_in: &F1, det_type: DeterminizeType, delta: f32) -> Result<F2>
+pub fn determinize_fst<W, F1, F2>(fst_in: &F1, config: DeterminizeConfig) -> Result<F2>
 where
     W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
     F1: ExpandedFst<W>,
@@ -60,19 +62,12 @@ where
 {
     let mut to_gallic = ToGallicConverter {};
     let mut from_gallic = FromGallicConverter {
-        superfinal_label: EPS_LABEL,
+        superfinal_label: config.subsequential_label,
     };
 
-    let factor_opts = FactorWeightOptions {
-        delta: KDELTA,
-        mode: FactorWeightType::FACTOR_FINAL_WEIGHTS,
-        final_ilabel: EPS_LABEL,
-        final_olabel: EPS_LABEL,
-        increment_final_ilabel: false,
-        increment_final_olabel: false,
-    };
+    let factor_opts = config.factor_weight_options();
 
-    match det_type {
+    match config.det_type {
         DeterminizeType::DeterminizeDisambiguate => {
             if !W::properties().contains(SemiringProperties::PATH) {
                 bail!("determinize : weight needs to have the path property to disambiguate output")
@@ -80,7 +75,7 @@ where
             let fsa: VectorFst<GallicWeightMin<W>> =
                 weight_convert(fst_in.borrow(), &mut to_gallic)?;
             let determinized_fsa: VectorFst<GallicWeightMin<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
+                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, config)?;
             let factored_determinized_fsa: VectorFst<GallicWeightMin<W>> =
                 factor_weight::<_, VectorFst<GallicWeightMin<W>>, _, _, GallicFactorMin<W>>(
                     &determinized_fsa,
@@ -92,7 +87,7 @@ where
             let fsa: VectorFst<GallicWeightRestrict<W>> =
                 weight_convert(fst_in.borrow(), &mut to_gallic)?;
             let determinized_fsa: VectorFst<GallicWeightRestrict<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
+                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, config)?;
             let factored_determinized_fsa: VectorFst<GallicWeightRestrict<W>> =
                 factor_weight::<
                     _,
@@ -106,7 +101,7 @@ where
         DeterminizeType::DeterminizeNonFunctional => {
             let fsa: VectorFst<GallicWeight<W>> = weight_convert(fst_in.borrow(), &mut to_gallic)?;
             let determinized_fsa: VectorFst<GallicWeight<W>> =
-                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, delta)?;
+                determinize_fsa::<_, VectorFst<_>, _, GallicCommonDivisor>(&fsa, config)?;
             let factored_determinized_fsa: VectorFst<GallicWeight<W>> =
                 factor_weight::<_, VectorFst<GallicWeight<W>>, _, _, GallicFactor<W>>(
                     &determinized_fsa,
@@ -121,11 +116,33 @@ where
 pub struct DeterminizeConfig {
     pub delta: f32,
     pub det_type: DeterminizeType,
+    /// Maximum number of states of the determinized automaton. A
+    /// `DeterminizeError::TooManyStates` is returned when it is exceeded.
+    ///
+    /// For a transducer, it bounds the intermediate determinization over Gallic weights : the
+    /// resulting FST can have more states, holding the output labels left at the end of paths.
+    pub max_states: Option<usize>,
+    /// Maximum number of trs of the determinized automaton. A
+    /// `DeterminizeError::TooManyTrs` is returned when it is exceeded.
+    ///
+    /// For a transducer, it bounds the intermediate determinization over Gallic weights : the
+    /// resulting FST can have more trs, emitting the output labels left at the end of paths.
+    pub max_trs: Option<usize>,
+    /// Input label of the final transitions emitting the output labels that remain at the end
+    /// of a path when determinizing a transducer.
+    pub subsequential_label: Label,
+    /// Whether to increment `subsequential_label` for each final transition leaving a state, so
+    /// that the result of a non-functional transducer is still deterministic.
+    pub increment_subsequential_label: bool,
 }
 
 impl DeterminizeConfig {
     pub fn new(delta: f32, det_type: DeterminizeType) -> Self {
-        Self { delta, det_type }
+        Self {
+            delta,
+            det_type,
+            ..Self::default()
+        }
     }
 
     pub fn with_delta(self, delta: f32) -> Self {
@@ -135,6 +152,50 @@ impl DeterminizeConfig {
     pub fn with_det_type(self, det_type: DeterminizeType) -> Self {
         Self { det_type, ..self }
     }
+
+    pub fn with_max_states(self, max_states: Option<usize>) -> Self {
+        Self { max_states, ..self }
+    }
+
+    pub fn with_max_trs(self, max_trs: Option<usize>) -> Self {
+        Self { max_trs, ..self }
+    }
+
+    pub fn with_subsequential_label(self, subsequential_label: Label) -> Self {
+        Self {
+            subsequential_label,
+            ..self
+        }
+    }
+
+    pub fn with_increment_subsequential_label(self, increment_subsequential_label: bool) -> Self {
+        Self {
+            increment_subsequential_label,
+            ..self
+        }
+    }
+
+    pub(crate) fn factor_weight_options(&self) -> FactorWeightOptions {
+        FactorWeightOptions {
+            delta: KDELTA,
+            mode: FactorWeightType::FACTOR_FINAL_WEIGHTS,
+            final_ilabel: self.subsequential_label,
+            final_olabel: self.subsequential_label,
+            increment_final_ilabel: self.increment_subsequential_label,
+            increment_final_olabel: self.increment_subsequential_label,
+        }
+    }
+
+    pub(crate) fn properties(&self, inprops: FstProperties) -> FstProperties {
+        let distinct_psubsequential_labels = self.det_type
+            != DeterminizeType::DeterminizeNonFunctional
+            || self.increment_subsequential_label;
+        determinize_properties(
+            inprops,
+            self.subsequential_label != EPS_LABEL,
+            distinct_psubsequential_labels,
+        )
+    }
 }
 
 impl Default for DeterminizeConfig {
@@ -142,6 +203,10 @@ impl Default for DeterminizeConfig {
         Self {
             delta: KDELTA,
             det_type: DeterminizeType::DeterminizeFunctional,
+            max_states: None,
+            max_trs: None,
+            subsequential_label: EPS_LABEL,
+            increment_subsequential_label: false,
         }
     }
 }
@@ -175,32 +240,28 @@ where
     F1: ExpandedFst<W>,
     F2: MutableFst<W> + AllocableFst<W>,
 {
-    let delta = config.delta;
-    let det_type = config.det_type;
     let iprops = fst_in.borrow().properties();
     let mut fst_res: F2 = if iprops.contains(FstProperties::ACCEPTOR) {
-        determinize_fsa::<_, F1, _, DefaultCommonDivisor>(fst_in, delta)?
+        determinize_fsa::<_, F1, _, DefaultCommonDivisor>(fst_in, config)?
     } else {
-        determinize_fst(fst_in, det_type, delta)?
+        determinize_fst(fst_in, config)?
     };
 
-    let distinct_psubsequential_labels = !(det_type == DeterminizeType::DeterminizeNonFunctional);
-    fst_res.set_properties(determinize_properties(
-        iprops,
-        false,
-        distinct_psubsequential_labels,
-    ));
+    fst_res.set_properties(config.properties(iprops));
     fst_res.set_symts_from_fst(fst_in.borrow());
     Ok(fst_res)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::algorithms::determinize::DeterminizeError;
     use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, StateIterator};
     use crate::semirings::TropicalWeight;
     use crate::tr::Tr;
     use crate::Semiring;
     use crate::SymbolTable;
+    use crate::Trs;
     use proptest::prelude::any;
     use proptest::proptest;
     use std::sync::Arc;
@@ -269,6 +330,100 @@ mod tests {
         Ok(())
     }
 
+    fn ambiguous_transducer() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 2, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 3, 2.0, 2)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        fst.set_final(2, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_determinize_max_states() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> =
+            crate::utils::acceptor(&[1, 2, 3], TropicalWeight::one());
+        let config = DeterminizeConfig::default().with_max_states(Some(3));
+        let err = determinize_with_config::<_, _, VectorFst<_>>(&fst, config).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeterminizeError>(),
+            Some(&DeterminizeError::TooManyStates(3))
+        );
+
+        let config = DeterminizeConfig::default().with_max_states(Some(4));
+        let det: VectorFst<_> = determinize_with_config(&fst, config)?;
+        assert_eq!(det.num_states(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_max_trs() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> =
+            crate::utils::acceptor(&[1, 2, 3], TropicalWeight::one());
+        let config = DeterminizeConfig::default().with_max_trs(Some(2));
+        let err = determinize_with_config::<_, _, VectorFst<_>>(&fst, config).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeterminizeError>(),
+            Some(&DeterminizeError::TooManyTrs(2))
+        );
+
+        let config = DeterminizeConfig::default().with_max_trs(Some(3));
+        let det: VectorFst<_> = determinize_with_config(&fst, config)?;
+        assert_eq!(det.num_trs(0)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_max_states_transducer() -> Result<()> {
+        let fst = ambiguous_transducer()?;
+        let config = DeterminizeConfig::default()
+            .with_det_type(DeterminizeType::DeterminizeNonFunctional)
+            .with_max_states(Some(1));
+        let err = determinize_with_config::<_, _, VectorFst<_>>(&fst, config).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeterminizeError>(),
+            Some(&DeterminizeError::TooManyStates(1))
+        );
+
+        // The limit applies to the determinization over Gallic weights, which has 2 states.
+        let config = config.with_max_states(Some(2));
+        let det: VectorFst<_> = determinize_with_config(&fst, config)?;
+        assert!(det.num_states() > 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_determinize_subsequential_label() -> Result<()> {
+        let fst = ambiguous_transducer()?;
+        let config = DeterminizeConfig::default()
+            .with_det_type(DeterminizeType::DeterminizeNonFunctional)
+            .with_subsequential_label(100)
+            .with_increment_subsequential_label(true);
+        let det: VectorFst<_> = determinize_with_config(&fst, config)?;
+
+        let mut final_trs: Vec<_> = det
+            .states_iter()
+            .flat_map(|s| det.get_trs(s).unwrap().trs().to_vec())
+            .filter(|tr| tr.ilabel >= 100)
+            .map(|tr| (tr.ilabel, tr.olabel))
+            .collect();
+        final_trs.sort();
+        assert_eq!(final_trs, vec![(100, 2), (101, 3)]);
+        assert!(det.properties().contains(FstProperties::I_DETERMINISTIC));
+
+        let mut known = FstProperties::empty();
+        let props = crate::fst_properties::compute_fst_properties(
+            &det,
+            FstProperties::I_DETERMINISTIC,
+            &mut known,
+            false,
+        )?;
+        assert!(props.contains(FstProperties::I_DETERMINISTIC));
+        Ok(())
+    }
+
     proptest! {
         #[test]
         fn test_proptest_determinize_keeps_symts(mut fst in any::<VectorFst::<TropicalWeight>>()) {
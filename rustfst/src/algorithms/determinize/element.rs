@@ -1,3 +1,10 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::divisors::CommonDivisor;
+use crate::semirings::{DivideType, WeaklyDivisibleSemiring, WeightQuantize};
 use crate::{Label, Semiring, StateId, Tr};
 
 #[derive(PartialEq, Eq, Clone, Hash, PartialOrd, Debug)]
@@ -52,3 +59,38 @@ impl<W: Semiring> DeterminizeTr<W> {
         }
     }
 }
+
+impl<W: WeaklyDivisibleSemiring + WeightQuantize> DeterminizeTr<W> {
+    /// Merges the elements of the destination subset sharing the same state, sets the weight
+    /// of the tr to their common divisor and divides the residual weights by it.
+    pub fn normalize<CD: CommonDivisor<W>>(&mut self, delta: f32) -> Result<()> {
+        self.dest_tuple.subset.pairs.sort_by_key(|a| a.state);
+
+        for dest_elt in self.dest_tuple.subset.pairs.iter() {
+            self.weight = CD::common_divisor(&self.weight, &dest_elt.weight)?;
+        }
+
+        let mut new_pairs = HashMap::new();
+        for x in &mut self.dest_tuple.subset.pairs {
+            match new_pairs.entry(x.state) {
+                Entry::Vacant(e) => {
+                    e.insert(x.clone());
+                }
+                Entry::Occupied(mut e) => {
+                    e.get_mut().weight.plus_assign(&x.weight)?;
+                }
+            };
+        }
+
+        self.dest_tuple.subset.pairs = new_pairs.values().cloned().collect();
+
+        for dest_elt in self.dest_tuple.subset.pairs.iter_mut() {
+            dest_elt.weight = dest_elt
+                .weight
+                .divide(&self.weight, DivideType::DivideLeft)?;
+            dest_elt.weight.quantize_assign(delta)?;
+        }
+
+        Ok(())
+    }
+}
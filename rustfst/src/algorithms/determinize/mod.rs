@@ -1,14 +1,22 @@
+use std::fmt;
+
 pub(self) use determinize_fsa::DeterminizeFsa;
 pub(self) use determinize_fsa_op::DeterminizeFsaOp;
+pub use determinize_fst::DeterminizeFst;
 pub use determinize_static::{
     determinize, determinize_with_config, determinize_with_distance, DeterminizeConfig,
 };
-pub(self) use divisors::{DefaultCommonDivisor, GallicCommonDivisor};
-pub(self) use element::{DeterminizeElement, DeterminizeStateTuple, DeterminizeTr, WeightedSubset};
-pub(self) use state_table::DeterminizeStateTable;
+pub(crate) use divisors::DefaultCommonDivisor;
+pub(self) use divisors::GallicCommonDivisor;
+pub(crate) use element::{
+    DeterminizeElement, DeterminizeStateTuple, DeterminizeTr, WeightedSubset,
+};
+pub(crate) use state_table::DeterminizeStateTable;
 
 mod determinize_fsa;
 mod determinize_fsa_op;
+mod determinize_fst;
+mod determinize_fst_op;
 mod determinize_static;
 mod divisors;
 mod element;
@@ -25,3 +33,34 @@ pub enum DeterminizeType {
     /// of ambiguous outputs.
     DeterminizeDisambiguate,
 }
+
+/// Error returned when the determinization exceeds one of the limits of its `DeterminizeConfig`.
+/// It can be retrieved from the `anyhow::Error` with `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeterminizeError {
+    /// The determinized automaton has more states than the `max_states` contained. For a
+    /// transducer, this is the automaton over Gallic weights.
+    TooManyStates(usize),
+    /// The determinized automaton has more trs than the `max_trs` contained. For a transducer,
+    /// this is the automaton over Gallic weights.
+    TooManyTrs(usize),
+}
+
+impl fmt::Display for DeterminizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeterminizeError::TooManyStates(max_states) => write!(
+                f,
+                "Determinize : the number of states exceeds the limit of {}",
+                max_states
+            ),
+            DeterminizeError::TooManyTrs(max_trs) => write!(
+                f,
+                "Determinize : the number of trs exceeds the limit of {}",
+                max_trs
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeterminizeError {}
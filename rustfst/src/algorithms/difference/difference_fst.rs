@@ -0,0 +1,229 @@
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::complement::ComplementFst;
+use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
+use crate::algorithms::compose::matchers::GenericMatcher;
+use crate::algorithms::compose::ComposeFst;
+use crate::algorithms::intersect::is_acceptor;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::{
+    AllocableFst, CoreFst, ExpandedFst, Fst, FstIterator, MutableFst, StateIterator,
+};
+use crate::semirings::Semiring;
+use crate::{Label, StateId, SymbolTable, Trs, TrsVec, EPS_LABEL};
+
+type CFst<W, F2, B2> = ComplementFst<W, F2, B2>;
+type CFstPtr<W, F2, B2> = Arc<CFst<W, F2, B2>>;
+type InnerComposeFst<W, F1, F2, B1, B2> = ComposeFst<
+    W,
+    F1,
+    CFst<W, F2, B2>,
+    B1,
+    CFstPtr<W, F2, B2>,
+    GenericMatcher<W, F1, B1>,
+    GenericMatcher<W, CFst<W, F2, B2>, CFstPtr<W, F2, B2>>,
+    SequenceComposeFilterBuilder<
+        W,
+        F1,
+        CFst<W, F2, B2>,
+        B1,
+        CFstPtr<W, F2, B2>,
+        GenericMatcher<W, F1, B1>,
+        GenericMatcher<W, CFst<W, F2, B2>, CFstPtr<W, F2, B2>>,
+    >,
+>;
+
+/// Computes the difference between two FSAs. This version is a delayed FST.
+/// Only strings that are in the first automaton but not in second are retained
+/// in the result.
+///
+/// The first argument must be an acceptor; the second argument must be an
+/// unweighted, epsilon-free, deterministic acceptor. All the states of the first
+/// FST are visited on creation to collect the labels to complement.
+pub struct DifferenceFst<W, F1, F2, B1, B2>(InnerComposeFst<W, F1, F2, B1, B2>)
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + 'static;
+
+impl<W, F1, F2, B1, B2> DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + 'static,
+{
+    pub fn new(fst1: B1, fst2: B2) -> Result<Self> {
+        if !is_acceptor(fst1.borrow())? {
+            bail!("DifferenceFst : 1st argument not an acceptor");
+        }
+        let mut known = FstProperties::empty();
+        let props2 = compute_fst_properties(
+            fst2.borrow(),
+            FstProperties::ACCEPTOR
+                | FstProperties::NOT_ACCEPTOR
+                | FstProperties::UNWEIGHTED
+                | FstProperties::WEIGHTED,
+            &mut known,
+            true,
+        )?;
+        if !props2.contains(FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED) {
+            bail!("DifferenceFst : 2nd argument not an unweighted acceptor");
+        }
+
+        // Only the labels read by the first FST need to be complemented.
+        let mut alphabet = BTreeSet::new();
+        for s in fst1.borrow().states_iter() {
+            for tr in fst1.borrow().get_trs(s)?.trs() {
+                if tr.olabel != EPS_LABEL {
+                    alphabet.insert(tr.olabel);
+                }
+            }
+        }
+        let alphabet: Vec<Label> = alphabet.into_iter().collect();
+
+        let cfst = ComplementFst::new(fst2, alphabet)?;
+        let compose_fst = ComposeFst::new_auto(fst1, Arc::new(cfst))?;
+        Ok(DifferenceFst(compose_fst))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F3: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F3> {
+        self.0.compute()
+    }
+}
+
+impl<W, F1, F2, B1, B2> CoreFst<W> for DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + 'static,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F1, F2, B1, B2> StateIterator<'a> for DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'a,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone + 'a,
+    B2: Borrow<F2> + 'static,
+{
+    type Iter = <InnerComposeFst<W, F1, F2, B1, B2> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F1, F2, B1, B2> FstIterator<'a, W> for DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'a,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone + 'a,
+    B2: Borrow<F2> + 'static,
+{
+    type FstIter = <InnerComposeFst<W, F1, F2, B1, B2> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F1, F2, B1, B2> Fst<W> for DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'static,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone + 'static,
+    B2: Borrow<F2> + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F1, F2, B1, B2> Debug for DifferenceFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: ExpandedFst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::connect;
+use crate::algorithms::difference::DifferenceFst;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+
+/// Computes the difference between two FSAs. Only strings that are in the first
+/// automaton but not in the second are retained in the result, with their weights
+/// in the first automaton.
+///
+/// The first argument must be an acceptor; the second argument must be an
+/// unweighted, epsilon-free, deterministic acceptor. An error is returned otherwise.
+/// The second FST is copied to be shared with the delayed complement.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use anyhow::Result;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::algorithms::union::union;
+/// # use rustfst::algorithms::difference::difference;
+/// # use rustfst::fst_traits::Fst;
+/// # use rustfst::FstPath;
+/// # fn main() -> Result<()> {
+/// let mut lexicon : VectorFst<TropicalWeight> = fst![1, 2; 0.5];
+/// let word : VectorFst<TropicalWeight> = fst![1, 3; 1.5];
+/// union(&mut lexicon, &word)?;
+/// let blacklist : VectorFst<TropicalWeight> = fst![1, 2];
+///
+/// let filtered : VectorFst<TropicalWeight> = difference(&lexicon, &blacklist)?;
+/// let paths : Vec<_> = filtered.paths_iter().collect();
+/// assert_eq!(paths, vec![fst_path![1, 3; 1.5]]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn difference<W, F1, F2, F3>(fst1: &F1, fst2: &F2) -> Result<F3>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W> + 'static,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    let difference_fst = DifferenceFst::<W, F1, F2, _, _>::new(fst1, Arc::new(fst2.clone()))?;
+    let mut ofst: F3 = difference_fst.compute()?;
+    connect(&mut ofst)?;
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::union::{union, UnionFst};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::utils::{acceptor, transducer};
+    use crate::{FstPath, Tr};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_difference() -> Result<()> {
+        let mut fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3], TropicalWeight::new(1.0));
+        let fst_b: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        let fst_c: VectorFst<TropicalWeight> = acceptor(&[4], TropicalWeight::new(3.0));
+        union(&mut fst1, &fst_b)?;
+        union(&mut fst1, &fst_c)?;
+
+        // Accepts every string starting with 1, 2.
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        fst2.add_states(3);
+        fst2.set_start(0)?;
+        fst2.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst2.add_tr(1, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        for label in 1..5 {
+            fst2.add_tr(2, Tr::new(label, label, TropicalWeight::one(), 2))?;
+        }
+        fst2.set_final(2, TropicalWeight::one())?;
+
+        let fst_res: VectorFst<TropicalWeight> = difference(&fst1, &fst2)?;
+        let paths: HashSet<_> = fst_res.paths_iter().collect();
+
+        let mut paths_ref = HashSet::new();
+        paths_ref.insert(FstPath::new(vec![4], vec![4], TropicalWeight::new(3.0)));
+        assert_eq!(paths, paths_ref);
+        Ok(())
+    }
+
+    #[test]
+    fn test_difference_fst_lazy_first_fst() -> Result<()> {
+        let fst_a: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(1.0));
+        let fst_b: VectorFst<TropicalWeight> = acceptor(&[3], TropicalWeight::new(2.0));
+        let lazy = Arc::new(UnionFst::new(fst_a, fst_b)?);
+        let fst2: VectorFst<TropicalWeight> = acceptor(&[3], TropicalWeight::one());
+
+        let fst_res: VectorFst<TropicalWeight> =
+            DifferenceFst::<_, UnionFst<_, VectorFst<_>>, VectorFst<_>, _, _>::new(
+                lazy,
+                Arc::new(fst2),
+            )?
+            .compute()?;
+        let paths: Vec<_> = fst_res.paths_iter().collect();
+        assert_eq!(
+            paths,
+            vec![FstPath::new(
+                vec![1, 2],
+                vec![1, 2],
+                TropicalWeight::new(1.0)
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_difference_empty_second_fst() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(1.0));
+        let fst2 = VectorFst::<TropicalWeight>::new();
+        let fst_res: VectorFst<TropicalWeight> = difference(&fst1, &fst2)?;
+        let paths: Vec<_> = fst_res.paths_iter().collect();
+        assert_eq!(
+            paths,
+            vec![FstPath::new(
+                vec![1, 2],
+                vec![1, 2],
+                TropicalWeight::new(1.0)
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_difference_invalid_inputs() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::one());
+
+        let transducer_fst: VectorFst<TropicalWeight> =
+            transducer(&[1], &[2], TropicalWeight::one());
+        assert!(difference::<_, _, _, VectorFst<_>>(&transducer_fst, &fst1).is_err());
+        assert!(difference::<_, _, _, VectorFst<_>>(&fst1, &transducer_fst).is_err());
+
+        let weighted_fst: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        assert!(difference::<_, _, _, VectorFst<_>>(&fst1, &weighted_fst).is_err());
+
+        let mut non_det_fst = VectorFst::<TropicalWeight>::new();
+        non_det_fst.add_states(2);
+        non_det_fst.set_start(0)?;
+        non_det_fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 0))?;
+        non_det_fst.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        non_det_fst.set_final(1, TropicalWeight::one())?;
+        assert!(difference::<_, _, _, VectorFst<_>>(&fst1, &non_det_fst).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod difference_fst;
+mod difference_static;
+
+pub use difference_fst::DifferenceFst;
+pub use difference_static::difference;
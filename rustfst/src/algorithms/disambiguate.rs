@@ -0,0 +1,481 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+
+use crate::algorithms::determinize::{
+    DefaultCommonDivisor, DeterminizeElement, DeterminizeStateTable, DeterminizeStateTuple,
+    DeterminizeTr, WeightedSubset,
+};
+use crate::algorithms::equivalent::UnionFind;
+use crate::algorithms::{connect, fst_convert_from_ref};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::{AllocableFst, CoreFst, ExpandedFst, MutableFst};
+use crate::semirings::{SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{Label, Semiring, StateId, Tr, Trs, KDELTA};
+
+/// Configuration for disambiguation.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct DisambiguateConfig {
+    delta: f32,
+}
+
+impl Default for DisambiguateConfig {
+    fn default() -> Self {
+        Self { delta: KDELTA }
+    }
+}
+
+impl DisambiguateConfig {
+    pub fn new(delta: f32) -> Self {
+        Self { delta }
+    }
+}
+
+/// Returns an equivalent unambiguous weighted acceptor : no two successful paths share
+/// the same label sequence. Unlike determinization, several trs with the same label can
+/// leave a state, which keeps the result small on inputs that determinize can't handle.
+///
+/// The algorithm is the one of Mohri & Riley, "A Disambiguation Algorithm for Weighted
+/// Automata". The input must be an acceptor and the weight must be left distributive.
+/// Epsilon transitions are treated as regular symbols.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::algorithms::disambiguate;
+/// # use rustfst::FstPath;
+/// # fn main() -> Result<()> {
+/// // Two paths accepting [1, 2].
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(4);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+/// fst.emplace_tr(0, 1, 1, 3.0, 2)?;
+/// fst.emplace_tr(1, 2, 2, 1.0, 3)?;
+/// fst.emplace_tr(2, 2, 2, 2.0, 3)?;
+/// fst.set_final(3, TropicalWeight::one())?;
+///
+/// let unambiguous: VectorFst<_> = disambiguate(&fst)?;
+///
+/// let paths: Vec<_> = unambiguous.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1, 2], vec![1, 2], TropicalWeight::new(2.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn disambiguate<W, F1, F2>(ifst: &F1) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    disambiguate_with_config(ifst, DisambiguateConfig::default())
+}
+
+/// Returns an equivalent unambiguous weighted acceptor. See `disambiguate`.
+pub fn disambiguate_with_config<W, F1, F2>(ifst: &F1, config: DisambiguateConfig) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    if !W::properties().contains(SemiringProperties::LEFT_SEMIRING) {
+        bail!("Disambiguate : weight must be left distributive")
+    }
+    let mut known = FstProperties::empty();
+    let props = compute_fst_properties(ifst, FstProperties::ACCEPTOR, &mut known, true)?;
+    if !props.contains(FstProperties::ACCEPTOR) {
+        bail!("Disambiguate : expected acceptor as argument")
+    }
+
+    let mut fst: VectorFst<W> = fst_convert_from_ref(ifst);
+    connect(&mut fst)?;
+
+    let mut disambiguator = Disambiguator::default();
+    let mut ofst: F2 = disambiguator.pre_disambiguate(&fst, config.delta)?;
+    disambiguator.find_ambiguities(&ofst)?;
+    disambiguator.remove_splits(&mut ofst)?;
+    disambiguator.mark_ambiguities();
+    disambiguator.remove_ambiguities(&mut ofst)?;
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+/// Identifies a tr by its source state and its position. `None` stands for the final weight.
+type TrId = (StateId, Option<usize>);
+
+fn ordered_pair(s1: StateId, s2: StateId) -> (StateId, StateId) {
+    if s1 <= s2 {
+        (s1, s2)
+    } else {
+        (s2, s1)
+    }
+}
+
+/// Computes the pairs of states (s1, s2) with s1 <= s2 such that a same label sequence
+/// leads from the start state to both s1 and s2, and a same label sequence leads from
+/// both s1 and s2 to a final state.
+fn common_relation<W: Semiring>(fst: &VectorFst<W>) -> Result<HashSet<(StateId, StateId)>> {
+    let mut predecessors: HashMap<(StateId, StateId), Vec<(StateId, StateId)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    if let Some(start) = fst.start() {
+        predecessors.insert((start, start), vec![]);
+        queue.push_back((start, start));
+    }
+    while let Some((s1, s2)) = queue.pop_front() {
+        for tr1 in fst.get_trs(s1)?.trs() {
+            for tr2 in fst.get_trs(s2)?.trs() {
+                if tr1.ilabel != tr2.ilabel {
+                    continue;
+                }
+                let pair = ordered_pair(tr1.nextstate, tr2.nextstate);
+                match predecessors.get_mut(&pair) {
+                    Some(preds) => preds.push((s1, s2)),
+                    None => {
+                        predecessors.insert(pair, vec![(s1, s2)]);
+                        queue.push_back(pair);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut relation = HashSet::new();
+    for &(s1, s2) in predecessors.keys() {
+        if fst.is_final(s1)? && fst.is_final(s2)? && relation.insert((s1, s2)) {
+            queue.push_back((s1, s2));
+        }
+    }
+    while let Some(pair) = queue.pop_front() {
+        for &pred in &predecessors[&pair] {
+            if relation.insert(pred) {
+                queue.push_back(pred);
+            }
+        }
+    }
+    Ok(relation)
+}
+
+#[derive(Default)]
+struct Disambiguator {
+    // Input state on which each state of the pre-disambiguated FST is built.
+    head: Vec<StateId>,
+    // Pairs of states reachable by a same label sequence.
+    coreachable: HashSet<(StateId, StateId)>,
+    queue: VecDeque<(StateId, StateId)>,
+    // Pairs of ambiguous trs. The first one is removed unless the second one already is.
+    candidates: Vec<(TrId, TrId)>,
+    // States split only because of weight quantization.
+    merge: Option<UnionFind>,
+    ambiguous: HashSet<TrId>,
+}
+
+impl Disambiguator {
+    /// Weighted subset construction where the subsets are further split according to their
+    /// head state : an element only goes into the subsets whose head is in relation with it.
+    fn pre_disambiguate<W, F>(&mut self, fst: &VectorFst<W>, delta: f32) -> Result<F>
+    where
+        W: WeaklyDivisibleSemiring + WeightQuantize,
+        F: MutableFst<W>,
+    {
+        let mut ofst = F::new();
+        let start = match fst.start() {
+            Some(s) => s,
+            None => return Ok(ofst),
+        };
+        let relation = common_relation(fst)?;
+        let state_table = DeterminizeStateTable::<W, Vec<W>>::new(None);
+
+        let start_tuple = DeterminizeStateTuple {
+            subset: WeightedSubset::from_vec(vec![DeterminizeElement::new(start, W::one())]),
+            filter_state: start,
+        };
+        let ostart = state_table.find_id_from_ref(&start_tuple)?;
+        ofst.add_state();
+        ofst.set_start(ostart)?;
+        self.head.push(start);
+
+        let mut state = 0;
+        while (state as usize) < self.head.len() {
+            let tuple = state_table.find_tuple(state);
+            let head = tuple.filter_state;
+
+            if fst.is_final(head)? {
+                let mut final_weight = W::zero();
+                for elt in tuple.subset.iter() {
+                    if let Some(w) = fst.final_weight(elt.state)? {
+                        final_weight.plus_assign(elt.weight.times(w)?)?;
+                    }
+                }
+                if !final_weight.is_zero() {
+                    ofst.set_final(state, final_weight)?;
+                }
+            }
+
+            // One destination subset per distinct (label, nextstate) of the head state.
+            let mut label_map: BTreeMap<(Label, StateId), DeterminizeTr<W>> = BTreeMap::new();
+            for tr in fst.get_trs(head)?.trs() {
+                label_map
+                    .entry((tr.ilabel, tr.nextstate))
+                    .or_insert_with(|| DeterminizeTr::from_tr(tr, tr.nextstate));
+            }
+            for elt in tuple.subset.iter() {
+                for tr in fst.get_trs(elt.state)?.trs() {
+                    let weight = elt.weight.times(&tr.weight)?;
+                    let range = label_map.range_mut((tr.ilabel, 0)..=(tr.ilabel, StateId::MAX));
+                    for (&(_, dest_head), det_tr) in range {
+                        if relation.contains(&ordered_pair(dest_head, tr.nextstate)) {
+                            det_tr
+                                .dest_tuple
+                                .subset
+                                .pairs
+                                .push(DeterminizeElement::new(tr.nextstate, weight.clone()));
+                        }
+                    }
+                }
+            }
+
+            for det_tr in label_map.values_mut() {
+                det_tr.normalize::<DefaultCommonDivisor>(delta)?;
+                let nextstate = state_table.find_id_from_ref(&det_tr.dest_tuple)?;
+                if nextstate as usize == self.head.len() {
+                    ofst.add_state();
+                    self.head.push(det_tr.dest_tuple.filter_state);
+                }
+                ofst.add_tr(
+                    state,
+                    Tr::new(det_tr.label, det_tr.label, det_tr.weight.clone(), nextstate),
+                )?;
+            }
+            state += 1;
+        }
+        Ok(ofst)
+    }
+
+    /// Finds the pairs of ambiguous trs of the pre-disambiguated FST, i.e. the trs reaching
+    /// a same state from two distinct states reachable by a same label sequence.
+    fn find_ambiguities<W: Semiring, F: ExpandedFst<W>>(&mut self, fst: &F) -> Result<()> {
+        let start = match fst.start() {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        self.coreachable.insert((start, start));
+        self.queue.push_back((start, start));
+        while let Some((s1, s2)) = self.queue.pop_front() {
+            self.find_ambiguous_pairs(fst, s1, s2)?;
+        }
+        Ok(())
+    }
+
+    fn find_ambiguous_pairs<W: Semiring, F: ExpandedFst<W>>(
+        &mut self,
+        fst: &F,
+        s1: StateId,
+        s2: StateId,
+    ) -> Result<()> {
+        let trs2 = fst.get_trs(s2)?;
+        for (pos1, tr1) in fst.get_trs(s1)?.trs().iter().enumerate() {
+            for (pos2, tr2) in trs2.trs().iter().enumerate() {
+                if tr1.ilabel != tr2.ilabel {
+                    continue;
+                }
+                if s1 != s2 && tr1.nextstate == tr2.nextstate {
+                    self.insert_candidate((s1, Some(pos1)), (s2, Some(pos2)));
+                }
+                let pair = ordered_pair(tr1.nextstate, tr2.nextstate);
+                if self.coreachable.insert(pair) {
+                    if pair.0 != pair.1 && self.head[pair.0 as usize] == self.head[pair.1 as usize]
+                    {
+                        let num_states = fst.num_states();
+                        self.merge
+                            .get_or_insert_with(|| UnionFind::new(num_states))
+                            .union(pair.0 as usize, pair.1 as usize);
+                    } else {
+                        self.queue.push_back(pair);
+                    }
+                }
+            }
+        }
+        if s1 != s2 && fst.is_final(s1)? && fst.is_final(s2)? {
+            self.insert_candidate((s1, None), (s2, None));
+        }
+        Ok(())
+    }
+
+    fn insert_candidate(&mut self, tr1: TrId, tr2: TrId) {
+        if self.head[tr1.0 as usize] > self.head[tr2.0 as usize] {
+            self.candidates.push((tr1, tr2));
+        } else {
+            self.candidates.push((tr2, tr1));
+        }
+    }
+
+    /// Merges the states that were only split because of weight quantization, which
+    /// would otherwise lead to spurious ambiguities.
+    fn remove_splits<W: Semiring, F: MutableFst<W>>(&mut self, fst: &mut F) -> Result<()> {
+        let mut merge = match self.merge.take() {
+            Some(merge) => merge,
+            None => return Ok(()),
+        };
+        for state in 0..fst.num_states() as StateId {
+            let mut trs = fst.tr_iter_mut(state)?;
+            for pos in 0..trs.len() {
+                let nextstate = trs.get(pos).unwrap().nextstate;
+                let merged = merge.find(nextstate as usize) as StateId;
+                if merged != nextstate {
+                    trs.set_nextstate(pos, merged)?;
+                }
+            }
+        }
+
+        self.coreachable.clear();
+        self.candidates.clear();
+        self.find_ambiguities(fst)?;
+        if self.merge.is_some() {
+            bail!("Disambiguate : unable to remove spurious ambiguities")
+        }
+        Ok(())
+    }
+
+    fn mark_ambiguities(&mut self) {
+        let head = &self.head;
+        let key = |tr: &TrId| (head[tr.0 as usize], tr.0, tr.1);
+        self.candidates.sort_by_key(|c| key(&c.0));
+        for (tr1, tr2) in self.candidates.drain(..) {
+            if !self.ambiguous.contains(&tr2) {
+                self.ambiguous.insert(tr1);
+            }
+        }
+        self.coreachable.clear();
+    }
+
+    /// Redirects the ambiguous trs to a dead state and trims the result.
+    fn remove_ambiguities<W: Semiring, F: MutableFst<W>>(&mut self, fst: &mut F) -> Result<()> {
+        if self.ambiguous.is_empty() {
+            return Ok(());
+        }
+        let dead = fst.add_state();
+        for (state, pos) in self.ambiguous.drain() {
+            match pos {
+                Some(pos) => fst.tr_iter_mut(state)?.set_nextstate(pos, dead)?,
+                None => fst.delete_final_weight(state)?,
+            }
+        }
+        connect(fst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::isomorphic;
+    use crate::fst_traits::Fst;
+    use crate::semirings::{LogWeight, TropicalWeight};
+    use crate::FstPath;
+
+    fn sorted_paths<W: Semiring, F: Fst<W>>(fst: &F) -> Vec<FstPath<W>> {
+        let mut paths: Vec<_> = fst.paths_iter().collect();
+        paths.sort_by_key(|p| p.ilabels.clone());
+        paths
+    }
+
+    fn ambiguous_fst<W: Semiring<Type = f32> + From<f32>>() -> Result<VectorFst<W>> {
+        // [1, 2] is accepted by two paths and [1, 3] by one path.
+        let mut fst = VectorFst::<W>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 1, 2.0, 2)?;
+        fst.emplace_tr(1, 2, 2, 1.0, 3)?;
+        fst.emplace_tr(2, 2, 2, 1.0, 4)?;
+        fst.emplace_tr(2, 3, 3, 3.0, 4)?;
+        fst.set_final(3, 0.5)?;
+        fst.set_final(4, W::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_disambiguate_tropical() -> Result<()> {
+        let fst = ambiguous_fst::<TropicalWeight>()?;
+        let unambiguous: VectorFst<_> = disambiguate(&fst)?;
+        assert_eq!(
+            sorted_paths(&unambiguous),
+            vec![
+                FstPath::new(vec![1, 2], vec![1, 2], TropicalWeight::new(2.5)),
+                FstPath::new(vec![1, 3], vec![1, 3], TropicalWeight::new(5.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguate_log() -> Result<()> {
+        let fst = ambiguous_fst::<LogWeight>()?;
+        let unambiguous: VectorFst<_> = disambiguate(&fst)?;
+        let paths = sorted_paths(&unambiguous);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].ilabels, vec![1, 2]);
+        let expected = LogWeight::new(2.5).plus(LogWeight::new(3.0))?;
+        assert!(paths[0].weight.approx_equal(expected, KDELTA));
+        assert_eq!(paths[1].ilabels, vec![1, 3]);
+        assert!(paths[1].weight.approx_equal(LogWeight::new(5.0), KDELTA));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguate_keeps_unambiguous_fst() -> Result<()> {
+        // Not deterministic but unambiguous : the result is not determinized.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 1, 2.0, 2)?;
+        fst.emplace_tr(1, 2, 2, 1.0, 3)?;
+        fst.emplace_tr(2, 3, 3, 1.0, 3)?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let unambiguous: VectorFst<_> = disambiguate(&fst)?;
+        assert!(isomorphic(&unambiguous, &fst)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguate_cycle() -> Result<()> {
+        // Both states accept 1*, so every string is accepted by two paths.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 1, 1, 2.0, 2)?;
+        fst.emplace_tr(1, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(2, 1, 1, 1.0, 2)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        fst.set_final(2, TropicalWeight::one())?;
+
+        let unambiguous: VectorFst<_> = disambiguate(&fst)?;
+        let mut known = FstProperties::empty();
+        let props = compute_fst_properties(
+            &unambiguous,
+            FstProperties::I_DETERMINISTIC,
+            &mut known,
+            false,
+        )?;
+        assert!(props.contains(FstProperties::I_DETERMINISTIC));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguate_transducer_fails() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 2, 1.0, 1)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        assert!(disambiguate::<_, _, VectorFst<_>>(&fst).is_err());
+        Ok(())
+    }
+}
@@ -1,8 +1,6 @@
-use std::cell::RefCell;
-
 use anyhow::{Context, Result};
 
-use crate::algorithms::encode::{EncodeTable, EncodeTableMut, EncodeType};
+use crate::algorithms::encode::{EncodeTable, EncodeType};
 use crate::algorithms::{FinalTr, MapFinalAction, TrMapper};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::MutableFst;
@@ -13,10 +11,8 @@ struct EncodeMapper<W: Semiring> {
 }
 
 impl<W: Semiring> EncodeMapper<W> {
-    pub fn new(encode_type: EncodeType) -> Self {
-        EncodeMapper {
-            encode_table: EncodeTable(RefCell::new(EncodeTableMut::new(encode_type))),
-        }
+    pub fn from_table(encode_table: EncodeTable<W>) -> Self {
+        EncodeMapper { encode_table }
     }
 
     pub fn encode_weights(&self) -> bool {
@@ -94,7 +90,18 @@ where
     W: Semiring,
     F: MutableFst<W>,
 {
-    let mut encode_mapper = EncodeMapper::new(encode_type);
+    encode_with_table(fst, EncodeTable::new(encode_type))
+}
+
+/// Same as `encode` but starts from an existing `EncodeTable` which is extended
+/// with the new pairs or triples found in the FST. Useful to encode several
+/// FSTs with the same encoding.
+pub fn encode_with_table<W, F>(fst: &mut F, encode_table: EncodeTable<W>) -> Result<EncodeTable<W>>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    let mut encode_mapper = EncodeMapper::from_table(encode_table);
     fst.tr_map(&mut encode_mapper)
         .with_context(|| format_err!("Error calling TrMap with EncodeMapper."))?;
     Ok(encode_mapper.encode_table)
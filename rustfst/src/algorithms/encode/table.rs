@@ -22,6 +22,12 @@ pub struct EncodeTableMut<W: Semiring> {
 
 pub struct EncodeTable<W: Semiring>(pub RefCell<EncodeTableMut<W>>);
 
+impl<W: Semiring> EncodeTable<W> {
+    pub fn new(encode_type: EncodeType) -> Self {
+        EncodeTable(RefCell::new(EncodeTableMut::new(encode_type)))
+    }
+}
+
 impl<W: Semiring> EncodeTableMut<W> {
     pub fn new(encode_type: EncodeType) -> Self {
         EncodeTableMut {
@@ -0,0 +1,175 @@
+use anyhow::Result;
+
+use crate::algorithms::factor_weight::factor_iterators::GallicFactorRight;
+use crate::algorithms::factor_weight::{factor_weight, FactorWeightOptions, FactorWeightType};
+use crate::algorithms::rm_epsilon::rm_epsilon;
+use crate::algorithms::weight_converters::{FromGallicConverter, ToGallicConverter};
+use crate::algorithms::{fst_convert_from_ref, invert, weight_convert};
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{GallicWeightRight, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{EPS_LABEL, KDELTA};
+
+/// Type of epsilon normalization.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub enum EpsNormalizeType {
+    /// Input epsilons are pushed after the non-epsilon input labels.
+    EpsNormInput,
+    /// Output epsilons are pushed after the non-epsilon output labels.
+    EpsNormOutput,
+}
+
+/// Returns an equivalent FST that is epsilon-normalized. An FST is input epsilon-normalized
+/// if, on each path, the input epsilons only appear after all the non-epsilon input labels.
+/// Output epsilon-normalization is defined similarly.
+///
+/// The input FST must be functional and the weight must be left distributive. The
+/// result can be determinized with `DeterminizeType::DeterminizeFunctional`.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::algorithms::{epsnormalize, EpsNormalizeType};
+/// # use rustfst::EPS_LABEL;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, EPS_LABEL, 1, 1.0, 1)?;
+/// fst.emplace_tr(1, 2, 3, 2.0, 2)?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let normalized: VectorFst<_> = epsnormalize(&fst, EpsNormalizeType::EpsNormInput)?;
+///
+/// let mut expected = VectorFst::<TropicalWeight>::new();
+/// expected.add_states(3);
+/// expected.set_start(0)?;
+/// expected.emplace_tr(0, 2, 1, 3.0, 1)?;
+/// expected.emplace_tr(1, EPS_LABEL, 3, TropicalWeight::one(), 2)?;
+/// expected.set_final(2, TropicalWeight::one())?;
+/// assert_eq!(normalized, expected);
+/// # Ok(())
+/// # }
+/// ```
+pub fn epsnormalize<W, F1, F2>(ifst: &F1, eps_norm_type: EpsNormalizeType) -> Result<F2>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    // The right gallic semiring is used because epsilon removal requires a right
+    // distributive weight.
+    let mut to_gallic = ToGallicConverter {};
+    let mut gfst: VectorFst<GallicWeightRight<W>> = match eps_norm_type {
+        EpsNormalizeType::EpsNormInput => weight_convert(ifst, &mut to_gallic)?,
+        EpsNormalizeType::EpsNormOutput => {
+            let mut inverted: VectorFst<W> = fst_convert_from_ref(ifst);
+            invert(&mut inverted);
+            weight_convert(&inverted, &mut to_gallic)?
+        }
+    };
+
+    rm_epsilon(&mut gfst)?;
+
+    let factor_opts = FactorWeightOptions {
+        delta: KDELTA,
+        mode: FactorWeightType::FACTOR_FINAL_WEIGHTS | FactorWeightType::FACTOR_ARC_WEIGHTS,
+        final_ilabel: EPS_LABEL,
+        final_olabel: EPS_LABEL,
+        increment_final_ilabel: false,
+        increment_final_olabel: false,
+    };
+    let fwfst: VectorFst<GallicWeightRight<W>> =
+        factor_weight::<_, VectorFst<_>, _, _, GallicFactorRight<W>>(&gfst, factor_opts)?;
+
+    let mut from_gallic = FromGallicConverter {
+        superfinal_label: EPS_LABEL,
+    };
+    let mut ofst: F2 = weight_convert(&fwfst, &mut from_gallic)?;
+    if eps_norm_type == EpsNormalizeType::EpsNormOutput {
+        invert(&mut ofst);
+    }
+    ofst.set_symts_from_fst(ifst);
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::determinize::{
+        determinize_with_config, DeterminizeConfig, DeterminizeType,
+    };
+    use crate::fst_traits::{CoreFst, Fst};
+    use crate::semirings::TropicalWeight;
+    use crate::{FstPath, StateId, Trs};
+
+    fn sorted_paths<F: Fst<TropicalWeight>>(fst: &F) -> Vec<FstPath<TropicalWeight>> {
+        let mut paths: Vec<_> = fst.paths_iter().collect();
+        paths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        paths
+    }
+
+    fn check_input_eps_normalized<F: Fst<TropicalWeight>>(
+        fst: &F,
+        state: StateId,
+        seen_eps: bool,
+    ) -> Result<()> {
+        for tr in fst.get_trs(state)?.trs() {
+            assert!(!seen_eps || tr.ilabel == EPS_LABEL);
+            check_input_eps_normalized(fst, tr.nextstate, seen_eps || tr.ilabel == EPS_LABEL)?;
+        }
+        Ok(())
+    }
+
+    fn functional_fst() -> Result<VectorFst<TropicalWeight>> {
+        // Two paths : 0:1 2:3 4:0 and 5:1 0:6
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, EPS_LABEL, 1, 1.0, 1)?;
+        fst.emplace_tr(1, 2, 3, 2.0, 2)?;
+        fst.emplace_tr(2, 4, EPS_LABEL, 3.0, 4)?;
+        fst.emplace_tr(0, 5, 1, 0.5, 3)?;
+        fst.emplace_tr(3, EPS_LABEL, 6, 0.5, 4)?;
+        fst.set_final(4, 1.0)?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_epsnormalize_input() -> Result<()> {
+        let fst = functional_fst()?;
+        let normalized: VectorFst<_> = epsnormalize(&fst, EpsNormalizeType::EpsNormInput)?;
+        check_input_eps_normalized(&normalized, normalized.start().unwrap(), false)?;
+        assert_eq!(sorted_paths(&normalized), sorted_paths(&fst));
+
+        let det: VectorFst<_> = determinize_with_config(
+            &normalized,
+            DeterminizeConfig::default().with_det_type(DeterminizeType::DeterminizeFunctional),
+        )?;
+        assert_eq!(sorted_paths(&det), sorted_paths(&fst));
+        Ok(())
+    }
+
+    #[test]
+    fn test_epsnormalize_output() -> Result<()> {
+        let mut fst = functional_fst()?;
+        invert(&mut fst);
+        let mut normalized: VectorFst<_> = epsnormalize(&fst, EpsNormalizeType::EpsNormOutput)?;
+        assert_eq!(sorted_paths(&normalized), sorted_paths(&fst));
+        invert(&mut normalized);
+        check_input_eps_normalized(&normalized, normalized.start().unwrap(), false)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_epsnormalize_empty() -> Result<()> {
+        let fst = VectorFst::<TropicalWeight>::new();
+        let normalized: VectorFst<_> = epsnormalize(&fst, EpsNormalizeType::EpsNormInput)?;
+        assert_eq!(normalized.start(), None);
+        Ok(())
+    }
+}
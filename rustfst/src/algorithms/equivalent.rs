@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+
+use crate::algorithms::encode::{encode_with_table, EncodeTable, EncodeType};
+use crate::algorithms::tr_mappers::QuantizeMapper;
+use crate::algorithms::{
+    connect, fst_convert_from_ref, push_weights_with_config, tr_map, PushWeightsConfig,
+    ReweightType,
+};
+use crate::fst_impls::VectorFst;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::{CoreFst, ExpandedFst};
+use crate::semirings::{Semiring, WeaklyDivisibleSemiring, WeightQuantize};
+use crate::{StateId, Trs, KDELTA};
+
+/// Configuration for equivalence checks.
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+pub struct EquivalentConfig {
+    delta: f32,
+}
+
+impl Default for EquivalentConfig {
+    fn default() -> Self {
+        Self { delta: KDELTA }
+    }
+}
+
+impl EquivalentConfig {
+    pub fn new(delta: f32) -> Self {
+        Self { delta }
+    }
+}
+
+/// Disjoint sets over the states of both FSTs. The states of the second FST
+/// are shifted by the number of states of the first one.
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    pub(crate) fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    pub(crate) fn find(&mut self, mut x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Path compression
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    pub(crate) fn union(&mut self, x: usize, y: usize) {
+        let root_x = self.find(x);
+        let root_y = self.find(y);
+        self.parent[root_y] = root_x;
+    }
+}
+
+fn check_equivalent_properties<W, F>(fst: &F, position: &str) -> Result<()>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+{
+    let needed =
+        FstProperties::NO_EPSILONS | FstProperties::I_DETERMINISTIC | FstProperties::ACCEPTOR;
+    let mut known = FstProperties::empty();
+    let props = compute_fst_properties(fst, needed, &mut known, true)?;
+    if !props.contains(needed) {
+        bail!(
+            "Equivalent : {} argument not an epsilon-free deterministic acceptor",
+            position
+        );
+    }
+    Ok(())
+}
+
+/// Weights are pushed towards the initial state and quantized so that
+/// equivalent FSTs end up with the same weights on their transitions.
+fn normalize_weights<W, F>(fst: &F, delta: f32) -> Result<VectorFst<W>>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F: ExpandedFst<W>,
+{
+    let mut ofst: VectorFst<W> = fst_convert_from_ref(fst);
+    connect(&mut ofst)?;
+    push_weights_with_config(
+        &mut ofst,
+        ReweightType::ReweightToInitial,
+        PushWeightsConfig::default().with_delta(delta),
+    )?;
+    tr_map(&mut ofst, &QuantizeMapper::new(delta))?;
+    Ok(ofst)
+}
+
+/// Determine if two deterministic, epsilon-free acceptors are equivalent,
+/// i.e. if they accept the same strings with the same weights.
+///
+/// The weights are pushed towards the initial state and the pairs
+/// (label, weight) are encoded as single labels with a table shared by
+/// both FSTs. The states of the resulting unweighted automata are then
+/// merged with a union-find structure, following Hopcroft and Karp.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use anyhow::Result;
+/// # use rustfst::algorithms::equivalent;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Tr;
+/// # fn main() -> Result<()> {
+/// let fst1: VectorFst<TropicalWeight> = fst![1, 2; 3.0];
+///
+/// let mut fst2 = VectorFst::<TropicalWeight>::new();
+/// fst2.add_states(3);
+/// fst2.set_start(0)?;
+/// fst2.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst2.add_tr(1, Tr::new(2, 2, 1.0, 2))?;
+/// fst2.set_final(2, 1.0)?;
+///
+/// assert!(equivalent(&fst1, &fst2)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn equivalent<W, F1, F2>(fst1: &F1, fst2: &F2) -> Result<bool>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    equivalent_with_config(fst1, fst2, EquivalentConfig::default())
+}
+
+/// Determine, with configurable comparison delta, if two deterministic,
+/// epsilon-free acceptors are equivalent.
+pub fn equivalent_with_config<W, F1, F2>(
+    fst1: &F1,
+    fst2: &F2,
+    config: EquivalentConfig,
+) -> Result<bool>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    check_equivalent_properties(fst1, "1st")?;
+    check_equivalent_properties(fst2, "2nd")?;
+
+    let mut efst1 = normalize_weights(fst1, config.delta)?;
+    let mut efst2 = normalize_weights(fst2, config.delta)?;
+    let table = EncodeTable::new(EncodeType::EncodeWeightsAndLabels);
+    let table = encode_with_table(&mut efst1, table)?;
+    encode_with_table(&mut efst2, table)?;
+
+    let (start1, start2) = match (efst1.start(), efst2.start()) {
+        (None, None) => return Ok(true),
+        (Some(start1), Some(start2)) => (start1, start2),
+        _ => return Ok(false),
+    };
+
+    let offset = efst1.num_states();
+    let mut union_find = UnionFind::new(offset + efst2.num_states());
+    let mut queue = VecDeque::new();
+    union_find.union(start1 as usize, offset + start2 as usize);
+    queue.push_back((start1, start2));
+
+    while let Some((s1, s2)) = queue.pop_front() {
+        if efst1.is_final(s1)? != efst2.is_final(s2)? {
+            return Ok(false);
+        }
+        let trs1 = efst1.get_trs(s1)?;
+        let trs2 = efst2.get_trs(s2)?;
+        if trs1.len() != trs2.len() {
+            return Ok(false);
+        }
+        let mut trs1: Vec<_> = trs1
+            .trs()
+            .iter()
+            .map(|tr| (tr.ilabel, tr.nextstate))
+            .collect();
+        let mut trs2: Vec<_> = trs2
+            .trs()
+            .iter()
+            .map(|tr| (tr.ilabel, tr.nextstate))
+            .collect();
+        trs1.sort_unstable();
+        trs2.sort_unstable();
+
+        for (&(label1, next1), &(label2, next2)) in trs1.iter().zip(trs2.iter()) {
+            if label1 != label2 {
+                return Ok(false);
+            }
+            let class1 = union_find.find(next1 as usize);
+            let class2 = union_find.find(offset + next2 as usize);
+            if class1 != class2 {
+                union_find.union(class1, class2);
+                queue.push_back((next1 as StateId, next2 as StateId));
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::utils::{acceptor, transducer};
+    use crate::Tr;
+
+    #[test]
+    fn test_equivalent_different_weight_distribution() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(3.0));
+
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        fst2.add_states(3);
+        fst2.set_start(0)?;
+        fst2.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst2.add_tr(1, Tr::new(2, 2, 1.5, 2))?;
+        fst2.set_final(2, 0.5)?;
+
+        assert!(equivalent(&fst1, &fst2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivalent_different_topology() -> Result<()> {
+        // a*b with a self-loop versus an unrolled version of the same language.
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        fst1.add_states(2);
+        fst1.set_start(0)?;
+        fst1.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 0))?;
+        fst1.add_tr(0, Tr::new(2, 2, TropicalWeight::one(), 1))?;
+        fst1.set_final(1, TropicalWeight::one())?;
+
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        fst2.add_states(3);
+        fst2.set_start(0)?;
+        fst2.add_tr(0, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst2.add_tr(0, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        fst2.add_tr(1, Tr::new(1, 1, TropicalWeight::one(), 1))?;
+        fst2.add_tr(1, Tr::new(2, 2, TropicalWeight::one(), 2))?;
+        fst2.set_final(2, TropicalWeight::one())?;
+
+        assert!(equivalent(&fst1, &fst2)?);
+
+        fst2.set_final(1, TropicalWeight::one())?;
+        assert!(!equivalent(&fst1, &fst2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivalent_different_weights() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(3.0));
+        let fst2: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        assert!(!equivalent(&fst1, &fst2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_equivalent_rejects_transducers() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = transducer(&[1], &[2], TropicalWeight::one());
+        let fst2: VectorFst<TropicalWeight> = acceptor(&[1], TropicalWeight::one());
+        assert!(equivalent(&fst1, &fst2).is_err());
+        Ok(())
+    }
+}
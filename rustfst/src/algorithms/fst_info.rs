@@ -0,0 +1,334 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::algorithms::dfs_visit::dfs_visit;
+use crate::algorithms::equivalent::UnionFind;
+use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::algorithms::visitors::SccVisitor;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Trs, EPS_LABEL};
+
+/// Statistics about an FST, similar to the ones reported by OpenFst's `fstinfo`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FstInfo {
+    /// Name of the input symbol table if any.
+    pub input_symbols: Option<String>,
+    /// Name of the output symbol table if any.
+    pub output_symbols: Option<String>,
+    pub num_states: usize,
+    pub num_trs: usize,
+    pub start: Option<StateId>,
+    pub num_final_states: usize,
+    /// Number of trs with both an input and an output epsilon.
+    pub num_epsilons: usize,
+    pub num_input_epsilons: usize,
+    pub num_output_epsilons: usize,
+    pub num_accessible: usize,
+    pub num_coaccessible: usize,
+    /// Number of states both accessible and coaccessible.
+    pub num_connected: usize,
+    /// Number of connected components, the direction of the trs being ignored.
+    pub num_ccs: usize,
+    /// Number of strongly connected components.
+    pub num_sccs: usize,
+    /// Properties of the FST. All of them are computed.
+    pub properties: FstProperties,
+}
+
+/// Names of the properties displayed by `FstInfo` along with their negation.
+const PROPERTY_NAMES: [(FstProperties, FstProperties, &str); 16] = [
+    (
+        FstProperties::ACCEPTOR,
+        FstProperties::NOT_ACCEPTOR,
+        "acceptor",
+    ),
+    (
+        FstProperties::I_DETERMINISTIC,
+        FstProperties::NOT_I_DETERMINISTIC,
+        "input deterministic",
+    ),
+    (
+        FstProperties::O_DETERMINISTIC,
+        FstProperties::NOT_O_DETERMINISTIC,
+        "output deterministic",
+    ),
+    (
+        FstProperties::EPSILONS,
+        FstProperties::NO_EPSILONS,
+        "input/output epsilons",
+    ),
+    (
+        FstProperties::I_EPSILONS,
+        FstProperties::NO_I_EPSILONS,
+        "input epsilons",
+    ),
+    (
+        FstProperties::O_EPSILONS,
+        FstProperties::NO_O_EPSILONS,
+        "output epsilons",
+    ),
+    (
+        FstProperties::I_LABEL_SORTED,
+        FstProperties::NOT_I_LABEL_SORTED,
+        "input label sorted",
+    ),
+    (
+        FstProperties::O_LABEL_SORTED,
+        FstProperties::NOT_O_LABEL_SORTED,
+        "output label sorted",
+    ),
+    (
+        FstProperties::WEIGHTED,
+        FstProperties::UNWEIGHTED,
+        "weighted",
+    ),
+    (FstProperties::CYCLIC, FstProperties::ACYCLIC, "cyclic"),
+    (
+        FstProperties::INITIAL_CYCLIC,
+        FstProperties::INITIAL_ACYCLIC,
+        "cyclic at initial state",
+    ),
+    (
+        FstProperties::TOP_SORTED,
+        FstProperties::NOT_TOP_SORTED,
+        "top sorted",
+    ),
+    (
+        FstProperties::ACCESSIBLE,
+        FstProperties::NOT_ACCESSIBLE,
+        "accessible",
+    ),
+    (
+        FstProperties::COACCESSIBLE,
+        FstProperties::NOT_COACCESSIBLE,
+        "coaccessible",
+    ),
+    (FstProperties::STRING, FstProperties::NOT_STRING, "string"),
+    (
+        FstProperties::WEIGHTED_CYCLES,
+        FstProperties::UNWEIGHTED_CYCLES,
+        "weighted cycles",
+    ),
+];
+
+/// Computes the statistics of an FST : number of states, trs, epsilons, accessible and
+/// coaccessible states, connected components and all the properties.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_properties::FstProperties;
+/// # use rustfst::algorithms::fst_info;
+/// # use rustfst::utils::acceptor;
+/// # fn main() -> Result<()> {
+/// let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3], TropicalWeight::one());
+///
+/// let info = fst_info(&fst)?;
+/// assert_eq!(info.num_states, 4);
+/// assert_eq!(info.num_trs, 3);
+/// assert_eq!(info.num_sccs, 4);
+/// assert!(info.properties.contains(FstProperties::STRING));
+/// # Ok(())
+/// # }
+/// ```
+pub fn fst_info<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<FstInfo> {
+    let num_states = fst.num_states();
+    let mut num_trs = 0;
+    let mut num_final_states = 0;
+    let mut num_epsilons = 0;
+    let mut num_input_epsilons = 0;
+    let mut num_output_epsilons = 0;
+    let mut ccs = UnionFind::new(num_states);
+    for s in fst.states_range() {
+        let trs = fst.get_trs(s)?;
+        num_trs += trs.len();
+        for tr in trs.trs() {
+            if tr.ilabel == EPS_LABEL && tr.olabel == EPS_LABEL {
+                num_epsilons += 1;
+            }
+            if tr.ilabel == EPS_LABEL {
+                num_input_epsilons += 1;
+            }
+            if tr.olabel == EPS_LABEL {
+                num_output_epsilons += 1;
+            }
+            ccs.union(s as usize, tr.nextstate as usize);
+        }
+        if fst.is_final(s)? {
+            num_final_states += 1;
+        }
+    }
+    let num_ccs = (0..num_states).filter(|s| ccs.find(*s) == *s).count();
+
+    let mut visitor = SccVisitor::new(fst, true, true);
+    dfs_visit(fst, &mut visitor, &AnyTrFilter {}, false);
+    let access = visitor.access.take().unwrap();
+    let num_accessible = access.iter().filter(|a| **a).count();
+    let num_coaccessible = visitor.coaccess.iter().filter(|c| **c).count();
+    let num_connected = access
+        .iter()
+        .zip(visitor.coaccess.iter())
+        .filter(|(a, c)| **a && **c)
+        .count();
+    let num_sccs = visitor.nscc as usize;
+
+    let mut known = FstProperties::empty();
+    let properties =
+        compute_fst_properties(fst, FstProperties::trinary_properties(), &mut known, false)?;
+
+    Ok(FstInfo {
+        input_symbols: fst
+            .input_symbols()
+            .map(|symt| symt.name().unwrap_or_default().to_string()),
+        output_symbols: fst
+            .output_symbols()
+            .map(|symt| symt.name().unwrap_or_default().to_string()),
+        num_states,
+        num_trs,
+        start: fst.start(),
+        num_final_states,
+        num_epsilons,
+        num_input_epsilons,
+        num_output_epsilons,
+        num_accessible,
+        num_coaccessible,
+        num_connected,
+        num_ccs,
+        num_sccs,
+        properties,
+    })
+}
+
+impl fmt::Display for FstInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symt_name = |symt: &Option<String>| symt.clone().unwrap_or_else(|| "none".to_string());
+        writeln!(
+            f,
+            "{:<50}{}",
+            "input symbol table",
+            symt_name(&self.input_symbols)
+        )?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "output symbol table",
+            symt_name(&self.output_symbols)
+        )?;
+        writeln!(f, "{:<50}{}", "# of states", self.num_states)?;
+        writeln!(f, "{:<50}{}", "# of trs", self.num_trs)?;
+        match self.start {
+            Some(start) => writeln!(f, "{:<50}{}", "initial state", start)?,
+            None => writeln!(f, "{:<50}none", "initial state")?,
+        };
+        writeln!(f, "{:<50}{}", "# of final states", self.num_final_states)?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "# of input/output epsilons", self.num_epsilons
+        )?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "# of input epsilons", self.num_input_epsilons
+        )?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "# of output epsilons", self.num_output_epsilons
+        )?;
+        writeln!(f, "{:<50}{}", "# of accessible states", self.num_accessible)?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "# of coaccessible states", self.num_coaccessible
+        )?;
+        writeln!(f, "{:<50}{}", "# of connected states", self.num_connected)?;
+        writeln!(f, "{:<50}{}", "# of connected components", self.num_ccs)?;
+        writeln!(
+            f,
+            "{:<50}{}",
+            "# of strongly conn components", self.num_sccs
+        )?;
+        for (property, negation, name) in PROPERTY_NAMES.iter() {
+            let value = if self.properties.contains(*property) {
+                "y"
+            } else if self.properties.contains(*negation) {
+                "n"
+            } else {
+                "?"
+            };
+            writeln!(f, "{:<50}{}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{Fst, MutableFst};
+    use crate::semirings::TropicalWeight;
+    use crate::SymbolTable;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_fst_info() -> Result<()> {
+        // 0 -> 1 -> 2 (final) with a cycle on 1, an unreachable state 3 leading to 2 and a
+        // dead end state 4.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(1, 0, 2, 1.0, 1)?;
+        fst.emplace_tr(1, 0, 0, 1.0, 2)?;
+        fst.emplace_tr(3, 3, 3, 1.0, 2)?;
+        fst.emplace_tr(0, 4, 4, 1.0, 4)?;
+        fst.set_final(2, TropicalWeight::one())?;
+        let mut symt = SymbolTable::new();
+        symt.set_name("words");
+        fst.set_input_symbols(Arc::new(symt));
+
+        let info = fst_info(&fst)?;
+        assert_eq!(
+            info,
+            FstInfo {
+                input_symbols: Some("words".to_string()),
+                output_symbols: None,
+                num_states: 5,
+                num_trs: 5,
+                start: Some(0),
+                num_final_states: 1,
+                num_epsilons: 1,
+                num_input_epsilons: 2,
+                num_output_epsilons: 1,
+                num_accessible: 4,
+                num_coaccessible: 4,
+                num_connected: 3,
+                num_ccs: 1,
+                num_sccs: 5,
+                properties: info.properties,
+            }
+        );
+        assert!(info.properties.contains(
+            FstProperties::NOT_ACCEPTOR
+                | FstProperties::CYCLIC
+                | FstProperties::NOT_ACCESSIBLE
+                | FstProperties::NOT_COACCESSIBLE
+                | FstProperties::WEIGHTED_CYCLES
+        ));
+
+        let report = format!("{}", info);
+        assert!(report.contains("# of states"));
+        assert!(report
+            .lines()
+            .any(|l| l.starts_with("cyclic ") && l.ends_with('y')));
+        Ok(())
+    }
+}
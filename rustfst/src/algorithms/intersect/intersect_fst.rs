@@ -0,0 +1,249 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
+use crate::algorithms::compose::matchers::GenericMatcher;
+use crate::algorithms::compose::ComposeFst;
+use crate::algorithms::intersect::is_acceptor;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, StateIterator};
+use crate::semirings::Semiring;
+use crate::{StateId, SymbolTable, TrsVec};
+
+type InnerComposeFst<W, F1, F2, B1, B2> = ComposeFst<
+    W,
+    F1,
+    F2,
+    B1,
+    B2,
+    GenericMatcher<W, F1, B1>,
+    GenericMatcher<W, F2, B2>,
+    SequenceComposeFilterBuilder<
+        W,
+        F1,
+        F2,
+        B1,
+        B2,
+        GenericMatcher<W, F1, B1>,
+        GenericMatcher<W, F2, B2>,
+    >,
+>;
+
+/// Computes the intersection (Hadamard product) of two FSAs. This version is a delayed FST.
+/// Only strings that are in both automata are retained in the result, with the product
+/// of their weights.
+///
+/// Both arguments must be acceptors. An error is returned otherwise.
+pub struct IntersectFst<W, F1, F2, B1, B2>(InnerComposeFst<W, F1, F2, B1, B2>)
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone;
+
+impl<W, F1, F2, B1, B2> IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone,
+{
+    pub fn new(fst1: B1, fst2: B2) -> Result<Self> {
+        if !is_acceptor(fst1.borrow())? || !is_acceptor(fst2.borrow())? {
+            bail!("IntersectFst : input FSTs are not acceptors");
+        }
+        Ok(IntersectFst(ComposeFst::new_auto(fst1, fst2)?))
+    }
+
+    /// Turns the Lazy FST into a static one.
+    pub fn compute<F3: MutableFst<W> + AllocableFst<W>>(&self) -> Result<F3> {
+        self.0.compute()
+    }
+}
+
+impl<W, F1, F2, B1, B2> CoreFst<W> for IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone,
+{
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.0.start()
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        self.0.final_weight(state_id)
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.0.final_weight_unchecked(state_id)
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        self.0.num_trs(s)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.0.num_trs_unchecked(s)
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        self.0.get_trs(state_id)
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.0.get_trs_unchecked(state_id)
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.0.properties()
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_input_epsilons(state)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        self.0.num_output_epsilons(state)
+    }
+}
+
+impl<'a, W, F1, F2, B1, B2> StateIterator<'a> for IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'a,
+    F2: Fst<W> + 'a,
+    B1: Borrow<F1> + Debug + Clone + 'a,
+    B2: Borrow<F2> + Debug + Clone + 'a,
+{
+    type Iter = <InnerComposeFst<W, F1, F2, B1, B2> as StateIterator<'a>>::Iter;
+
+    fn states_iter(&'a self) -> Self::Iter {
+        self.0.states_iter()
+    }
+}
+
+impl<'a, W, F1, F2, B1, B2> FstIterator<'a, W> for IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'a,
+    F2: Fst<W> + 'a,
+    B1: Borrow<F1> + Debug + Clone + 'a,
+    B2: Borrow<F2> + Debug + Clone + 'a,
+{
+    type FstIter = <InnerComposeFst<W, F1, F2, B1, B2> as FstIterator<'a, W>>::FstIter;
+
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.0.fst_iter()
+    }
+}
+
+impl<W, F1, F2, B1, B2> Fst<W> for IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W> + 'static,
+    F2: Fst<W> + 'static,
+    B1: Borrow<F1> + Debug + Clone + 'static,
+    B2: Borrow<F2> + Debug + Clone + 'static,
+{
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.input_symbols()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.0.output_symbols()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_input_symbols(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.0.set_output_symbols(symt)
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_input_symbols()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.0.take_output_symbols()
+    }
+}
+
+impl<W, F1, F2, B1, B2> Debug for IntersectFst<W, F1, F2, B1, B2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: Fst<W>,
+    B1: Borrow<F1> + Debug + Clone,
+    B2: Borrow<F2> + Debug + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::intersect::intersect;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::utils::{acceptor, transducer};
+
+    type LazyIntersectFst = IntersectFst<
+        TropicalWeight,
+        VectorFst<TropicalWeight>,
+        VectorFst<TropicalWeight>,
+        Arc<VectorFst<TropicalWeight>>,
+        Arc<VectorFst<TropicalWeight>>,
+    >;
+
+    #[test]
+    fn test_intersect_fst_computes_acceptor_property() -> Result<()> {
+        let mut fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(1.0));
+        let fst2: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        fst1.set_properties(FstProperties::empty());
+
+        let lazy: VectorFst<_> =
+            IntersectFst::<_, VectorFst<_>, VectorFst<_>, _, _>::new(&fst1, &fst2)?.compute()?;
+        let static_fst: VectorFst<_> = intersect(&fst1, &fst2)?;
+        assert_eq!(lazy, static_fst);
+
+        let fst3: VectorFst<TropicalWeight> = transducer(&[1, 2], &[2, 1], TropicalWeight::one());
+        assert!(IntersectFst::<_, VectorFst<_>, VectorFst<_>, _, _>::new(&fst1, &fst3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_intersect_fst_lazy_inputs() -> Result<()> {
+        let fst1: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(1.0));
+        let fst2: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        let fst3: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(3.0));
+
+        let lazy = Arc::new(LazyIntersectFst::new(
+            Arc::new(fst1.clone()),
+            Arc::new(fst2.clone()),
+        )?);
+        let nested: VectorFst<_> =
+            IntersectFst::<_, LazyIntersectFst, VectorFst<_>, _, _>::new(lazy, &fst3)?.compute()?;
+        let fst12: VectorFst<_> = intersect(&fst1, &fst2)?;
+        let static_fst: VectorFst<_> = intersect(&fst12, &fst3)?;
+        assert_eq!(nested, static_fst);
+
+        let fst4: VectorFst<TropicalWeight> = transducer(&[1, 2], &[2, 1], TropicalWeight::one());
+        let lazy = Arc::new(LazyIntersectFst::new(Arc::new(fst1), Arc::new(fst2))?);
+        assert!(IntersectFst::<_, LazyIntersectFst, VectorFst<_>, _, _>::new(lazy, &fst4).is_err());
+        Ok(())
+    }
+}
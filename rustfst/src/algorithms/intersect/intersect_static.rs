@@ -0,0 +1,105 @@
+use anyhow::Result;
+
+use crate::algorithms::compose::{compose_with_config, ComposeConfig};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{AllocableFst, ExpandedFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+use crate::Trs;
+
+/// Uses the properties stored in `fst` when they tell whether it is an acceptor. Otherwise the
+/// trs are scanned, which only expands the states of a lazy FST up to the first transducer tr.
+pub(crate) fn is_acceptor<W: Semiring, F: Fst<W>>(fst: &F) -> Result<bool> {
+    let props = fst.properties();
+    if props.contains(FstProperties::ACCEPTOR) {
+        return Ok(true);
+    }
+    if props.contains(FstProperties::NOT_ACCEPTOR) {
+        return Ok(false);
+    }
+    for s in fst.states_iter() {
+        if fst
+            .get_trs(s)?
+            .trs()
+            .iter()
+            .any(|tr| tr.ilabel != tr.olabel)
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Computes the intersection (Hadamard product) of two FSAs with a custom `ComposeConfig`.
+/// Both arguments must be acceptors. An error is returned otherwise.
+pub fn intersect_with_config<W, F1, F2, F3>(
+    fst1: &F1,
+    fst2: &F2,
+    config: ComposeConfig,
+) -> Result<F3>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    if !is_acceptor(fst1)? || !is_acceptor(fst2)? {
+        bail!("intersect : input FSTs are not acceptors");
+    }
+    compose_with_config::<W, F1, F2, _, _, F3>(fst1, fst2, config)
+}
+
+/// Computes the intersection (Hadamard product) of two FSAs. Only strings that are
+/// in both automata are retained in the result, with the product of their weights.
+///
+/// Both arguments must be acceptors. An error is returned otherwise.
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate rustfst;
+/// # use anyhow::Result;
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::algorithms::union::union;
+/// # use rustfst::algorithms::intersect::intersect;
+/// # use rustfst::fst_traits::Fst;
+/// # use rustfst::FstPath;
+/// # fn main() -> Result<()> {
+/// let mut fst1 : VectorFst<TropicalWeight> = fst![1, 2; 0.5];
+/// let fst1_bis : VectorFst<TropicalWeight> = fst![1, 3; 1.5];
+/// union(&mut fst1, &fst1_bis)?;
+/// let fst2 : VectorFst<TropicalWeight> = fst![1, 3; 1.0];
+///
+/// let fst_res : VectorFst<TropicalWeight> = intersect(&fst1, &fst2)?;
+/// let paths : Vec<_> = fst_res.paths_iter().collect();
+/// assert_eq!(paths, vec![fst_path![1, 3; 2.5]]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn intersect<W, F1, F2, F3>(fst1: &F1, fst2: &F2) -> Result<F3>
+where
+    W: Semiring,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    F3: MutableFst<W> + AllocableFst<W>,
+{
+    intersect_with_config(fst1, fst2, ComposeConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::semirings::ProbabilityWeight;
+    use crate::utils::{acceptor, transducer};
+
+    #[test]
+    fn test_intersect_rejects_transducers() -> Result<()> {
+        let fst1: VectorFst<ProbabilityWeight> = acceptor(&[1, 2], ProbabilityWeight::one());
+        let fst2: VectorFst<ProbabilityWeight> =
+            transducer(&[1, 2], &[2, 1], ProbabilityWeight::one());
+        assert!(intersect::<_, _, _, VectorFst<_>>(&fst1, &fst2).is_err());
+        assert!(intersect::<_, _, _, VectorFst<_>>(&fst2, &fst1).is_err());
+        Ok(())
+    }
+}
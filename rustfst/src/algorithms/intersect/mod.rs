@@ -0,0 +1,6 @@
+mod intersect_fst;
+mod intersect_static;
+
+pub use intersect_fst::IntersectFst;
+pub(crate) use intersect_static::is_acceptor;
+pub use intersect_static::{intersect, intersect_with_config};
@@ -0,0 +1,444 @@
+use std::collections::{BTreeMap, HashMap};
+use std::mem::size_of;
+use std::sync::Mutex;
+
+use crate::algorithms::lazy::cache::cache_internal_types::{
+    CacheTrs, CachedData, FinalWeight, StartState,
+};
+use crate::algorithms::lazy::{CacheStatus, FstCache};
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+/// Memory budget of a [`LruCache`]. Each limit is only enforced when set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LruCacheConfig {
+    /// Maximum number of states whose trs or final weight are kept in the cache.
+    pub max_states: Option<usize>,
+    /// Maximum (estimated) number of bytes used by the cached trs and final weights.
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for LruCacheConfig {
+    /// Same default as the garbage collection limit of OpenFst : 1MB.
+    fn default() -> Self {
+        Self {
+            max_states: None,
+            max_bytes: Some(1 << 20),
+        }
+    }
+}
+
+impl LruCacheConfig {
+    pub fn new(max_states: Option<usize>, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_states,
+            max_bytes,
+        }
+    }
+
+    pub fn with_max_states(self, max_states: usize) -> Self {
+        Self {
+            max_states: Some(max_states),
+            ..self
+        }
+    }
+
+    pub fn with_max_bytes(self, max_bytes: usize) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LruEntry<W: Semiring> {
+    trs: Option<CacheTrs<W>>,
+    final_weight: CacheStatus<FinalWeight<W>>,
+    last_use: u64,
+}
+
+impl<W: Semiring> LruEntry<W> {
+    fn num_bytes(&self) -> usize {
+        size_of::<Self>() + self.trs.as_ref().map_or(0, |t| t.trs.len()) * size_of::<Tr<W>>()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LruData<W: Semiring> {
+    start: CachedData<CacheStatus<StartState>>,
+    entries: HashMap<StateId, LruEntry<W>>,
+    // Least recently used states first.
+    recency: BTreeMap<u64, StateId>,
+    clock: u64,
+    num_bytes: usize,
+    // Never decreases, even when states are evicted.
+    num_known_states: usize,
+}
+
+impl<W: Semiring> Default for LruData<W> {
+    fn default() -> Self {
+        Self {
+            start: CachedData::default(),
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            num_bytes: 0,
+            num_known_states: 0,
+        }
+    }
+}
+
+fn mark_used<W: Semiring>(
+    recency: &mut BTreeMap<u64, StateId>,
+    clock: &mut u64,
+    id: StateId,
+    entry: &mut LruEntry<W>,
+) {
+    *clock += 1;
+    recency.remove(&entry.last_use);
+    recency.insert(*clock, id);
+    entry.last_use = *clock;
+}
+
+impl<W: Semiring> LruData<W> {
+    /// Marks `id` as the most recently used state, creating an empty entry if needed.
+    fn touch(&mut self, id: StateId) -> &mut LruEntry<W> {
+        // Not yet a key of `recency` : `mark_used` has nothing to remove for a new entry.
+        let next_use = self.clock + 1;
+        let num_bytes = &mut self.num_bytes;
+        let entry = self.entries.entry(id).or_insert_with(|| {
+            let entry = LruEntry {
+                trs: None,
+                final_weight: CacheStatus::NotComputed,
+                last_use: next_use,
+            };
+            *num_bytes += entry.num_bytes();
+            entry
+        });
+        mark_used(&mut self.recency, &mut self.clock, id, entry);
+        entry
+    }
+
+    /// Entry of `id` if it is in the cache, marked as the most recently used.
+    fn get_mut(&mut self, id: StateId) -> Option<&mut LruEntry<W>> {
+        let entry = self.entries.get_mut(&id)?;
+        mark_used(&mut self.recency, &mut self.clock, id, entry);
+        Some(entry)
+    }
+
+    fn is_over_budget(&self, config: &LruCacheConfig) -> bool {
+        matches!(config.max_states, Some(m) if self.entries.len() > m)
+            || matches!(config.max_bytes, Some(m) if self.num_bytes > m)
+    }
+
+    /// Evicts the least recently used states until the budget is met. The most recently used
+    /// state is always kept so that a value that has just been inserted can be read back.
+    fn evict(&mut self, config: &LruCacheConfig) {
+        while self.entries.len() > 1 && self.is_over_budget(config) {
+            let oldest = *self.recency.keys().next().unwrap();
+            let state = self.recency.remove(&oldest).unwrap();
+            let entry = self.entries.remove(&state).unwrap();
+            self.num_bytes -= entry.num_bytes();
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Cache keeping the trs and final weights of the most recently used states only. When the
+/// budget defined in the [`LruCacheConfig`] is exceeded, the least recently used states are
+/// evicted and will be recomputed on the next access.
+///
+/// The number of known states is never decreased by an eviction, so states discovered by the
+/// lazy FST stay reachable.
+#[derive(Debug)]
+pub struct LruCache<W: Semiring> {
+    config: LruCacheConfig,
+    data: Mutex<LruData<W>>,
+}
+
+impl<W: Semiring> LruCache<W> {
+    pub fn new(config: LruCacheConfig) -> Self {
+        Self {
+            config,
+            data: Mutex::new(LruData::default()),
+        }
+    }
+
+    pub fn config(&self) -> LruCacheConfig {
+        self.config
+    }
+
+    /// Estimated number of bytes used by the cached trs and final weights.
+    pub fn num_bytes(&self) -> usize {
+        self.data.lock().unwrap().num_bytes
+    }
+
+    pub fn clear(&self) {
+        self.data.lock().unwrap().clear();
+    }
+}
+
+impl<W: Semiring> Clone for LruCache<W> {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config,
+            data: Mutex::new(self.data.lock().unwrap().clone()),
+        }
+    }
+}
+
+impl<W: Semiring> Default for LruCache<W> {
+    fn default() -> Self {
+        Self::new(LruCacheConfig::default())
+    }
+}
+
+impl<W: Semiring> FstCache<W> for LruCache<W> {
+    fn get_start(&self) -> CacheStatus<StartState> {
+        self.data.lock().unwrap().start.data
+    }
+
+    fn insert_start(&self, id: StartState) {
+        let mut data = self.data.lock().unwrap();
+        if let Some(s) = id {
+            data.num_known_states = std::cmp::max(data.num_known_states, s as usize + 1);
+        }
+        data.start.data = CacheStatus::Computed(id);
+    }
+
+    fn get_trs(&self, id: StateId) -> CacheStatus<TrsVec<W>> {
+        let mut data = self.data.lock().unwrap();
+        match data.get_mut(id) {
+            Some(LruEntry { trs: Some(e), .. }) => CacheStatus::Computed(e.trs.shallow_clone()),
+            _ => CacheStatus::NotComputed,
+        }
+    }
+
+    fn insert_trs(&self, id: StateId, trs: TrsVec<W>) {
+        let mut data = self.data.lock().unwrap();
+        let mut niepsilons = 0;
+        let mut noepsilons = 0;
+        for tr in trs.trs() {
+            data.num_known_states = std::cmp::max(data.num_known_states, tr.nextstate as usize + 1);
+            if tr.ilabel == EPS_LABEL {
+                niepsilons += 1;
+            }
+            if tr.olabel == EPS_LABEL {
+                noepsilons += 1;
+            }
+        }
+        let entry = data.touch(id);
+        let old_num_bytes = entry.num_bytes();
+        entry.trs = Some(CacheTrs {
+            trs,
+            niepsilons,
+            noepsilons,
+        });
+        let new_num_bytes = entry.num_bytes();
+        data.num_bytes = data.num_bytes + new_num_bytes - old_num_bytes;
+        data.evict(&self.config);
+    }
+
+    fn get_final_weight(&self, id: StateId) -> CacheStatus<FinalWeight<W>> {
+        let mut data = self.data.lock().unwrap();
+        match data.get_mut(id) {
+            Some(e) => e.final_weight.clone(),
+            None => CacheStatus::NotComputed,
+        }
+    }
+
+    fn insert_final_weight(&self, id: StateId, weight: FinalWeight<W>) {
+        let mut data = self.data.lock().unwrap();
+        data.num_known_states = std::cmp::max(data.num_known_states, id as usize + 1);
+        data.touch(id).final_weight = CacheStatus::Computed(weight);
+        data.evict(&self.config);
+    }
+
+    fn num_known_states(&self) -> usize {
+        self.data.lock().unwrap().num_known_states
+    }
+
+    fn compute_num_known_trs(&self) -> usize {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .values()
+            .filter_map(|e| e.trs.as_ref())
+            .map(|t| t.trs.len())
+            .sum()
+    }
+
+    fn num_trs(&self, id: StateId) -> Option<usize> {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .get(&id)
+            .and_then(|e| e.trs.as_ref())
+            .map(|t| t.trs.len())
+    }
+
+    fn num_input_epsilons(&self, id: StateId) -> Option<usize> {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .get(&id)
+            .and_then(|e| e.trs.as_ref())
+            .map(|t| t.niepsilons)
+    }
+
+    fn num_output_epsilons(&self, id: StateId) -> Option<usize> {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .get(&id)
+            .and_then(|e| e.trs.as_ref())
+            .map(|t| t.noepsilons)
+    }
+
+    fn len_trs(&self) -> usize {
+        let data = self.data.lock().unwrap();
+        data.entries.values().filter(|e| e.trs.is_some()).count()
+    }
+
+    fn len_final_weights(&self) -> usize {
+        let data = self.data.lock().unwrap();
+        data.entries
+            .values()
+            .filter(|e| e.final_weight.is_computed())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use anyhow::Result;
+
+    use crate::algorithms::compose::compose;
+    use crate::algorithms::compose::compose_filters::SequenceComposeFilterBuilder;
+    use crate::algorithms::compose::matchers::GenericMatcher;
+    use crate::algorithms::compose::{ComposeFst, ComposeFstOpOptions};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, MutableFst, StateIterator};
+    use crate::semirings::TropicalWeight;
+
+    fn trs(nextstates: &[StateId]) -> TrsVec<TropicalWeight> {
+        let mut trs = TrsVec::default();
+        for (i, n) in nextstates.iter().enumerate() {
+            trs.push(Tr::new(i as _, 1, TropicalWeight::one(), *n));
+        }
+        trs
+    }
+
+    #[test]
+    fn test_lru_cache_max_states() {
+        let cache = LruCache::new(LruCacheConfig::new(None, None).with_max_states(2));
+        cache.insert_trs(0, trs(&[1, 2]));
+        cache.insert_final_weight(1, Some(TropicalWeight::one()));
+        assert!(cache.get_trs(0).is_computed());
+        cache.insert_trs(2, trs(&[3]));
+
+        // State 1 is the least recently used.
+        assert!(cache.get_trs(0).is_computed());
+        assert!(cache.get_final_weight(1).is_not_computed());
+        assert!(cache.get_trs(2).is_computed());
+        assert_eq!(cache.num_input_epsilons(0), Some(1));
+        assert_eq!(cache.len_trs(), 2);
+        assert_eq!(cache.len_final_weights(), 0);
+        assert_eq!(cache.compute_num_known_trs(), 3);
+    }
+
+    #[test]
+    fn test_lru_cache_max_bytes() {
+        let cache = LruCache::new(LruCacheConfig::new(None, Some(0)));
+        for s in 0..10 {
+            cache.insert_trs(s, trs(&[s + 1, s + 2]));
+            // The state that has just been inserted is always kept.
+            assert_eq!(cache.num_trs(s), Some(2));
+            assert_eq!(cache.len_trs(), 1);
+        }
+        cache.clear();
+        assert_eq!(cache.num_bytes(), 0);
+        assert_eq!(cache.num_known_states(), 0);
+    }
+
+    #[test]
+    fn test_lru_cache_num_known_states_after_eviction() {
+        let cache = LruCache::new(LruCacheConfig::new(Some(1), None));
+        cache.insert_start(Some(0));
+        cache.insert_trs(0, trs(&[5]));
+        cache.insert_final_weight(5, None);
+        assert!(cache.get_trs(0).is_not_computed());
+        assert_eq!(cache.num_trs(0), None);
+        assert_eq!(cache.num_known_states(), 6);
+        assert!(matches!(cache.get_start(), CacheStatus::Computed(Some(0))));
+    }
+
+    #[test]
+    fn test_lru_cache_compose_fst() -> Result<()> {
+        let mut fst1 = VectorFst::<TropicalWeight>::new();
+        let mut fst2 = VectorFst::<TropicalWeight>::new();
+        for fst in [&mut fst1, &mut fst2].iter_mut() {
+            let states: Vec<_> = (0..6).map(|_| fst.add_state()).collect();
+            fst.set_start(states[0])?;
+            for (i, s) in states.iter().enumerate() {
+                for l in 1..4 {
+                    let n = states[(i + l) % states.len()];
+                    fst.add_tr(
+                        *s,
+                        Tr::new(l as _, l as _, TropicalWeight::new(i as f32), n),
+                    )?;
+                }
+                if i % 2 == 1 {
+                    fst.set_final(*s, TropicalWeight::new(0.5))?;
+                }
+            }
+        }
+        let fst1 = Arc::new(fst1);
+        let fst2 = Arc::new(fst2);
+        let expected: VectorFst<_> = compose::<_, VectorFst<_>, VectorFst<_>, _, _, _>(
+            Arc::clone(&fst1),
+            Arc::clone(&fst2),
+        )?;
+
+        for max_states in 1..4 {
+            let cache = Arc::new(LruCache::new(
+                LruCacheConfig::new(None, None).with_max_states(max_states),
+            ));
+            type M = GenericMatcher<
+                TropicalWeight,
+                VectorFst<TropicalWeight>,
+                Arc<VectorFst<TropicalWeight>>,
+            >;
+            let lazy_fst = ComposeFst::<
+                _,
+                _,
+                _,
+                _,
+                _,
+                M,
+                M,
+                SequenceComposeFilterBuilder<_, _, _, _, _, M, M>,
+                _,
+            >::new_with_options_and_cache(
+                Arc::clone(&fst1),
+                Arc::clone(&fst2),
+                ComposeFstOpOptions::default(),
+                Arc::clone(&cache),
+            )?;
+            let computed: VectorFst<_> = lazy_fst.compute()?;
+            assert_eq!(computed, expected);
+            assert!(cache.len_trs() <= max_states);
+
+            // Evicted states are recomputed on access.
+            for s in lazy_fst.states_iter() {
+                assert_eq!(lazy_fst.num_trs(s)?, expected.num_trs(s)?);
+                assert_eq!(lazy_fst.final_weight(s)?, expected.final_weight(s)?);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use crate::algorithms::lazy::cache::cache_internal_types::{CacheTrs, FinalWeight, StartState};
+use crate::algorithms::lazy::{CacheStatus, FstCache};
+use crate::semirings::Semiring;
+use crate::{StateId, Trs, TrsVec, EPS_LABEL};
+
+/// Number of shards used by `ShardedHashMapCache::default`.
+pub const DEFAULT_NUM_SHARDS: usize = 64;
+
+#[derive(Debug, Clone)]
+struct Shard<W: Semiring> {
+    trs: HashMap<StateId, CacheTrs<W>>,
+    final_weights: HashMap<StateId, FinalWeight<W>>,
+}
+
+impl<W: Semiring> Default for Shard<W> {
+    fn default() -> Self {
+        Self {
+            trs: HashMap::new(),
+            final_weights: HashMap::new(),
+        }
+    }
+}
+
+/// Cache meant to be shared between threads. The states are split between several shards,
+/// each one behind its own `RwLock`, so that threads reading or filling different states don't
+/// wait on each other. Reads of already computed states only take a read lock.
+#[derive(Debug)]
+pub struct ShardedHashMapCache<W: Semiring> {
+    start: RwLock<CacheStatus<StartState>>,
+    shards: Vec<RwLock<Shard<W>>>,
+    num_known_states: AtomicUsize,
+}
+
+impl<W: Semiring> ShardedHashMapCache<W> {
+    /// Creates a cache with `num_shards` shards. A good value is a few times the number of
+    /// threads accessing the cache.
+    pub fn new(num_shards: usize) -> Self {
+        let num_shards = std::cmp::max(num_shards, 1);
+        Self {
+            start: RwLock::new(CacheStatus::NotComputed),
+            shards: (0..num_shards)
+                .map(|_| RwLock::new(Shard::default()))
+                .collect(),
+            num_known_states: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn clear(&self) {
+        *self.start.write().unwrap() = CacheStatus::NotComputed;
+        for shard in self.shards.iter() {
+            *shard.write().unwrap() = Shard::default();
+        }
+        self.num_known_states.store(0, Ordering::SeqCst);
+    }
+
+    fn shard(&self, id: StateId) -> &RwLock<Shard<W>> {
+        &self.shards[id as usize % self.shards.len()]
+    }
+
+    fn update_num_known_states(&self, n: usize) {
+        self.num_known_states.fetch_max(n, Ordering::SeqCst);
+    }
+}
+
+impl<W: Semiring> Clone for ShardedHashMapCache<W> {
+    fn clone(&self) -> Self {
+        Self {
+            start: RwLock::new(*self.start.read().unwrap()),
+            shards: self
+                .shards
+                .iter()
+                .map(|shard| RwLock::new(shard.read().unwrap().clone()))
+                .collect(),
+            num_known_states: AtomicUsize::new(self.num_known_states.load(Ordering::SeqCst)),
+        }
+    }
+}
+
+impl<W: Semiring> Default for ShardedHashMapCache<W> {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_SHARDS)
+    }
+}
+
+impl<W: Semiring> FstCache<W> for ShardedHashMapCache<W> {
+    fn get_start(&self) -> CacheStatus<StartState> {
+        *self.start.read().unwrap()
+    }
+
+    fn insert_start(&self, id: StartState) {
+        if let Some(s) = id {
+            self.update_num_known_states(s as usize + 1);
+        }
+        *self.start.write().unwrap() = CacheStatus::Computed(id);
+    }
+
+    fn get_trs(&self, id: StateId) -> CacheStatus<TrsVec<W>> {
+        match self.shard(id).read().unwrap().trs.get(&id) {
+            Some(e) => CacheStatus::Computed(e.trs.shallow_clone()),
+            None => CacheStatus::NotComputed,
+        }
+    }
+
+    fn insert_trs(&self, id: StateId, trs: TrsVec<W>) {
+        let mut num_known_states = 0;
+        let mut niepsilons = 0;
+        let mut noepsilons = 0;
+        for tr in trs.trs() {
+            num_known_states = std::cmp::max(num_known_states, tr.nextstate as usize + 1);
+            if tr.ilabel == EPS_LABEL {
+                niepsilons += 1;
+            }
+            if tr.olabel == EPS_LABEL {
+                noepsilons += 1;
+            }
+        }
+        self.update_num_known_states(num_known_states);
+        self.shard(id).write().unwrap().trs.insert(
+            id,
+            CacheTrs {
+                trs,
+                niepsilons,
+                noepsilons,
+            },
+        );
+    }
+
+    fn get_final_weight(&self, id: StateId) -> CacheStatus<FinalWeight<W>> {
+        match self.shard(id).read().unwrap().final_weights.get(&id) {
+            Some(e) => CacheStatus::Computed(e.clone()),
+            None => CacheStatus::NotComputed,
+        }
+    }
+
+    fn insert_final_weight(&self, id: StateId, weight: FinalWeight<W>) {
+        self.update_num_known_states(id as usize + 1);
+        self.shard(id)
+            .write()
+            .unwrap()
+            .final_weights
+            .insert(id, weight);
+    }
+
+    fn num_known_states(&self) -> usize {
+        self.num_known_states.load(Ordering::SeqCst)
+    }
+
+    fn compute_num_known_trs(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let shard = shard.read().unwrap();
+                shard.trs.values().map(|it| it.trs.len()).sum::<usize>()
+            })
+            .sum()
+    }
+
+    fn num_trs(&self, id: StateId) -> Option<usize> {
+        let shard = self.shard(id).read().unwrap();
+        shard.trs.get(&id).map(|v| v.trs.len())
+    }
+
+    fn num_input_epsilons(&self, id: StateId) -> Option<usize> {
+        let shard = self.shard(id).read().unwrap();
+        shard.trs.get(&id).map(|v| v.niepsilons)
+    }
+
+    fn num_output_epsilons(&self, id: StateId) -> Option<usize> {
+        let shard = self.shard(id).read().unwrap();
+        shard.trs.get(&id).map(|v| v.noepsilons)
+    }
+
+    fn len_trs(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().trs.len())
+            .sum()
+    }
+
+    fn len_final_weights(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().final_weights.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::algorithms::lazy::{FirstCache, LruCache, SimpleHashMapCache, SimpleVecCache};
+    use crate::semirings::TropicalWeight;
+    use crate::Tr;
+
+    #[test]
+    fn test_caches_send_sync() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<ShardedHashMapCache<TropicalWeight>>();
+        is_send_sync::<SimpleHashMapCache<TropicalWeight>>();
+        is_send_sync::<SimpleVecCache<TropicalWeight>>();
+        is_send_sync::<LruCache<TropicalWeight>>();
+        is_send_sync::<FirstCache<TropicalWeight, ShardedHashMapCache<TropicalWeight>>>();
+    }
+
+    #[test]
+    fn test_sharded_hash_map_cache() {
+        let cache = ShardedHashMapCache::<TropicalWeight>::new(3);
+        assert!(cache.get_start().is_not_computed());
+        cache.insert_start(Some(0));
+        for s in 0..10 {
+            let mut trs = TrsVec::default();
+            trs.push(Tr::new(0, s, TropicalWeight::one(), s + 1));
+            cache.insert_trs(s, trs);
+            cache.insert_final_weight(s, None);
+        }
+        assert_eq!(cache.num_known_states(), 11);
+        assert_eq!(cache.len_trs(), 10);
+        assert_eq!(cache.len_final_weights(), 10);
+        assert_eq!(cache.compute_num_known_trs(), 10);
+        assert_eq!(cache.num_input_epsilons(4), Some(1));
+        assert_eq!(cache.num_output_epsilons(4), Some(0));
+        assert_eq!(cache.num_output_epsilons(0), Some(1));
+        assert!(cache.get_trs(10).is_not_computed());
+
+        cache.clear();
+        assert_eq!(cache.len_trs(), 0);
+        assert_eq!(cache.num_known_states(), 0);
+    }
+
+    #[test]
+    fn test_sharded_hash_map_cache_concurrent_inserts() {
+        let cache = Arc::new(ShardedHashMapCache::<TropicalWeight>::new(4));
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = Arc::clone(&cache);
+                std::thread::spawn(move || {
+                    for s in (t..100).step_by(4) {
+                        cache.insert_final_weight(s, Some(TropicalWeight::new(s as f32)));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cache.num_known_states(), 100);
+        assert_eq!(cache.len_final_weights(), 100);
+        for s in 0..100 {
+            assert!(matches!(
+                cache.get_final_weight(s),
+                CacheStatus::Computed(Some(w)) if w == TropicalWeight::new(s as f32)
+            ));
+        }
+    }
+}
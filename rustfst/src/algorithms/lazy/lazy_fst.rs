@@ -18,7 +18,28 @@ use crate::fst_traits::{
     AllocableFst, CoreFst, Fst, FstIterData, FstIterator, MutableFst, StateIterator,
 };
 use crate::semirings::{Semiring, SerializableSemiring};
-use crate::{StateId, SymbolTable, Trs, TrsVec};
+use crate::{StateId, SymbolTable, Tr, Trs, TrsVec, EPS_LABEL};
+
+/// Number of trs of `state` matching `filter`, read from the cache when it still holds the trs
+/// of the state. Otherwise, if the state is known, its trs are recomputed through `get_trs`.
+pub(super) fn count_trs<W: Semiring, F: CoreFst<W>>(
+    fst: &F,
+    state: StateId,
+    cached: Option<usize>,
+    num_known_states: usize,
+    filter: impl Fn(&Tr<W>) -> bool,
+) -> Result<usize> {
+    match cached {
+        Some(n) => Ok(n),
+        None if (state as usize) < num_known_states => Ok(fst
+            .get_trs(state)?
+            .trs()
+            .iter()
+            .filter(|tr| filter(tr))
+            .count()),
+        None => Err(format_err!("State {:?} doesn't exist", state)),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct LazyFst<W: Semiring, Op: FstOp<W>, Cache> {
@@ -61,13 +82,17 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
     }
 
     fn num_trs(&self, s: StateId) -> Result<usize> {
-        self.cache
-            .num_trs(s)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", s))
+        count_trs(
+            self,
+            s,
+            self.cache.num_trs(s),
+            self.cache.num_known_states(),
+            |_| true,
+        )
     }
 
     unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
-        self.cache.num_trs(s).unsafe_unwrap()
+        self.num_trs(s).unsafe_unwrap()
     }
 
     fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
@@ -90,15 +115,23 @@ impl<W: Semiring, Op: FstOp<W>, Cache: FstCache<W>> CoreFst<W> for LazyFst<W, Op
     }
 
     fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
-        self.cache
-            .num_input_epsilons(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))
+        count_trs(
+            self,
+            state,
+            self.cache.num_input_epsilons(state),
+            self.cache.num_known_states(),
+            |tr| tr.ilabel == EPS_LABEL,
+        )
     }
 
     fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
-        self.cache
-            .num_output_epsilons(state)
-            .ok_or_else(|| format_err!("State {:?} doesn't exist", state))
+        count_trs(
+            self,
+            state,
+            self.cache.num_output_epsilons(state),
+            self.cache.num_known_states(),
+            |tr| tr.olabel == EPS_LABEL,
+        )
     }
 }
 
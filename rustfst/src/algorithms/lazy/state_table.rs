@@ -1,6 +1,6 @@
 use std::fmt;
 use std::hash::Hash;
-use std::sync::Mutex;
+use std::sync::RwLock;
 
 use crate::StateId;
 use std::collections::hash_map::Entry;
@@ -58,19 +58,23 @@ impl<T: Hash + Eq + Clone, H: BuildHasher> BiHashMap<T, H> {
         }
     }
 
+    pub fn get_id(&self, tuple: &T) -> Option<StateId> {
+        self.tuple_to_id.get(tuple).copied()
+    }
+
     pub fn get_tuple_unchecked(&self, id: StateId) -> T {
         self.id_to_tuple[id as usize].clone()
     }
 }
 
 pub struct StateTable<T: Hash + Eq + Clone> {
-    pub(crate) table: Mutex<BiHashMap<T>>,
+    pub(crate) table: RwLock<BiHashMap<T>>,
 }
 
 impl<T: Hash + Eq + Clone> Clone for StateTable<T> {
     fn clone(&self) -> Self {
         Self {
-            table: Mutex::new(self.table.lock().unwrap().clone()),
+            table: RwLock::new(self.table.read().unwrap().clone()),
         }
     }
 }
@@ -78,7 +82,7 @@ impl<T: Hash + Eq + Clone> Clone for StateTable<T> {
 impl<T: Hash + Eq + Clone> Default for StateTable<T> {
     fn default() -> Self {
         Self {
-            table: Mutex::new(BiHashMap::new()),
+            table: RwLock::new(BiHashMap::new()),
         }
     }
 }
@@ -88,38 +92,46 @@ impl<T: Hash + Eq + Clone + fmt::Debug> fmt::Debug for StateTable<T> {
         write!(
             f,
             "StateTable {{ table : {:?} }}",
-            self.table.lock().unwrap()
+            self.table.read().unwrap()
         )
     }
 }
 
 impl<T: Hash + Eq + Clone + PartialEq> PartialEq for StateTable<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.table.lock().unwrap().eq(&*other.table.lock().unwrap())
+        self.table.read().unwrap().eq(&*other.table.read().unwrap())
     }
 }
 
 impl<T: Hash + Eq + Clone> StateTable<T> {
     pub fn new() -> Self {
         Self {
-            table: Mutex::new(BiHashMap::new()),
+            table: RwLock::new(BiHashMap::new()),
         }
     }
 
     /// Looks up integer ID from entry. If it doesn't exist and insert
     pub fn find_id_from_ref(&self, tuple: &T) -> StateId {
-        let mut table = self.table.lock().unwrap();
+        if let Some(id) = self.table.read().unwrap().get_id(tuple) {
+            return id;
+        }
+        let mut table = self.table.write().unwrap();
         table.get_id_or_insert(tuple.clone())
     }
 
     pub fn find_id(&self, tuple: T) -> StateId {
-        let mut table = self.table.lock().unwrap();
+        // Only take the write lock when the tuple is new so that lookups from several threads
+        // don't wait on each other.
+        if let Some(id) = self.table.read().unwrap().get_id(&tuple) {
+            return id;
+        }
+        let mut table = self.table.write().unwrap();
         table.get_id_or_insert(tuple)
     }
 
     /// Looks up tuple from integer ID.
     pub fn find_tuple(&self, tuple_id: StateId) -> T {
-        let table = self.table.lock().unwrap();
+        let table = self.table.read().unwrap();
         table.get_tuple_unchecked(tuple_id)
     }
 }
@@ -144,7 +156,7 @@ impl<T: SerializeBinary + Hash + Eq + Clone> SerializeBinary for StateTable<T> {
         Ok((
             i,
             StateTable {
-                table: Mutex::new(BiHashMap {
+                table: RwLock::new(BiHashMap {
                     tuple_to_id,
                     id_to_tuple,
                 }),
@@ -153,7 +165,7 @@ impl<T: SerializeBinary + Hash + Eq + Clone> SerializeBinary for StateTable<T> {
     }
     /// Writes a struct to a writable buffer.
     fn write_binary<WB: Write>(&self, writer: &mut WB) -> Result<()> {
-        let table = self.table.lock().map_err(|err| anyhow!("{}", err))?;
+        let table = self.table.read().map_err(|err| anyhow!("{}", err))?;
         write_bin_u64(writer, table.tuple_to_id.len() as u64)?;
 
         // Final weights serialization
@@ -3,29 +3,39 @@ pub use self::{
     all_pairs_shortest_distance::all_pairs_shortest_distance,
     condense::condense,
     connect::connect,
+    disambiguate::{disambiguate, disambiguate_with_config, DisambiguateConfig},
+    epsnormalize::{epsnormalize, EpsNormalizeType},
+    equivalent::{equivalent, equivalent_with_config, EquivalentConfig},
     fst_convert::{fst_convert, fst_convert_from_ref},
+    fst_info::{fst_info, FstInfo},
     inversion::invert,
     isomorphic::{isomorphic, isomorphic_with_config, IsomorphicConfig},
     minimize::{acceptor_minimize, minimize, minimize_with_config, MinimizeConfig},
     optimize::optimize,
     projection::{project, ProjectType},
+    prune::{prune, prune_fst, prune_fst_with_config, prune_with_config, PruneConfig},
     push::{
         push, push_weights, push_weights_with_config, push_with_config, PushConfig, PushType,
         PushWeightsConfig,
     },
     queue::{Queue, QueueType},
+    rand_equivalent::{rand_equivalent, rand_equivalent_with_config, RandEquivalentConfig},
     relabel_pairs::relabel_pairs,
     reverse::reverse,
     reweight::{reweight, ReweightType},
     rm_final_epsilon::rm_final_epsilon,
     shortest_distance::{shortest_distance, shortest_distance_with_config, ShortestDistanceConfig},
-    shortest_path::{shortest_path, shortest_path_with_config, ShortestPathConfig},
+    shortest_path::{
+        shortest_path, shortest_path_unique_output, shortest_path_with_config, ShortestPathConfig,
+        ShortestPathsIterator,
+    },
     state_sort::state_sort,
     top_sort::top_sort,
     tr_map::{tr_map, FinalTr, MapFinalAction, TrMapper},
     tr_sort::tr_sort,
     tr_sum::tr_sum,
     tr_unique::tr_unique,
+    verify::{verify, VerifyError},
     weight_convert::{weight_convert, WeightConverter},
 };
 
@@ -33,6 +43,8 @@ mod add_super_final_state;
 mod all_pairs_shortest_distance;
 /// Functions to compute Kleene closure (star or plus) of an FST.
 pub mod closure;
+/// Functions to complement FSAs.
+pub mod complement;
 #[allow(clippy::type_complexity)]
 /// Functions to compose FSTs.
 pub mod compose;
@@ -43,19 +55,32 @@ mod connect;
 /// Functions to determinize FSTs.
 pub mod determinize;
 pub(crate) mod dfs_visit;
+/// Functions to compute the difference of FSAs.
+pub mod difference;
+mod disambiguate;
 /// Functions to encode FSTs as FSAs and vice versa.
 pub mod encode;
+mod epsnormalize;
+mod equivalent;
 /// Functions to factor various weight types.
 pub mod factor_weight;
 mod fst_convert;
+mod fst_info;
+/// Functions to compute the intersection of FSAs.
+pub mod intersect;
 mod inversion;
 mod isomorphic;
 mod minimize;
 mod optimize;
+/// Parallel versions of some algorithms, available with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub mod parallel;
 mod partition;
 mod projection;
+mod prune;
 mod push;
 mod queue;
+mod rand_equivalent;
 
 /// Functions to randomly generate paths through an Fst. A static and a delayed version are available.
 pub mod randgen;
@@ -71,6 +96,8 @@ mod rm_final_epsilon;
 mod shortest_distance;
 mod shortest_path;
 mod state_sort;
+/// Functions to synchronize FSTs.
+pub mod synchronize;
 mod top_sort;
 mod tr_map;
 mod tr_sort;
@@ -78,6 +105,7 @@ mod tr_sum;
 pub(crate) mod tr_unique;
 /// Functions to compute the union of FSTs.
 pub mod union;
+mod verify;
 mod weight_convert;
 
 /// Module providing different structures implementing the `Queue` trait.
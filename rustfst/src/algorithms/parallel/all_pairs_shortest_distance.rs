@@ -0,0 +1,52 @@
+use anyhow::Result;
+use rayon::prelude::*;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::StarSemiring;
+use crate::{StateId, Trs};
+
+/// Parallel version of
+/// [`all_pairs_shortest_distance`](crate::algorithms::all_pairs_shortest_distance). For each
+/// intermediate state, the rows of the distance matrix are updated concurrently.
+pub fn all_pairs_shortest_distance<W, F>(fst: &F) -> Result<Vec<Vec<W>>>
+where
+    F: ExpandedFst<W> + Sync,
+    W: StarSemiring + Send,
+{
+    let num_states = fst.num_states();
+
+    // Distance between all states are initialized to zero and the weights of the trs are added.
+    let mut d = (0..num_states)
+        .into_par_iter()
+        .map(|state| {
+            let mut row = vec![W::zero(); num_states];
+            for tr in fst.get_trs(state as StateId)?.trs() {
+                row[tr.nextstate as usize].plus_assign(&tr.weight)?;
+            }
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for k in 0..num_states {
+        let closure_d_k_k = d[k][k].closure();
+        let d_k = d[k].clone();
+        d.par_iter_mut()
+            .enumerate()
+            .filter(|(i, _)| *i != k)
+            .try_for_each(|(_, d_i)| -> Result<()> {
+                let d_i_k = d_i[k].times(&closure_d_k_k)?;
+                for j in (0..num_states).filter(|j| *j != k) {
+                    let a = d_i_k.times(&d_k[j])?;
+                    d_i[j].plus_assign(a)?;
+                }
+                d_i[k] = d_i[k].times(&closure_d_k_k)?;
+                Ok(())
+            })?;
+        for i in (0..num_states).filter(|i| *i != k) {
+            d[k][i] = closure_d_k_k.times(&d[k][i])?;
+        }
+        d[k][k] = closure_d_k_k;
+    }
+
+    Ok(d)
+}
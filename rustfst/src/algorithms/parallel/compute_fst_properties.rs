@@ -0,0 +1,46 @@
+use anyhow::Result;
+use rayon::prelude::*;
+
+use crate::fst_properties::{compute_fst_properties_with, compute_state_properties, FstProperties};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::StateId;
+
+/// Parallel version of [`compute_fst_properties`](crate::fst_properties::compute_fst_properties).
+/// The properties of the states are computed concurrently.
+///
+/// The strongly connected components are still computed by a sequential DFS, as in the
+/// sequential version. It only runs when the mask contains one of `ACYCLIC`, `CYCLIC`,
+/// `INITIAL_ACYCLIC`, `INITIAL_CYCLIC`, `ACCESSIBLE`, `NOT_ACCESSIBLE`, `COACCESSIBLE`,
+/// `NOT_COACCESSIBLE`, `WEIGHTED_CYCLES` or `UNWEIGHTED_CYCLES` : leave them out of the mask
+/// for the whole computation to be parallel.
+pub fn compute_fst_properties<W, F>(
+    fst: &F,
+    mask: FstProperties,
+    known: &mut FstProperties,
+    use_stored: bool,
+) -> Result<FstProperties>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + Sync,
+{
+    compute_fst_properties_with(
+        fst,
+        mask,
+        known,
+        use_stored,
+        |fst, check_weighted_cycles, sccs| {
+            let num_states = fst.num_states() as StateId;
+            let states_props = (0..num_states)
+                .into_par_iter()
+                .map(|state| {
+                    compute_state_properties(fst, state, mask, check_weighted_cycles, sccs)
+                })
+                .reduce(|| Ok(FstProperties::empty()), |a, b| Ok(a? | b?))?;
+            let first_final = (0..num_states)
+                .into_par_iter()
+                .find_first(|state| unsafe { fst.is_final_unchecked(*state) });
+            Ok((states_props, first_final))
+        },
+    )
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+
+use crate::algorithms::parallel::{accessible_states, coaccessible_states};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{ExpandedFst, MutableFst};
+use crate::semirings::Semiring;
+use crate::StateId;
+
+/// Parallel version of [`connect`](crate::algorithms::connect). The accessible and coaccessible
+/// states are computed concurrently, each with a parallel breadth-first search.
+pub fn connect<W, F>(fst: &mut F) -> Result<()>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + MutableFst<W> + Sync,
+{
+    let (access, coaccess) = {
+        let fst: &F = fst;
+        rayon::join(|| accessible_states(fst), || coaccessible_states(fst))
+    };
+    let dstates: Vec<_> = (0..access.len())
+        .filter(|s| !access[*s] || !coaccess[*s])
+        .map(|s| s as StateId)
+        .collect();
+    fst.del_states(dstates)?;
+    fst.set_properties_with_mask(
+        FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE,
+        FstProperties::ACCESSIBLE | FstProperties::COACCESSIBLE,
+    );
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! The algorithms of this module run on the global rayon thread pool and return exactly the
+//! same results as their sequential counterpart. They are only worth it on large FSTs.
+//!
+//! The DFS computing the strongly connected components in `compute_fst_properties` is not
+//! parallelized and runs sequentially when the mask requires it.
+
+pub use self::all_pairs_shortest_distance::all_pairs_shortest_distance;
+pub use self::compute_fst_properties::compute_fst_properties;
+pub use self::connect::connect;
+pub use self::reachability::{accessible_states, coaccessible_states};
+pub use self::tr_sort::tr_sort;
+
+mod all_pairs_shortest_distance;
+mod compute_fst_properties;
+mod connect;
+mod reachability;
+mod tr_sort;
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::algorithms::tr_compares::{ILabelCompare, OLabelCompare};
+    use crate::fst_impls::VectorFst;
+    use crate::fst_properties::FstProperties;
+    use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::{StateId, Tr};
+
+    // Adds states that are not accessible or not coaccessible, with trs in a random order.
+    fn add_useless_states(fst: &mut VectorFst<TropicalWeight>, n: usize) {
+        let num_states = fst.num_states() as StateId;
+        for i in 0..n {
+            let w = TropicalWeight::new(i as f32);
+            // Not accessible.
+            let s = fst.add_state();
+            fst.add_tr(s, Tr::new(3, 1, w, (i as StateId) % num_states))
+                .unwrap();
+            fst.add_tr(s, Tr::new(1, 2, w, s)).unwrap();
+            // Not coaccessible.
+            let t = fst.add_state();
+            fst.add_tr(0, Tr::new(2, 3, w, t)).unwrap();
+            fst.add_tr(t, Tr::new(0, 0, w, t)).unwrap();
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_proptest_parallel_connect(mut fst in any::<VectorFst::<TropicalWeight>>(), n in 0usize..5) {
+            add_useless_states(&mut fst, n);
+            let mut fst_seq = fst.clone();
+            crate::algorithms::connect(&mut fst_seq).unwrap();
+            let num_states = fst.num_states();
+            super::connect(&mut fst).unwrap();
+            prop_assert!(fst.num_states() + 2 * n <= num_states);
+            prop_assert_eq!(fst, fst_seq);
+        }
+
+        #[test]
+        fn test_proptest_parallel_tr_sort(mut fst in any::<VectorFst::<TropicalWeight>>(), n in 0usize..5) {
+            add_useless_states(&mut fst, n);
+            let mut fst_seq = fst.clone();
+            crate::algorithms::tr_sort(&mut fst_seq, ILabelCompare {});
+            super::tr_sort(&mut fst, ILabelCompare {});
+            prop_assert_eq!(&fst, &fst_seq);
+            crate::algorithms::tr_sort(&mut fst_seq, OLabelCompare {});
+            super::tr_sort(&mut fst, OLabelCompare {});
+            prop_assert_eq!(fst, fst_seq);
+        }
+
+        #[test]
+        fn test_proptest_parallel_compute_fst_properties(mut fst in any::<VectorFst::<TropicalWeight>>(), n in 0usize..5) {
+            add_useless_states(&mut fst, n);
+            for mask in [
+                FstProperties::all_properties(),
+                FstProperties::STRING | FstProperties::I_DETERMINISTIC,
+                FstProperties::WEIGHTED_CYCLES,
+            ].iter() {
+                let mut known_seq = FstProperties::empty();
+                let props_seq = crate::fst_properties::compute_fst_properties(&fst, *mask, &mut known_seq, false).unwrap();
+                let mut known = FstProperties::empty();
+                let props = super::compute_fst_properties(&fst, *mask, &mut known, false).unwrap();
+                prop_assert_eq!(props, props_seq);
+                prop_assert_eq!(known, known_seq);
+            }
+        }
+
+        #[test]
+        fn test_proptest_parallel_all_pairs_shortest_distance(mut fst in any::<VectorFst::<TropicalWeight>>(), n in 0usize..5) {
+            add_useless_states(&mut fst, n);
+            let d_seq = crate::algorithms::all_pairs_shortest_distance(&fst).unwrap();
+            let d = super::all_pairs_shortest_distance(&fst).unwrap();
+            prop_assert_eq!(d, d_seq);
+        }
+    }
+
+    #[test]
+    fn test_parallel_connect_no_start() {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s = fst.add_state();
+        fst.set_final(s, TropicalWeight::one()).unwrap();
+        super::connect(&mut fst).unwrap();
+        assert_eq!(fst.num_states(), 0);
+        assert_eq!(fst.start(), None);
+    }
+}
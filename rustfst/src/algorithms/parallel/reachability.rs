@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
+
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, Trs};
+
+/// Returns for each state whether it can be reached from the start state.
+pub fn accessible_states<W, F>(fst: &F) -> Vec<bool>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + Sync,
+{
+    let sources = fst.start().into_iter().collect();
+    parallel_bfs(fst.num_states(), sources, |s| {
+        let trs = unsafe { fst.get_trs_unchecked(s) };
+        trs.trs().iter().map(|tr| tr.nextstate).collect()
+    })
+}
+
+/// Returns for each state whether a final state can be reached from it.
+pub fn coaccessible_states<W, F>(fst: &F) -> Vec<bool>
+where
+    W: Semiring,
+    F: ExpandedFst<W> + Sync,
+{
+    let num_states = fst.num_states() as StateId;
+
+    // Reversed transitions sorted by destination state.
+    let mut reversed_trs: Vec<(StateId, StateId)> = (0..num_states)
+        .into_par_iter()
+        .flat_map(|s| {
+            let trs = unsafe { fst.get_trs_unchecked(s) };
+            trs.trs()
+                .iter()
+                .map(|tr| (tr.nextstate, s))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    reversed_trs.par_sort_unstable();
+
+    let sources = (0..num_states)
+        .into_par_iter()
+        .filter(|s| unsafe { fst.is_final_unchecked(*s) })
+        .collect();
+    parallel_bfs(fst.num_states(), sources, |s| {
+        let begin = reversed_trs.partition_point(|(t, _)| *t < s);
+        let end = begin + reversed_trs[begin..].partition_point(|(t, _)| *t == s);
+        reversed_trs[begin..end].iter().map(|(_, p)| *p).collect()
+    })
+}
+
+/// Breadth-first search where all the states of a level are expanded in parallel.
+fn parallel_bfs<N>(num_states: usize, sources: Vec<StateId>, neighbors: N) -> Vec<bool>
+where
+    N: Fn(StateId) -> Vec<StateId> + Sync,
+{
+    let visited: Vec<AtomicBool> = (0..num_states).map(|_| AtomicBool::new(false)).collect();
+    let visit = |s: &StateId| !visited[*s as usize].swap(true, Ordering::Relaxed);
+
+    let mut frontier: Vec<StateId> = sources.into_iter().filter(visit).collect();
+    while !frontier.is_empty() {
+        frontier = frontier
+            .par_iter()
+            .flat_map(|s| neighbors(*s).into_iter().filter(visit).collect::<Vec<_>>())
+            .collect();
+    }
+    visited.into_iter().map(AtomicBool::into_inner).collect()
+}
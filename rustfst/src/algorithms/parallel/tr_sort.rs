@@ -0,0 +1,17 @@
+use crate::algorithms::tr_compares::TrCompare;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::MutableFst;
+use crate::semirings::Semiring;
+
+/// Parallel version of [`tr_sort`](crate::algorithms::tr_sort), the trs of the states being
+/// sorted concurrently through [`MutableFst::par_sort_trs`].
+pub fn tr_sort<W, F, C>(fst: &mut F, _comp: C)
+where
+    W: Semiring + Send,
+    F: MutableFst<W>,
+    C: TrCompare,
+{
+    let props = fst.properties();
+    fst.par_sort_trs(C::compare);
+    fst.set_properties_with_mask(C::properties(props), FstProperties::all_properties());
+}
@@ -0,0 +1,332 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use anyhow::Result;
+
+use crate::algorithms::queues::{natural_less, ShortestFirstQueue};
+use crate::algorithms::ShortestDistanceConfig;
+use crate::algorithms::{fst_convert_from_ref, shortest_distance_with_config, Queue};
+use crate::fst_traits::{AllocableFst, ExpandedFst, MutableFst};
+use crate::semirings::{Semiring, SemiringProperties};
+use crate::{StateId, KSHORTESTDELTA};
+
+/// Configuration for pruning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneConfig<W: Semiring> {
+    /// Paths whose weight is worse than the weight of the best path times
+    /// this threshold are removed.
+    pub weight_threshold: W,
+    /// Maximum number of states to keep in the pruned FST. `None` means no limit.
+    pub state_threshold: Option<usize>,
+    pub delta: f32,
+}
+
+impl<W: Semiring> PruneConfig<W> {
+    pub fn new(weight_threshold: W) -> Self {
+        Self {
+            weight_threshold,
+            state_threshold: None,
+            delta: KSHORTESTDELTA,
+        }
+    }
+
+    pub fn with_state_threshold(self, state_threshold: Option<usize>) -> Self {
+        Self {
+            state_threshold,
+            ..self
+        }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+/// Prune an FST in place by removing all the states and transitions
+/// that don't belong to a successful path whose weight is no more than
+/// the weight of the shortest path times `weight_threshold`.
+///
+/// The weights need to be commutative and have the path property.
+///
+/// # Example
+/// ```
+/// # use rustfst::algorithms::prune;
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{MutableFst, Fst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::{FstPath, Tr};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+/// fst.add_tr(0, Tr::new(2, 2, 5.0, 2))?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// prune(&mut fst, TropicalWeight::new(2.0))?;
+///
+/// let paths: Vec<_> = fst.paths_iter().collect();
+/// assert_eq!(paths, vec![FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0))]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prune<W, F>(fst: &mut F, weight_threshold: W) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    prune_with_config(fst, PruneConfig::new(weight_threshold))
+}
+
+/// Prune an FST in place by removing all the states and transitions
+/// that don't belong to a successful path whose weight is no more than
+/// the weight of the shortest path times `config.weight_threshold`. When
+/// `config.state_threshold` is set, at most that many states are kept.
+///
+/// The weights need to be commutative and have the path property.
+pub fn prune_with_config<W, F>(fst: &mut F, config: PruneConfig<W>) -> Result<()>
+where
+    W: Semiring,
+    F: MutableFst<W>,
+{
+    if !W::properties().contains(SemiringProperties::PATH | SemiringProperties::COMMUTATIVE) {
+        bail!("Prune : Weight needs to be commutative and have the path property")
+    }
+    let num_states = fst.num_states();
+    if num_states == 0 {
+        return Ok(());
+    }
+    let fdistance =
+        shortest_distance_with_config(fst, true, ShortestDistanceConfig::new(config.delta))?;
+
+    let start = match fst.start() {
+        Some(start) => start,
+        None => {
+            fst.del_all_states();
+            return Ok(());
+        }
+    };
+    let start_distance = distance_or_zero(&fdistance, start);
+    if config.state_threshold == Some(0) || start_distance.is_zero() {
+        fst.del_all_states();
+        return Ok(());
+    }
+
+    let limit = start_distance.times(&config.weight_threshold)?;
+    let mut idistance = vec![W::zero(); num_states];
+    // Weight of the best path going through each state found so far, shared with the queue.
+    let path_weights = Rc::new(RefCell::new(vec![W::zero(); num_states]));
+    // The queue can't return errors : the first one raised by a comparison is stored here.
+    let compare_error = Rc::new(RefCell::new(None));
+
+    // States are visited in the natural order of the weight of the best
+    // path going through them.
+    let mut queue = {
+        let path_weights = Rc::clone(&path_weights);
+        let compare_error = Rc::clone(&compare_error);
+        ShortestFirstQueue::new(move |s1: &StateId, s2: &StateId| {
+            let path_weights = path_weights.borrow();
+            compare_path_weights(&path_weights[*s1 as usize], &path_weights[*s2 as usize])
+                .unwrap_or_else(|e| {
+                    compare_error.borrow_mut().get_or_insert(e);
+                    Ordering::Equal
+                })
+        })
+    };
+    let check_compare_error = || match compare_error.borrow_mut().take() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    };
+
+    let mut num_visited = 0;
+    let mut visited = vec![false; num_states];
+    let mut enqueued = vec![false; num_states];
+    // Transitions that must be removed are redirected to this state.
+    let dead = fst.add_state();
+
+    idistance[start as usize] = W::one();
+    path_weights.borrow_mut()[start as usize] = start_distance;
+    visited[start as usize] = true;
+    enqueued[start as usize] = true;
+    queue.enqueue(start);
+    num_visited += 1;
+
+    while let Some(s) = queue.head() {
+        queue.dequeue();
+        check_compare_error()?;
+        enqueued[s as usize] = false;
+        visited[s as usize] = true;
+        let sd = idistance[s as usize].clone();
+
+        if let Some(final_weight) = fst.final_weight(s)? {
+            if natural_less(&limit, &sd.times(final_weight)?)? {
+                fst.delete_final_weight(s)?;
+            }
+        }
+
+        let mut trs = fst.tr_iter_mut(s)?;
+        for idx in 0..trs.len() {
+            let (weight, nextstate) = {
+                let tr = trs.get(idx).unwrap();
+                (sd.times(&tr.weight)?, tr.nextstate)
+            };
+            let path_weight = weight.times(distance_or_zero(&fdistance, nextstate))?;
+            if natural_less(&limit, &path_weight)? {
+                trs.set_nextstate(idx, dead)?;
+                continue;
+            }
+            if natural_less(&weight, &idistance[nextstate as usize])? {
+                idistance[nextstate as usize] = weight;
+                path_weights.borrow_mut()[nextstate as usize] = path_weight;
+            }
+            if visited[nextstate as usize] {
+                continue;
+            }
+            if let Some(state_threshold) = config.state_threshold {
+                if num_visited >= state_threshold {
+                    continue;
+                }
+            }
+            if enqueued[nextstate as usize] {
+                queue.update(nextstate);
+            } else {
+                enqueued[nextstate as usize] = true;
+                queue.enqueue(nextstate);
+                num_visited += 1;
+            }
+            check_compare_error()?;
+        }
+    }
+
+    let mut to_delete = vec![dead];
+    to_delete.extend(
+        visited
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| !**v)
+            .map(|(s, _)| s as StateId),
+    );
+    fst.del_states(to_delete)
+}
+
+/// Orders the states of the queue : the lightest path weight w.r.t. the natural order is the
+/// greatest, i.e. the head of the queue.
+fn compare_path_weights<W: Semiring>(w1: &W, w2: &W) -> Result<Ordering> {
+    Ok(if natural_less(w1, w2)? {
+        Ordering::Greater
+    } else if natural_less(w2, w1)? {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    })
+}
+
+fn distance_or_zero<W: Semiring>(distance: &[W], state: StateId) -> W {
+    distance
+        .get(state as usize)
+        .cloned()
+        .unwrap_or_else(W::zero)
+}
+
+/// Prune an FST and write the result in a new FST, leaving the input untouched.
+/// See [`prune`](fn.prune.html) for the pruning criterion.
+pub fn prune_fst<W, FI, FO>(ifst: &FI, weight_threshold: W) -> Result<FO>
+where
+    W: Semiring,
+    FI: ExpandedFst<W>,
+    FO: MutableFst<W> + AllocableFst<W>,
+{
+    prune_fst_with_config(ifst, PruneConfig::new(weight_threshold))
+}
+
+/// Prune an FST and write the result in a new FST, leaving the input untouched.
+/// See [`prune_with_config`](fn.prune_with_config.html) for the pruning criterion.
+pub fn prune_fst_with_config<W, FI, FO>(ifst: &FI, config: PruneConfig<W>) -> Result<FO>
+where
+    W: Semiring,
+    FI: ExpandedFst<W>,
+    FO: MutableFst<W> + AllocableFst<W>,
+{
+    let mut ofst: FO = fst_convert_from_ref(ifst);
+    prune_with_config(&mut ofst, config)?;
+    Ok(ofst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::Fst;
+    use crate::semirings::TropicalWeight;
+    use crate::{FstPath, Tr};
+    use std::collections::HashSet;
+
+    fn build_fst() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, 1.0, 1))?;
+        fst.add_tr(0, Tr::new(2, 2, 5.0, 2))?;
+        fst.add_tr(0, Tr::new(3, 3, 2.0, 3))?;
+        fst.add_tr(3, Tr::new(4, 4, 10.0, 4))?;
+        fst.set_final(1, TropicalWeight::one())?;
+        fst.set_final(2, TropicalWeight::one())?;
+        fst.set_final(4, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    fn paths(fst: &VectorFst<TropicalWeight>) -> HashSet<FstPath<TropicalWeight>> {
+        fst.paths_iter().collect()
+    }
+
+    #[test]
+    fn test_prune_weight_threshold() -> Result<()> {
+        let mut fst = build_fst()?;
+        prune(&mut fst, TropicalWeight::new(5.0))?;
+
+        let mut paths_ref = HashSet::new();
+        paths_ref.insert(FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0)));
+        paths_ref.insert(FstPath::new(vec![2], vec![2], TropicalWeight::new(5.0)));
+        assert_eq!(paths(&fst), paths_ref);
+        assert_eq!(fst.num_states(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_state_threshold() -> Result<()> {
+        let fst = build_fst()?;
+        let config = PruneConfig::new(TropicalWeight::new(100.0)).with_state_threshold(Some(2));
+        let pruned: VectorFst<_> = prune_fst_with_config(&fst, config)?;
+
+        let mut paths_ref = HashSet::new();
+        paths_ref.insert(FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0)));
+        assert_eq!(paths(&pruned), paths_ref);
+        assert_eq!(pruned.num_states(), 2);
+        // The input is left untouched.
+        assert_eq!(fst, build_fst()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_no_successful_path() -> Result<()> {
+        let mut fst = build_fst()?;
+        for s in 0..5 {
+            fst.delete_final_weight(s)?;
+        }
+        prune(&mut fst, TropicalWeight::new(100.0))?;
+        assert_eq!(fst.num_states(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_path_weights() -> Result<()> {
+        let w = |v: f32| TropicalWeight::new(v);
+        assert_eq!(compare_path_weights(&w(1.0), &w(2.0))?, Ordering::Greater);
+        assert_eq!(compare_path_weights(&w(2.0), &w(1.0))?, Ordering::Less);
+        assert_eq!(compare_path_weights(&w(1.0), &w(1.0))?, Ordering::Equal);
+        Ok(())
+    }
+}
@@ -3,7 +3,6 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 
 use anyhow::Result;
-use binary_heap_plus::{BinaryHeap, FnComparator};
 
 use crate::algorithms::{Queue, QueueType};
 use crate::semirings::Semiring;
@@ -29,9 +28,16 @@ pub fn natural_less<W: Semiring>(w1: &W, w2: &W) -> Result<bool> {
     Ok((&w1.plus(w2)? == w1) && (w1 != w2))
 }
 
+/// Priority queue of states ordered by `compare`, the greatest state being the head.
+/// The position of each state in the heap is tracked so that `update` restores the order
+/// in O(log n) after the priority of a state changed, as OpenFst's `ShortestFirstQueue`
+/// with `update = true`. A state is at most once in the queue.
 #[derive(Clone)]
 pub struct ShortestFirstQueue<C: Clone + FnMut(&StateId, &StateId) -> Ordering> {
-    heap: BinaryHeap<StateId, FnComparator<C>>,
+    heap: Vec<StateId>,
+    // Position of each state in `heap`, `None` if the state isn't in the queue.
+    positions: Vec<Option<usize>>,
+    compare: C,
 }
 
 impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> Debug for ShortestFirstQueue<C> {
@@ -43,26 +49,89 @@ impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> Debug for ShortestFirstQu
 impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> ShortestFirstQueue<C> {
     pub fn new(c: C) -> Self {
         Self {
-            heap: BinaryHeap::new_by(c),
+            heap: vec![],
+            positions: vec![],
+            compare: c,
+        }
+    }
+
+    fn position(&self, state: StateId) -> Option<usize> {
+        self.positions.get(state as usize).cloned().flatten()
+    }
+
+    fn greater(&mut self, i: usize, j: usize) -> bool {
+        let (s1, s2) = (self.heap[i], self.heap[j]);
+        (self.compare)(&s1, &s2) == Ordering::Greater
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.positions[self.heap[i] as usize] = Some(i);
+        self.positions[self.heap[j] as usize] = Some(j);
+    }
+
+    fn sift_up(&mut self, mut i: usize) -> usize {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.greater(i, parent) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+        i
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut greatest = i;
+            for child in [2 * i + 1, 2 * i + 2].iter().cloned() {
+                if child < self.heap.len() && self.greater(child, greatest) {
+                    greatest = child;
+                }
+            }
+            if greatest == i {
+                break;
+            }
+            self.swap(i, greatest);
+            i = greatest;
         }
     }
 }
 
 impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> Queue for ShortestFirstQueue<C> {
     fn head(&mut self) -> Option<StateId> {
-        self.heap.peek().cloned()
+        self.heap.first().cloned()
     }
 
     fn enqueue(&mut self, state: StateId) {
+        if self.position(state).is_some() {
+            return self.update(state);
+        }
+        if self.positions.len() <= state as usize {
+            self.positions.resize(state as usize + 1, None);
+        }
         self.heap.push(state);
+        self.positions[state as usize] = Some(self.heap.len() - 1);
+        self.sift_up(self.heap.len() - 1);
     }
 
     fn dequeue(&mut self) {
-        self.heap.pop();
+        if self.heap.is_empty() {
+            return;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let state = self.heap.pop().unwrap();
+        self.positions[state as usize] = None;
+        self.sift_down(0);
     }
 
-    fn update(&mut self, _state: StateId) {
-        unimplemented!()
+    fn update(&mut self, state: StateId) {
+        if let Some(i) = self.position(state) {
+            let i = self.sift_up(i);
+            self.sift_down(i);
+        }
     }
 
     fn is_empty(&self) -> bool {
@@ -70,7 +139,9 @@ impl<C: Clone + FnMut(&StateId, &StateId) -> Ordering> Queue for ShortestFirstQu
     }
 
     fn clear(&mut self) {
-        self.heap.clear()
+        for state in self.heap.drain(..) {
+            self.positions[state as usize] = None;
+        }
     }
 
     fn queue_type(&self) -> QueueType {
@@ -128,3 +199,40 @@ impl Queue for NaturalShortestFirstQueue {
         self.queue.queue_type()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_shortest_first_queue_update() {
+        let priorities = Rc::new(RefCell::new(vec![5, 3, 8, 1, 7, 2]));
+        let mut queue = {
+            let priorities = Rc::clone(&priorities);
+            ShortestFirstQueue::new(move |s1: &StateId, s2: &StateId| {
+                let priorities = priorities.borrow();
+                priorities[*s2 as usize].cmp(&priorities[*s1 as usize])
+            })
+        };
+        for s in 0..6 {
+            queue.enqueue(s);
+        }
+        assert_eq!(queue.head(), Some(3));
+
+        priorities.borrow_mut()[2] = 0;
+        queue.update(2);
+        priorities.borrow_mut()[3] = 9;
+        queue.update(3);
+        queue.enqueue(4);
+
+        let mut states = vec![];
+        while let Some(s) = queue.head() {
+            queue.dequeue();
+            states.push(s);
+        }
+        assert_eq!(states, vec![2, 5, 1, 0, 4, 3]);
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::Result;
+
+use crate::algorithms::compose::compose;
+use crate::algorithms::randgen::{
+    randgen_with_config, RandGenConfig, TrSelector, UniformTrSelector,
+};
+use crate::algorithms::shortest_distance::shortest_distance_3;
+use crate::fst_impls::VectorFst;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{Semiring, WeightQuantize};
+use crate::utils::acceptor;
+use crate::{FstPath, KDELTA};
+
+/// Configuration for randomized equivalence checks.
+#[derive(Debug, Clone)]
+pub struct RandEquivalentConfig<S> {
+    /// How a transition is selected at a state when sampling paths.
+    pub selector: S,
+    /// Number of paths to sample, alternatively from each FST.
+    pub npath: usize,
+    /// Maximum length of the sampled paths.
+    pub max_length: usize,
+    /// Comparison delta for the weights of the paths.
+    pub delta: f32,
+}
+
+impl<S> RandEquivalentConfig<S> {
+    pub fn new(selector: S) -> Self {
+        Self {
+            selector,
+            npath: 1,
+            max_length: usize::MAX,
+            delta: KDELTA,
+        }
+    }
+
+    pub fn with_npath(self, npath: usize) -> Self {
+        Self { npath, ..self }
+    }
+
+    pub fn with_max_length(self, max_length: usize) -> Self {
+        Self { max_length, ..self }
+    }
+
+    pub fn with_delta(self, delta: f32) -> Self {
+        Self { delta, ..self }
+    }
+}
+
+fn sample_paths<W, F, S>(
+    fst: &F,
+    selector: S,
+    npath: usize,
+    max_length: usize,
+) -> Result<Vec<FstPath<W>>>
+where
+    W: Semiring<Type = f32>,
+    F: Fst<W>,
+    S: TrSelector<W>,
+{
+    if npath == 0 {
+        return Ok(vec![]);
+    }
+    let config = RandGenConfig::new(selector)
+        .with_npath(npath)
+        .with_max_length(max_length);
+    let paths: VectorFst<W> = randgen_with_config(fst, config)?;
+    Ok(paths.paths_iter().collect())
+}
+
+/// Sum of the weights of all the paths of the FST matching both the
+/// input and output labels of `path`.
+fn path_weight<W, F>(fst: &F, path: &FstPath<W>, delta: f32) -> Result<W>
+where
+    W: Semiring + WeightQuantize,
+    W::ReverseWeight: WeightQuantize,
+    F: ExpandedFst<W>,
+{
+    let ipath: VectorFst<W> = acceptor(&path.ilabels, W::one());
+    let opath: VectorFst<W> = acceptor(&path.olabels, W::one());
+    let left: VectorFst<W> = compose::<W, VectorFst<W>, F, _, _, _>(&ipath, fst)?;
+    let restricted: VectorFst<W> =
+        compose::<W, VectorFst<W>, VectorFst<W>, _, _, _>(&left, &opath)?;
+    shortest_distance_3(&restricted, delta)
+}
+
+/// Test whether two FSTs are equivalent by sampling random paths in both
+/// of them and checking that each path has the same weight in both FSTs.
+///
+/// Contrary to [`equivalent`](fn.equivalent.html), this works on any FST
+/// but a positive answer is only correct with high probability.
+pub fn rand_equivalent<W, F1, F2>(fst1: &F1, fst2: &F2, npath: usize) -> Result<bool>
+where
+    W: Semiring<Type = f32> + WeightQuantize,
+    W::ReverseWeight: WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+{
+    let config = RandEquivalentConfig::new(UniformTrSelector::new()).with_npath(npath);
+    rand_equivalent_with_config(fst1, fst2, config)
+}
+
+/// Test whether two FSTs are equivalent by sampling random paths in both
+/// of them, with a configurable tr selector, path length and comparison delta.
+pub fn rand_equivalent_with_config<W, F1, F2, S>(
+    fst1: &F1,
+    fst2: &F2,
+    config: RandEquivalentConfig<S>,
+) -> Result<bool>
+where
+    W: Semiring<Type = f32> + WeightQuantize,
+    W::ReverseWeight: WeightQuantize,
+    F1: ExpandedFst<W>,
+    F2: ExpandedFst<W>,
+    S: TrSelector<W> + Clone,
+{
+    let npath2 = config.npath / 2;
+    let npath1 = config.npath - npath2;
+    let mut paths = sample_paths(fst1, config.selector.clone(), npath1, config.max_length)?;
+    paths.extend(sample_paths(
+        fst2,
+        config.selector,
+        npath2,
+        config.max_length,
+    )?);
+
+    for path in paths.iter() {
+        let weight1 = path_weight(fst1, path, config.delta)?;
+        let weight2 = path_weight(fst2, path, config.delta)?;
+        if !weight1.approx_equal(&weight2, config.delta) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::union::union;
+    use crate::algorithms::{determinize::determinize, minimize};
+    use crate::semirings::TropicalWeight;
+
+    fn build_fst() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3], TropicalWeight::new(1.0));
+        union(
+            &mut fst,
+            &acceptor::<_, VectorFst<_>>(&[1, 2, 4], TropicalWeight::new(2.0)),
+        )?;
+        union(
+            &mut fst,
+            &acceptor::<_, VectorFst<_>>(&[1, 5], TropicalWeight::new(3.0)),
+        )?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_rand_equivalent_after_optimization() -> Result<()> {
+        let fst = build_fst()?;
+        let mut optimized: VectorFst<_> = determinize(&fst)?;
+        minimize(&mut optimized)?;
+
+        let config = RandEquivalentConfig::new(UniformTrSelector::from_seed(42)).with_npath(20);
+        assert!(rand_equivalent_with_config(&fst, &optimized, config)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rand_equivalent_different_weights() -> Result<()> {
+        let fst = build_fst()?;
+        let mut other = build_fst()?;
+        union(
+            &mut other,
+            &acceptor::<_, VectorFst<_>>(&[6], TropicalWeight::new(1.0)),
+        )?;
+        let mut modified = build_fst()?;
+        union(
+            &mut modified,
+            &acceptor::<_, VectorFst<_>>(&[1, 5], TropicalWeight::new(0.5)),
+        )?;
+
+        let config = RandEquivalentConfig::new(UniformTrSelector::from_seed(42)).with_npath(50);
+        assert!(!rand_equivalent_with_config(&fst, &other, config.clone())?);
+        assert!(!rand_equivalent_with_config(&fst, &modified, config)?);
+        Ok(())
+    }
+}
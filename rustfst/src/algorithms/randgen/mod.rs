@@ -5,7 +5,9 @@ use crate::fst_impls::VectorFst;
 pub use randgen_config::RandGenConfig;
 pub use randgen_fst::RandGenFst;
 use tr_sampler::TrSampler;
-pub use tr_selector::{TrSelector, UniformTrSelector};
+pub use tr_selector::{
+    FastLogProbTrSelector, LogProbTrSelector, ProbabilisticWeight, TrSelector, UniformTrSelector,
+};
 
 use crate::fst_traits::Fst;
 use crate::prelude::dfs_visit::dfs_visit;
@@ -27,7 +29,7 @@ pub fn randgen_with_config<
     W: Semiring<Type = f32>,
     FI: Fst<W>,
     FO: MutableFst<W>,
-    S: TrSelector,
+    S: TrSelector<W>,
 >(
     ifst: &FI,
     config: RandGenConfig<S>,
@@ -63,8 +65,10 @@ pub fn randgen<W: Semiring<Type = f32>, FI: Fst<W>, FO: MutableFst<W>>(ifst: &FI
 mod tests {
     use super::*;
     use crate::algorithms::union::union;
-    use crate::semirings::TropicalWeight;
+    use crate::fst_traits::{CoreFst, StateIterator};
+    use crate::semirings::{ProbabilityWeight, TropicalWeight};
     use crate::utils::acceptor;
+    use crate::StateId;
 
     #[test]
     fn test_randgen_weighted() -> Result<()> {
@@ -113,4 +117,98 @@ mod tests {
 
         Ok(())
     }
+
+    fn biased_fst() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, -(0.8f32.ln()), 1)?;
+        fst.emplace_tr(0, 2, 2, -(0.2f32.ln()), 1)?;
+        fst.emplace_tr(1, 3, 3, -(0.5f32.ln()), 2)?;
+        fst.set_final(1, -(0.5f32.ln()))?;
+        fst.set_final(2, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    fn sample_counts<W: Semiring, S: TrSelector<W>>(
+        selector: &mut S,
+        fst: &VectorFst<W>,
+        state: StateId,
+        nsamples: usize,
+    ) -> Result<Vec<usize>> {
+        let mut counts = vec![0; fst.num_trs(state)? + 1];
+        for _ in 0..nsamples {
+            counts[selector.select_tr(fst, state)?] += 1;
+        }
+        Ok(counts)
+    }
+
+    #[test]
+    fn test_log_prob_tr_selector() -> Result<()> {
+        let fst = biased_fst()?;
+
+        let mut selector = LogProbTrSelector::from_seed(2022);
+        let counts = sample_counts(&mut selector, &fst, 0, 10000)?;
+        // The start state is not final : the stop option is never selected.
+        assert_eq!(counts[2], 0);
+        assert!((counts[0] as f32 / 10000.0 - 0.8).abs() < 0.02);
+
+        // Stopping at state 1 is as likely as following its only tr.
+        let counts = sample_counts(&mut selector, &fst, 1, 10000)?;
+        assert!((counts[1] as f32 / 10000.0 - 0.5).abs() < 0.02);
+
+        // Only the stop option is available at state 2.
+        let counts = sample_counts(&mut selector, &fst, 2, 100)?;
+        assert_eq!(counts, vec![100]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_log_prob_tr_selector() -> Result<()> {
+        let fst = biased_fst()?;
+
+        let mut selector = LogProbTrSelector::from_seed(42);
+        let mut fast_selector = FastLogProbTrSelector::from_seed(&fst, 42)?;
+        for state in fst.states_iter() {
+            assert_eq!(
+                sample_counts(&mut selector, &fst, state, 1000)?,
+                sample_counts(&mut fast_selector, &fst, state, 1000)?
+            );
+        }
+
+        let config = RandGenConfig::new(FastLogProbTrSelector::from_seed(&fst, 2022)?)
+            .with_npath(100)
+            .with_weighted(false);
+        let res: VectorFst<_> = randgen_with_config(&fst, config)?;
+        for path in res.paths_iter() {
+            assert!(path.ilabels.len() == 1 || path.ilabels.len() == 2);
+            assert!(path.ilabels[0] == 1 || path.ilabels[0] == 2);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_prob_tr_selector_probability_weight() -> Result<()> {
+        let mut fst: VectorFst<ProbabilityWeight> = VectorFst::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 0.8, 1)?;
+        fst.emplace_tr(0, 2, 2, 0.2, 1)?;
+        fst.set_final(1, ProbabilityWeight::one())?;
+
+        let mut selector = LogProbTrSelector::from_seed(2022);
+        let counts = sample_counts(&mut selector, &fst, 0, 10000)?;
+        assert!((counts[0] as f32 / 10000.0 - 0.8).abs() < 0.02);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_log_prob_tr_selector_other_fst() -> Result<()> {
+        let fst = biased_fst()?;
+        let mut selector = FastLogProbTrSelector::from_seed(&fst, 42)?;
+        let other: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3, 4], TropicalWeight::one());
+        assert!(selector.select_tr(&other, 0).is_err());
+        assert!(selector.select_tr(&other, 3).is_err());
+        Ok(())
+    }
 }
@@ -1,7 +1,5 @@
-use crate::algorithms::randgen::TrSelector;
-
 /// Configuration struct for random path generation.
-pub struct RandGenConfig<S: TrSelector> {
+pub struct RandGenConfig<S> {
     /// How an arc is selected at a state.
     pub selector: S,
     /// Maximum path length.
@@ -14,7 +12,7 @@ pub struct RandGenConfig<S: TrSelector> {
     pub remove_total_weight: bool,
 }
 
-impl<S: TrSelector> RandGenConfig<S> {
+impl<S> RandGenConfig<S> {
     pub fn new(selector: S) -> Self {
         Self {
             selector,
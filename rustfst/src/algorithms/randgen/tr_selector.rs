@@ -1,7 +1,9 @@
-use crate::prelude::Fst;
-use crate::{Semiring, StateId};
-use anyhow::Result;
+use crate::fst_traits::{ExpandedFst, Fst};
+use crate::semirings::{LogWeight, ProbabilityWeight, TropicalWeight};
+use crate::{Semiring, StateId, Trs};
+use anyhow::{bail, Result};
 use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
 use rand_chacha::rand_core::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::fmt::Debug;
@@ -11,8 +13,33 @@ use std::fmt::Debug;
 /// `fst.num_trs(s)`, then the final weight is selected; otherwise the `N`-th transition is
 /// selected. It is assumed these are not applied to any state which is neither
 /// final nor has any arcs leaving it.
-pub trait TrSelector: Debug {
-    fn select_tr<W: Semiring, F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize>;
+pub trait TrSelector<W: Semiring>: Debug {
+    fn select_tr<F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize>;
+}
+
+/// Semirings whose weights can be read as probabilities, e.g to sample transitions
+/// w.r.t. their weights.
+pub trait ProbabilisticWeight: Semiring {
+    /// Negative log of the probability represented by the weight.
+    fn neg_log_prob(&self) -> f64;
+}
+
+impl ProbabilisticWeight for TropicalWeight {
+    fn neg_log_prob(&self) -> f64 {
+        *self.value() as f64
+    }
+}
+
+impl ProbabilisticWeight for LogWeight {
+    fn neg_log_prob(&self) -> f64 {
+        *self.value() as f64
+    }
+}
+
+impl ProbabilisticWeight for ProbabilityWeight {
+    fn neg_log_prob(&self) -> f64 {
+        -(*self.value() as f64).ln()
+    }
 }
 
 /// Randomly selects a transition using the uniform distribution.
@@ -40,8 +67,8 @@ impl UniformTrSelector {
     }
 }
 
-impl TrSelector for UniformTrSelector {
-    fn select_tr<W: Semiring, F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize> {
+impl<W: Semiring> TrSelector<W> for UniformTrSelector {
+    fn select_tr<F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize> {
         let mut n = fst.num_trs(state)?;
         if fst.is_final(state)? {
             n += 1;
@@ -51,3 +78,128 @@ impl TrSelector for UniformTrSelector {
         Ok(res)
     }
 }
+
+/// Cumulative probabilities of the trs leaving `state` followed by the one of stopping at this
+/// state. The probabilities are relative to the most likely choice to avoid underflows and are
+/// not normalized.
+fn cumulative_probs<W: ProbabilisticWeight, F: Fst<W>>(
+    fst: &F,
+    state: StateId,
+) -> Result<Vec<f64>> {
+    let mut neg_log_probs = fst
+        .get_trs(state)?
+        .trs()
+        .iter()
+        .map(|tr| tr.weight.neg_log_prob())
+        .collect::<Vec<_>>();
+    neg_log_probs.push(
+        fst.final_weight(state)?
+            .map_or(f64::INFINITY, |w| w.neg_log_prob()),
+    );
+    let min = neg_log_probs.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mut sum = 0.0;
+    Ok(neg_log_probs
+        .into_iter()
+        .map(|w| {
+            if min.is_finite() {
+                sum += (min - w).exp();
+            }
+            sum
+        })
+        .collect())
+}
+
+/// Index of the first cumulative probability above a threshold drawn uniformly between zero and
+/// the total probability. The last index, i.e. stopping, is returned when all the probabilities
+/// are null.
+fn select_from_cumulative_probs<R: Rng>(rng: &mut R, cumulative_probs: &[f64]) -> usize {
+    let total = cumulative_probs[cumulative_probs.len() - 1];
+    let threshold = rng.gen::<f64>() * total;
+    cumulative_probs
+        .partition_point(|p| *p <= threshold)
+        .min(cumulative_probs.len() - 1)
+}
+
+/// Randomly selects a transition w.r.t. the probabilities of the weights after normalizing for
+/// the total weight leaving the state. The final weight is the weight of stopping at the state.
+#[derive(Debug, Clone)]
+pub struct LogProbTrSelector {
+    rng: ChaCha8Rng,
+}
+
+impl Default for LogProbTrSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogProbTrSelector {
+    pub fn new() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<W: ProbabilisticWeight> TrSelector<W> for LogProbTrSelector {
+    fn select_tr<F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize> {
+        let cumulative_probs = cumulative_probs(fst, state)?;
+        Ok(select_from_cumulative_probs(
+            &mut self.rng,
+            &cumulative_probs,
+        ))
+    }
+}
+
+/// Same as `LogProbTrSelector` but the cumulative distributions of all the states of the FST
+/// are computed once when the selector is built instead of at each selection, which is faster
+/// when many paths are generated. The selector must only be used with the FST it was built from.
+#[derive(Debug, Clone)]
+pub struct FastLogProbTrSelector {
+    rng: ChaCha8Rng,
+    cumulative_probs: Vec<Vec<f64>>,
+}
+
+impl FastLogProbTrSelector {
+    pub fn new<W: ProbabilisticWeight, F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        Self::from_rng(fst, ChaCha8Rng::from_entropy())
+    }
+    pub fn from_seed<W: ProbabilisticWeight, F: ExpandedFst<W>>(
+        fst: &F,
+        seed: u64,
+    ) -> Result<Self> {
+        Self::from_rng(fst, ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    fn from_rng<W: ProbabilisticWeight, F: ExpandedFst<W>>(
+        fst: &F,
+        rng: ChaCha8Rng,
+    ) -> Result<Self> {
+        let cumulative_probs = fst
+            .states_iter()
+            .map(|s| cumulative_probs(fst, s))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            rng,
+            cumulative_probs,
+        })
+    }
+}
+
+impl<W: ProbabilisticWeight> TrSelector<W> for FastLogProbTrSelector {
+    fn select_tr<F: Fst<W>>(&mut self, fst: &F, state: StateId) -> Result<usize> {
+        let probs = match self.cumulative_probs.get(state as usize) {
+            Some(probs) if probs.len() == fst.num_trs(state)? + 1 => probs,
+            _ => bail!(
+                "FastLogProbTrSelector : state {} doesn't match the FST the selector was built from",
+                state
+            ),
+        };
+        Ok(select_from_cumulative_probs(&mut self.rng, probs))
+    }
+}
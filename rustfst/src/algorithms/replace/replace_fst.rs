@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::algorithms::lazy::{LazyFst, SimpleHashMapCache};
+use crate::algorithms::lazy::{FstCache, LazyFst, SimpleHashMapCache};
 use crate::algorithms::replace::config::ReplaceFstOptions;
 use crate::algorithms::replace::replace_fst_op::ReplaceFstOp;
 use crate::fst_properties::FstProperties;
@@ -12,12 +12,14 @@ use crate::fst_traits::{AllocableFst, CoreFst, Fst, FstIterator, MutableFst, Sta
 use crate::semirings::Semiring;
 use crate::{Label, StateId, SymbolTable, TrsVec};
 
-type InnerLazyFst<W, F, B> = LazyFst<W, ReplaceFstOp<W, F, B>, SimpleHashMapCache<W>>;
+type InnerLazyFst<W, F, B, Cache> = LazyFst<W, ReplaceFstOp<W, F, B>, Cache>;
 
 /// ReplaceFst supports lazy replacement of trs in one FST with another FST.
 /// This replacement is recursive. ReplaceFst can be used to support a variety of
 /// delayed constructions such as recursive transition networks, union, or closure.
-pub struct ReplaceFst<W: Semiring, F: Fst<W>, B: Borrow<F>>(InnerLazyFst<W, F, B>);
+pub struct ReplaceFst<W: Semiring, F: Fst<W>, B: Borrow<F>, Cache = SimpleHashMapCache<W>>(
+    InnerLazyFst<W, F, B, Cache>,
+);
 
 impl<W, F, B> ReplaceFst<W, F, B>
 where
@@ -26,6 +28,30 @@ where
     B: Borrow<F>,
 {
     pub fn new(fst_list: Vec<(Label, B)>, root: Label, epsilon_on_replace: bool) -> Result<Self> {
+        Self::new_with_cache(
+            fst_list,
+            root,
+            epsilon_on_replace,
+            SimpleHashMapCache::default(),
+        )
+    }
+}
+
+impl<W, F, B, Cache> ReplaceFst<W, F, B, Cache>
+where
+    W: Semiring,
+    F: Fst<W>,
+    B: Borrow<F>,
+    Cache: FstCache<W>,
+{
+    /// Same as `new` but the trs and final weights are stored in `fst_cache`. For instance, a
+    /// `ShardedHashMapCache` allows to share the ReplaceFst between threads without contention.
+    pub fn new_with_cache(
+        fst_list: Vec<(Label, B)>,
+        root: Label,
+        epsilon_on_replace: bool,
+        fst_cache: Cache,
+    ) -> Result<Self> {
         let mut isymt = None;
         let mut osymt = None;
         if let Some(first_elt) = fst_list.first() {
@@ -34,7 +60,6 @@ where
         }
         let opts = ReplaceFstOptions::new(root, epsilon_on_replace);
         let fst_op = ReplaceFstOp::new(fst_list, opts)?;
-        let fst_cache = SimpleHashMapCache::default();
         Ok(ReplaceFst(LazyFst::from_op_and_cache(
             fst_op, fst_cache, isymt, osymt,
         )))
@@ -46,11 +71,12 @@ where
     }
 }
 
-impl<W, F, B> CoreFst<W> for ReplaceFst<W, F, B>
+impl<W, F, B, Cache> CoreFst<W> for ReplaceFst<W, F, B, Cache>
 where
     W: Semiring,
     F: Fst<W>,
     B: Borrow<F>,
+    Cache: FstCache<W>,
 {
     type TRS = TrsVec<W>;
 
@@ -95,37 +121,40 @@ where
     }
 }
 
-impl<'a, W, F, B> StateIterator<'a> for ReplaceFst<W, F, B>
+impl<'a, W, F, B, Cache> StateIterator<'a> for ReplaceFst<W, F, B, Cache>
 where
     W: Semiring,
     F: Fst<W> + 'a,
     B: Borrow<F> + 'a,
+    Cache: FstCache<W> + 'a,
 {
-    type Iter = <InnerLazyFst<W, F, B> as StateIterator<'a>>::Iter;
+    type Iter = <InnerLazyFst<W, F, B, Cache> as StateIterator<'a>>::Iter;
 
     fn states_iter(&'a self) -> Self::Iter {
         self.0.states_iter()
     }
 }
 
-impl<'a, W, F, B> FstIterator<'a, W> for ReplaceFst<W, F, B>
+impl<'a, W, F, B, Cache> FstIterator<'a, W> for ReplaceFst<W, F, B, Cache>
 where
     W: Semiring,
     F: Fst<W> + 'a,
     B: Borrow<F> + 'a,
+    Cache: FstCache<W> + 'a,
 {
-    type FstIter = <InnerLazyFst<W, F, B> as FstIterator<'a, W>>::FstIter;
+    type FstIter = <InnerLazyFst<W, F, B, Cache> as FstIterator<'a, W>>::FstIter;
 
     fn fst_iter(&'a self) -> Self::FstIter {
         self.0.fst_iter()
     }
 }
 
-impl<W, F, B> Fst<W> for ReplaceFst<W, F, B>
+impl<W, F, B, Cache> Fst<W> for ReplaceFst<W, F, B, Cache>
 where
     W: Semiring,
     F: Fst<W> + 'static,
     B: Borrow<F> + 'static,
+    Cache: FstCache<W> + 'static,
 {
     fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
         self.0.input_symbols()
@@ -152,11 +181,12 @@ where
     }
 }
 
-impl<W, F, B> Debug for ReplaceFst<W, F, B>
+impl<W, F, B, Cache> Debug for ReplaceFst<W, F, B, Cache>
 where
     W: Semiring,
     F: Fst<W>,
     B: Borrow<F>,
+    Cache: FstCache<W>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
@@ -165,8 +195,11 @@ where
 
 #[cfg(test)]
 mod test {
+    use crate::algorithms::lazy::ShardedHashMapCache;
+    use crate::fst;
     use crate::fst_impls::VectorFst;
     use crate::semirings::TropicalWeight;
+    use crate::utils::{acceptor, transducer};
 
     use super::*;
 
@@ -175,4 +208,43 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<ReplaceFst<TropicalWeight, VectorFst<_>, VectorFst<_>>>();
     }
+
+    #[test]
+    fn test_replace_fst_send_sync_sharded_cache() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<
+            ReplaceFst<
+                TropicalWeight,
+                VectorFst<TropicalWeight>,
+                Arc<VectorFst<TropicalWeight>>,
+                ShardedHashMapCache<TropicalWeight>,
+            >,
+        >();
+    }
+
+    #[test]
+    fn test_replace_fst_shared_between_threads() -> Result<()> {
+        let root: VectorFst<TropicalWeight> = fst![1, 10, 2, 10, 3];
+        let sub: VectorFst<TropicalWeight> = fst![4, 5 => 6];
+        let fst_list = vec![(0, Arc::new(root)), (10, Arc::new(sub))];
+        let expected: VectorFst<_> =
+            ReplaceFst::<_, VectorFst<_>, _>::new(fst_list.clone(), 0, false)?.compute()?;
+
+        let lazy_fst = Arc::new(ReplaceFst::<_, VectorFst<_>, _, _>::new_with_cache(
+            fst_list,
+            0,
+            false,
+            ShardedHashMapCache::new(4),
+        )?);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let lazy_fst = Arc::clone(&lazy_fst);
+                std::thread::spawn(move || lazy_fst.compute::<VectorFst<_>>())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap()?, expected);
+        }
+        Ok(())
+    }
 }
@@ -335,10 +335,9 @@ pub fn shortest_distance_with_config<W: Semiring, F: ExpandedFst<W>>(
     }
 }
 
-#[allow(unused)]
 /// Return the sum of the weight of all successful paths in an FST, i.e., the
 /// shortest-distance from the initial state to the final states..
-fn shortest_distance_3<W: Semiring + WeightQuantize, F: MutableFst<W>>(
+pub(crate) fn shortest_distance_3<W: Semiring + WeightQuantize, F: MutableFst<W>>(
     fst: &F,
     delta: f32,
 ) -> Result<W>
@@ -1,23 +1,34 @@
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 use anyhow::Result;
+use binary_heap_plus::{BinaryHeap, Compare};
 use unsafe_unwrap::UnsafeUnwrap;
 
-use crate::algorithms::determinize::determinize_with_distance;
+use crate::algorithms::determinize::{
+    determinize_with_distance, DeterminizeConfig, DeterminizeFst, DeterminizeType,
+};
 use crate::algorithms::queues::AutoQueue;
+use crate::algorithms::rm_epsilon::rm_epsilon;
 use crate::algorithms::tr_filters::AnyTrFilter;
+use crate::algorithms::weight_converters::ToGallicConverter;
 use crate::algorithms::{
-    connect, reverse, shortest_distance_with_config, Queue, ShortestDistanceConfig,
+    connect, fst_convert_from_ref, invert, reverse, shortest_distance_with_config, weight_convert,
+    Queue, ShortestDistanceConfig,
 };
 use crate::fst_impls::VectorFst;
+use crate::fst_path::FstPath;
 use crate::fst_properties::mutable_properties::shortest_path_properties;
 use crate::fst_properties::FstProperties;
-use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst};
+use crate::fst_traits::{CoreFst, ExpandedFst, Fst, MutableFst};
 use crate::semirings::{
-    ReverseBack, Semiring, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize,
+    GallicWeightMin, ReverseBack, Semiring, SemiringProperties, StringWeightVariant,
+    WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::Tr;
-use crate::{StateId, Trs, KSHORTESTDELTA};
+use crate::{Label, StateId, Trs, EPS_LABEL, KSHORTESTDELTA};
 use bitflags::_core::fmt::Formatter;
 use std::fmt::Debug;
 
@@ -172,6 +183,155 @@ where
     Ok(fst_res)
 }
 
+/// Create an FST containing the n-shortest paths in the input FST with distinct output
+/// strings. Paths sharing the same output string are merged beforehand, keeping the best one,
+/// so that the n returned paths have n different output strings. Output epsilons are ignored
+/// when comparing output strings.
+///
+/// The inverted FST is determinized with a lazy `DeterminizeFst` in which the n-shortest paths
+/// are searched, so that only the states needed to find them are determinized. As the search
+/// explores the paths by increasing weight, extending a path must never make it lighter
+/// (e.g no negative weight in the tropical semiring), an error is returned otherwise.
+///
+/// The paths being already unique, an error is returned if the `unique` field of the config is
+/// set.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # use rustfst::algorithms::{shortest_path_unique_output, ShortestPathConfig};
+/// # use rustfst::EPS_LABEL;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, 10, 1.0, 1)?;
+/// fst.emplace_tr(0, 2, EPS_LABEL, 1.5, 2)?;
+/// fst.emplace_tr(2, 3, 10, 0.0, 1)?;
+/// fst.emplace_tr(0, 4, 11, 2.0, 1)?;
+/// fst.set_final(1, TropicalWeight::one())?;
+///
+/// let config = ShortestPathConfig::default().with_nshortest(2);
+/// let nbest: VectorFst<_> = shortest_path_unique_output(&fst, config)?;
+///
+/// let mut olabels: Vec<_> = nbest.paths_iter().map(|p| p.olabels).collect();
+/// olabels.sort();
+/// assert_eq!(olabels, vec![vec![10], vec![11]]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn shortest_path_unique_output<W, FI, FO>(ifst: &FI, config: ShortestPathConfig) -> Result<FO>
+where
+    FI: ExpandedFst<W>,
+    FO: MutableFst<W>,
+    W: WeaklyDivisibleSemiring + WeightQuantize + 'static,
+{
+    if config.unique {
+        bail!("shortest_path_unique_output : the paths have distinct output strings, unique must not be set")
+    }
+    if !W::properties().contains(SemiringProperties::PATH) {
+        bail!("shortest_path_unique_output : weight needs to have the path property")
+    }
+    let mut fst_res = FO::new();
+    fst_res.set_symts_from_fst(ifst);
+    if config.nshortest == 0 {
+        return Ok(fst_res);
+    }
+
+    let mut inverted: VectorFst<W> = fst_convert_from_ref(ifst);
+    invert(&mut inverted);
+    let (encoded, input_strings) = encode_input_strings(&inverted)?;
+    let det_config = DeterminizeConfig::default()
+        .with_delta(config.delta)
+        .with_det_type(DeterminizeType::DeterminizeDisambiguate);
+    let dfst = DeterminizeFst::new_with_config(encoded, det_config)?;
+
+    for path in ShortestPathsIterator::new(&dfst)?.with_nshortest(config.nshortest) {
+        let path = path?;
+        let ilabels: Vec<_> = path
+            .olabels
+            .iter()
+            .flat_map(|l| input_strings[*l as usize].iter().cloned())
+            .collect();
+        // The output strings are the input strings of the inverted FST.
+        let olabels = path.ilabels;
+        if fst_res.start().is_none() {
+            let start = fst_res.add_state();
+            fst_res.set_start(start)?;
+        }
+        let mut state = unsafe { fst_res.start().unsafe_unwrap() };
+        for i in 0..std::cmp::max(ilabels.len(), olabels.len()) {
+            let nextstate = fst_res.add_state();
+            fst_res.add_tr(
+                state,
+                Tr::new(
+                    ilabels.get(i).cloned().unwrap_or(EPS_LABEL),
+                    olabels.get(i).cloned().unwrap_or(EPS_LABEL),
+                    W::one(),
+                    nextstate,
+                ),
+            )?;
+            state = nextstate;
+        }
+        fst_res.set_final(state, path.weight)?;
+    }
+    Ok(fst_res)
+}
+
+/// Removes the input epsilons of a transducer : its Gallic encoding is epsilon-removed and each
+/// output string left on a transition is then encoded as a single output label. The final
+/// weights become transitions with an input epsilon to a super-final state so that all the
+/// paths end with exactly one input epsilon. Returns the encoded transducer along with the
+/// output string of each label.
+fn encode_input_strings<W>(fst: &VectorFst<W>) -> Result<(VectorFst<W>, Vec<Vec<Label>>)>
+where
+    W: WeaklyDivisibleSemiring + WeightQuantize,
+{
+    let mut fsa: VectorFst<GallicWeightMin<W>> = weight_convert(fst, &mut ToGallicConverter {})?;
+    // The input epsilons are epsilon transitions of the gallic acceptor : their output
+    // labels end up in the string weights.
+    rm_epsilon(&mut fsa)?;
+
+    // The empty string is encoded as an epsilon.
+    let mut strings = vec![vec![]];
+    let mut labels = HashMap::new();
+    let mut encode = |weight: &GallicWeightMin<W>| -> Result<Label> {
+        match weight.value1().value() {
+            StringWeightVariant::Infinity => bail!("Unexpected infinity"),
+            StringWeightVariant::Labels(l) if l.is_empty() => Ok(EPS_LABEL),
+            StringWeightVariant::Labels(l) => Ok(*labels.entry(l.clone()).or_insert_with(|| {
+                strings.push(l.clone());
+                (strings.len() - 1) as Label
+            })),
+        }
+    };
+
+    let mut encoded = VectorFst::new();
+    encoded.add_states(fsa.num_states() + 1);
+    let superfinal = fsa.num_states() as StateId;
+    encoded.set_final(superfinal, W::one())?;
+    if let Some(start) = fsa.start() {
+        encoded.set_start(start)?;
+    }
+    for state in fsa.states_range() {
+        for tr in fsa.get_trs(state)?.trs() {
+            let olabel = encode(&tr.weight)?;
+            let tr = Tr::new(tr.ilabel, olabel, tr.weight.value2().clone(), tr.nextstate);
+            encoded.add_tr(state, tr)?;
+        }
+        if let Some(final_weight) = fsa.final_weight(state)? {
+            let olabel = encode(&final_weight)?;
+            let tr = Tr::new(EPS_LABEL, olabel, final_weight.value2().clone(), superfinal);
+            encoded.add_tr(state, tr)?;
+        }
+    }
+    Ok((encoded, strings))
+}
+
 fn single_shortest_path<W, F>(
     ifst: &F,
     distance: &mut Vec<W>,
@@ -290,6 +450,275 @@ pub fn natural_less<W: Semiring>(w1: &W, w2: &W) -> Result<bool> {
     Ok((&w1.plus(w2)? == w1) && (w1 != w2))
 }
 
+/// Iterator on the successful paths of an FST in increasing weight order w.r.t. the natural
+/// semiring order. The paths are computed lazily with a best-first search, so it can be stopped
+/// at any time, works on cyclic FSTs accepting infinitely many paths and only expands the states
+/// of a lazy FST that it needs.
+///
+/// By default the partial paths are explored by increasing weight, which requires that extending
+/// a path never makes it lighter (e.g no negative weight in the tropical semiring) : an error is
+/// yielded otherwise. With `new_with_distance`, the shortest distance to the final states is used
+/// as A* heuristic instead and any weight with the path property is supported.
+///
+/// The partial paths are kept in memory until the iterator is dropped. When only the n-shortest
+/// paths are needed, `with_nshortest` bounds the memory by expanding each state at most n times.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{Fst, MutableFst};
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, 1, 1.0, 0)?;
+/// fst.emplace_tr(0, 2, 2, 0.5, 1)?;
+/// fst.set_final(1, TropicalWeight::one())?;
+///
+/// let weights = fst
+///     .shortest_paths_iter()?
+///     .take(3)
+///     .map(|p| p.map(|p| *p.weight.value()))
+///     .collect::<Result<Vec<_>>>()?;
+/// assert_eq!(weights, vec![0.5, 1.5, 2.5]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ShortestPathsIterator<'a, W: Semiring, F: Fst<W>> {
+    fst: &'a F,
+    // Shortest distance from each state to the final states, used as heuristic if present.
+    distance: Option<Vec<W>>,
+    nshortest: Option<usize>,
+    // Number of times each state has been expanded, only tracked with `nshortest`.
+    num_expansions: Vec<usize>,
+    num_paths: usize,
+    // Partial paths explored so far, each one pointing to the path it extends.
+    entries: Vec<PartialPath<W>>,
+    // Candidate partial paths with their priority, the lowest on top.
+    heap: BinaryHeap<(W, usize), PriorityCompare>,
+    // First error raised by a comparison of the heap, which can't return it.
+    compare_error: Rc<RefCell<Option<anyhow::Error>>>,
+    failed: bool,
+}
+
+// Puts the lowest priority on top of the heap, ties are broken by exploration order.
+#[derive(Clone)]
+struct PriorityCompare {
+    error: Rc<RefCell<Option<anyhow::Error>>>,
+}
+
+impl<W: Semiring> Compare<(W, usize)> for PriorityCompare {
+    fn compare(&mut self, (wi, idi): &(W, usize), (wj, idj): &(W, usize)) -> Ordering {
+        compare_priorities(wi, wj)
+            .map(|ordering| ordering.then_with(|| idj.cmp(idi)))
+            .unwrap_or_else(|e| {
+                self.error.borrow_mut().get_or_insert(e);
+                Ordering::Equal
+            })
+    }
+}
+
+fn compare_priorities<W: Semiring>(wi: &W, wj: &W) -> Result<Ordering> {
+    Ok(if natural_less(wi, wj)? {
+        Ordering::Greater
+    } else if natural_less(wj, wi)? {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    })
+}
+
+struct PartialPath<W> {
+    parent: Option<usize>,
+    ilabel: Label,
+    olabel: Label,
+    // None once the final weight has been added to the path.
+    state: Option<StateId>,
+    weight: W,
+}
+
+impl<'a, W: Semiring, F: Fst<W>> ShortestPathsIterator<'a, W, F> {
+    pub fn new(fst: &'a F) -> Result<Self> {
+        Self::new_with_optional_distance(fst, None)
+    }
+
+    /// Uses `distance`, the shortest distance from each state to the final states as computed
+    /// by `shortest_distance(fst, true)`, as A* heuristic.
+    pub fn new_with_distance(fst: &'a F, distance: Vec<W>) -> Result<Self> {
+        Self::new_with_optional_distance(fst, Some(distance))
+    }
+
+    fn new_with_optional_distance(fst: &'a F, distance: Option<Vec<W>>) -> Result<Self> {
+        if !W::properties().contains(SemiringProperties::PATH) {
+            bail!("ShortestPathsIterator : weight needs to have the path property")
+        }
+        let compare_error = Rc::new(RefCell::new(None));
+        let heap = BinaryHeap::from_vec_cmp(
+            vec![],
+            PriorityCompare {
+                error: Rc::clone(&compare_error),
+            },
+        );
+        let mut iter = Self {
+            fst,
+            distance,
+            nshortest: None,
+            num_expansions: vec![],
+            num_paths: 0,
+            entries: vec![],
+            heap,
+            compare_error,
+            failed: false,
+        };
+        if let Some(start) = fst.start() {
+            iter.push(PartialPath {
+                parent: None,
+                ilabel: EPS_LABEL,
+                olabel: EPS_LABEL,
+                state: Some(start),
+                weight: W::one(),
+            })?;
+        }
+        Ok(iter)
+    }
+
+    /// Stops after the `nshortest` first paths. Each state is then expanded at most `nshortest`
+    /// times, as none of the n-shortest paths goes through a state with a prefix that is not
+    /// among the n lightest prefixes reaching it.
+    pub fn with_nshortest(self, nshortest: usize) -> Self {
+        Self {
+            nshortest: Some(nshortest),
+            ..self
+        }
+    }
+
+    fn push(&mut self, partial_path: PartialPath<W>) -> Result<()> {
+        let priority = match (partial_path.state, &self.distance) {
+            (Some(state), Some(distance)) => match distance.get(state as usize) {
+                Some(d) if d.is_zero() => return Ok(()),
+                Some(d) => partial_path.weight.times(d)?,
+                None => bail!(
+                    "ShortestPathsIterator : no shortest distance for state {}, it must be computed on the same FST",
+                    state
+                ),
+            },
+            _ if partial_path.weight.is_zero() => return Ok(()),
+            _ => partial_path.weight.clone(),
+        };
+        self.heap.push((priority, self.entries.len()));
+        self.entries.push(partial_path);
+        self.check_compare_error()
+    }
+
+    fn pop(&mut self) -> Result<Option<usize>> {
+        let id = self.heap.pop().map(|(_, id)| id);
+        self.check_compare_error()?;
+        Ok(id)
+    }
+
+    fn check_compare_error(&self) -> Result<()> {
+        match self.compare_error.borrow_mut().take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn extend(
+        &mut self,
+        id: usize,
+        ilabel: Label,
+        olabel: Label,
+        state: Option<StateId>,
+        weight: &W,
+    ) -> Result<()> {
+        let prefix_weight = &self.entries[id].weight;
+        let weight = prefix_weight.times(weight)?;
+        if self.distance.is_none() && natural_less(&weight, prefix_weight)? {
+            bail!("ShortestPathsIterator : a path gets lighter when extended, the shortest distance to the final states must be used as heuristic")
+        }
+        self.push(PartialPath {
+            parent: Some(id),
+            ilabel,
+            olabel,
+            state,
+            weight,
+        })
+    }
+
+    fn expand(&mut self, id: usize, state: StateId) -> Result<()> {
+        if let Some(nshortest) = self.nshortest {
+            let s = state as usize;
+            if self.num_expansions.len() <= s {
+                self.num_expansions.resize(s + 1, 0);
+            }
+            if self.num_expansions[s] >= nshortest {
+                return Ok(());
+            }
+            self.num_expansions[s] += 1;
+        }
+        for tr in self.fst.get_trs(state)?.trs() {
+            self.extend(id, tr.ilabel, tr.olabel, Some(tr.nextstate), &tr.weight)?;
+        }
+        if let Some(final_weight) = self.fst.final_weight(state)? {
+            self.extend(id, EPS_LABEL, EPS_LABEL, None, &final_weight)?;
+        }
+        Ok(())
+    }
+
+    fn next_path(&mut self) -> Result<Option<FstPath<W>>> {
+        if matches!(self.nshortest, Some(n) if self.num_paths >= n) {
+            return Ok(None);
+        }
+        while let Some(id) = self.pop()? {
+            match self.entries[id].state {
+                Some(state) => self.expand(id, state)?,
+                None => {
+                    self.num_paths += 1;
+                    return Ok(Some(self.path(id)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn path(&self, mut id: usize) -> FstPath<W> {
+        let weight = self.entries[id].weight.clone();
+        let mut ilabels = vec![];
+        let mut olabels = vec![];
+        while let Some(parent) = self.entries[id].parent {
+            let entry = &self.entries[id];
+            if entry.ilabel != EPS_LABEL {
+                ilabels.push(entry.ilabel);
+            }
+            if entry.olabel != EPS_LABEL {
+                olabels.push(entry.olabel);
+            }
+            id = parent;
+        }
+        ilabels.reverse();
+        olabels.reverse();
+        FstPath::new(ilabels, olabels, weight)
+    }
+}
+
+impl<'a, W: Semiring, F: Fst<W>> Iterator for ShortestPathsIterator<'a, W, F> {
+    type Item = Result<FstPath<W>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let res = self.next_path().transpose();
+        if let Some(Err(_)) = res {
+            self.failed = true;
+        }
+        res
+    }
+}
+
 struct ShortestPathCompare<'a, 'b, W: Semiring> {
     pairs: &'a RefCell<Vec<(Option<StateId>, W)>>,
     distance: &'b [W],
@@ -524,3 +953,205 @@ where
     );
     Ok(ofst)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::shortest_distance;
+    use crate::semirings::TropicalWeight;
+    use crate::{FstPath, EPS_LABEL};
+
+    fn ambiguous_outputs_fst() -> Result<VectorFst<TropicalWeight>> {
+        // Three paths output [10], the best one starts with an output epsilon.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 10, 1.0, 2)?;
+        fst.emplace_tr(2, 2, EPS_LABEL, 1.0, 1)?;
+        fst.emplace_tr(0, 3, 10, 3.0, 1)?;
+        fst.emplace_tr(0, 4, EPS_LABEL, 1.0, 3)?;
+        fst.emplace_tr(3, 5, 10, 0.5, 1)?;
+        fst.emplace_tr(0, 6, 11, 4.0, 1)?;
+        fst.emplace_tr(0, 7, 12, 10.0, 1)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    fn sorted_paths(fst: &VectorFst<TropicalWeight>) -> Vec<FstPath<TropicalWeight>> {
+        let mut paths: Vec<_> = fst.paths_iter().collect();
+        paths.sort_by(|a, b| a.weight.value().partial_cmp(b.weight.value()).unwrap());
+        paths
+    }
+
+    #[test]
+    fn test_shortest_path_unique_output() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let config = ShortestPathConfig::default().with_nshortest(3);
+
+        let nbest: VectorFst<_> = shortest_path_with_config(&fst, config)?;
+        let olabels: Vec<_> = sorted_paths(&nbest)
+            .into_iter()
+            .map(|p| p.olabels)
+            .collect();
+        assert_eq!(olabels, vec![vec![10], vec![10], vec![10]]);
+
+        let nbest: VectorFst<_> = shortest_path_unique_output(&fst, config)?;
+        assert_eq!(
+            sorted_paths(&nbest),
+            vec![
+                FstPath::new(vec![4, 5], vec![10], TropicalWeight::new(1.5)),
+                FstPath::new(vec![6], vec![11], TropicalWeight::new(4.0)),
+                FstPath::new(vec![7], vec![12], TropicalWeight::new(10.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path_unique_output_fewer_strings() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let config = ShortestPathConfig::default().with_nshortest(10);
+        let nbest: VectorFst<_> = shortest_path_unique_output(&fst, config)?;
+        assert_eq!(nbest.paths_iter().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path_unique_output_not_determinizable() -> Result<()> {
+        // Outputs 10+ 11 and 10+ 12 with different weights per 10 : the inverted FST doesn't
+        // have the twins property, so only the states needed are determinized.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(4);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 10, 1.0, 1)?;
+        fst.emplace_tr(1, 1, 10, 1.0, 1)?;
+        fst.emplace_tr(1, 2, 11, 0.0, 3)?;
+        fst.emplace_tr(0, 3, 10, 1.5, 2)?;
+        fst.emplace_tr(2, 3, 10, 1.5, 2)?;
+        fst.emplace_tr(2, 4, 12, 0.0, 3)?;
+        fst.set_final(3, TropicalWeight::one())?;
+
+        let config = ShortestPathConfig::default().with_nshortest(3);
+        let nbest: VectorFst<_> = shortest_path_unique_output(&fst, config)?;
+        assert_eq!(
+            sorted_paths(&nbest),
+            vec![
+                FstPath::new(vec![1, 2], vec![10, 11], TropicalWeight::new(1.0)),
+                FstPath::new(vec![3, 4], vec![10, 12], TropicalWeight::new(1.5)),
+                FstPath::new(vec![1, 1, 2], vec![10, 10, 11], TropicalWeight::new(2.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_path_unique_output_rejects_unique() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let config = ShortestPathConfig::default().with_unique(true);
+        assert!(shortest_path_unique_output::<_, _, VectorFst<_>>(&fst, config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_matches_shortest_path() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let config = ShortestPathConfig::default().with_nshortest(4);
+        let nbest: VectorFst<_> = shortest_path_with_config(&fst, config)?;
+
+        let paths = fst.shortest_paths_iter()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(paths.len(), 5);
+        assert_eq!(paths[..4].to_vec(), sorted_paths(&nbest));
+        assert_eq!(
+            paths[4],
+            FstPath::new(vec![7], vec![12], TropicalWeight::new(10.0))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_cyclic() -> Result<()> {
+        // Accepts 1* 2 with a final weight on the second state.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 2.0, 0)?;
+        fst.emplace_tr(0, 2, 2, 1.0, 1)?;
+        fst.emplace_tr(1, 3, 3, 1.0, 2)?;
+        fst.set_final(1, 2.5)?;
+        fst.set_final(2, TropicalWeight::one())?;
+
+        let paths = fst
+            .shortest_paths_iter()?
+            .take(4)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![2, 3], vec![2, 3], TropicalWeight::new(2.0)),
+                FstPath::new(vec![2], vec![2], TropicalWeight::new(3.5)),
+                FstPath::new(vec![1, 2, 3], vec![1, 2, 3], TropicalWeight::new(4.0)),
+                FstPath::new(vec![1, 2], vec![1, 2], TropicalWeight::new(5.5)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_nshortest() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let paths = ShortestPathsIterator::new(&fst)?
+            .with_nshortest(2)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![4, 5], vec![10], TropicalWeight::new(1.5)),
+                FstPath::new(vec![1, 2], vec![10], TropicalWeight::new(2.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_negative_weights() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(0, 2, 2, 0.5, 2)?;
+        fst.emplace_tr(2, 3, 3, -2.0, 1)?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let mut iter = fst.shortest_paths_iter()?;
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+
+        let distance = shortest_distance(&fst, true)?;
+        let paths = ShortestPathsIterator::new_with_distance(&fst, distance)?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            paths,
+            vec![
+                FstPath::new(vec![2, 3], vec![2, 3], TropicalWeight::new(-1.5)),
+                FstPath::new(vec![1], vec![1], TropicalWeight::new(1.0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_missing_distance() -> Result<()> {
+        let fst = ambiguous_outputs_fst()?;
+        let distance = vec![TropicalWeight::one()];
+        let mut iter = ShortestPathsIterator::new_with_distance(&fst, distance)?;
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortest_paths_iter_requires_path_property() {
+        let fst = VectorFst::<crate::semirings::LogWeight>::new();
+        assert!(fst.shortest_paths_iter().is_err());
+    }
+}
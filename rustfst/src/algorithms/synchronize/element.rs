@@ -0,0 +1,62 @@
+use crate::semirings::{Semiring, StringWeightLeft};
+use crate::{Label, StateId, EPS_LABEL};
+
+/// State of the synchronized FST : a state of the input FST (or `None` for the
+/// super-final state) along with the residual input and output strings that are
+/// still to be emitted.
+#[derive(PartialOrd, PartialEq, Hash, Clone, Debug, Eq)]
+pub struct Element {
+    pub state: Option<StateId>,
+    pub istring: StringWeightLeft,
+    pub ostring: StringWeightLeft,
+}
+
+impl Element {
+    pub fn new(
+        state: Option<StateId>,
+        istring: StringWeightLeft,
+        ostring: StringWeightLeft,
+    ) -> Self {
+        Self {
+            state,
+            istring,
+            ostring,
+        }
+    }
+}
+
+fn labels(s: &StringWeightLeft) -> &[Label] {
+    s.value.unwrap_labels()
+}
+
+/// Returns true if the concatenation of `s` and `l` is empty.
+pub fn is_empty(s: &StringWeightLeft, l: Label) -> bool {
+    labels(s).is_empty() && l == EPS_LABEL
+}
+
+/// First label of the concatenation of `s` and `l`.
+pub fn car(s: &StringWeightLeft, l: Label) -> Label {
+    labels(s).first().cloned().unwrap_or(l)
+}
+
+/// Concatenation of `s` and `l` without its first label.
+pub fn cdr(s: &StringWeightLeft, l: Label) -> StringWeightLeft {
+    let s = labels(s);
+    if s.is_empty() {
+        return StringWeightLeft::one();
+    }
+    let mut r = s[1..].to_vec();
+    if l != EPS_LABEL {
+        r.push(l);
+    }
+    StringWeightLeft::from(r)
+}
+
+/// Concatenation of `s` and `l`.
+pub fn concat(s: &StringWeightLeft, l: Label) -> StringWeightLeft {
+    let mut r = labels(s).to_vec();
+    if l != EPS_LABEL {
+        r.push(l);
+    }
+    StringWeightLeft::from(r)
+}
@@ -0,0 +1,7 @@
+mod element;
+mod synchronize_fst;
+mod synchronize_op;
+mod synchronize_static;
+
+pub use synchronize_fst::SynchronizeFst;
+pub use synchronize_static::synchronize;
@@ -0,0 +1,127 @@
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use crate::algorithms::lazy::{FstOp, StateTable};
+use crate::algorithms::synchronize::element::{car, cdr, concat, is_empty, Element};
+use crate::fst_properties::mutable_properties::synchronization_properties;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::Fst;
+use crate::semirings::{Semiring, StringWeightLeft};
+use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL};
+
+pub struct SynchronizeOp<W: Semiring, F: Fst<W>, B: Borrow<F>> {
+    fst: B,
+    state_table: StateTable<Element>,
+    properties: FstProperties,
+    w: PhantomData<(W, F)>,
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> Debug for SynchronizeOp<W, F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SynchronizeOp {{ fst: {:?}, state_table: {:?} }}",
+            self.fst.borrow(),
+            self.state_table
+        )
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> SynchronizeOp<W, F, B> {
+    pub fn new(fst: B) -> Self {
+        let properties = synchronization_properties(fst.borrow().properties());
+        Self {
+            fst,
+            state_table: StateTable::new(),
+            properties,
+            w: PhantomData,
+        }
+    }
+}
+
+impl<W: Semiring, F: Fst<W>, B: Borrow<F>> FstOp<W> for SynchronizeOp<W, F, B> {
+    fn compute_start(&self) -> Result<Option<StateId>> {
+        Ok(self.fst.borrow().start().map(|s| {
+            self.state_table.find_id(Element::new(
+                Some(s),
+                StringWeightLeft::one(),
+                StringWeightLeft::one(),
+            ))
+        }))
+    }
+
+    fn compute_trs(&self, id: StateId) -> Result<TrsVec<W>> {
+        let elt = self.state_table.find_tuple(id);
+        let mut trs = vec![];
+        if let Some(state) = elt.state {
+            for tr in self.fst.borrow().get_trs(state)?.trs() {
+                let (ilabel, olabel, next_elt) =
+                    if !is_empty(&elt.istring, tr.ilabel) && !is_empty(&elt.ostring, tr.olabel) {
+                        // Both sides have a label to emit : emit the first one and keep the rest.
+                        (
+                            car(&elt.istring, tr.ilabel),
+                            car(&elt.ostring, tr.olabel),
+                            Element::new(
+                                Some(tr.nextstate),
+                                cdr(&elt.istring, tr.ilabel),
+                                cdr(&elt.ostring, tr.olabel),
+                            ),
+                        )
+                    } else {
+                        // Delays the emission of the labels until both sides are non empty.
+                        (
+                            EPS_LABEL,
+                            EPS_LABEL,
+                            Element::new(
+                                Some(tr.nextstate),
+                                concat(&elt.istring, tr.ilabel),
+                                concat(&elt.ostring, tr.olabel),
+                            ),
+                        )
+                    };
+                let nextstate = self.state_table.find_id(next_elt);
+                trs.push(Tr::new(ilabel, olabel, tr.weight.clone(), nextstate));
+            }
+        }
+
+        // Flushes the residual strings through a transition towards a super-final state.
+        let final_weight = match elt.state {
+            Some(state) => self.fst.borrow().final_weight(state)?,
+            None => Some(W::one()),
+        };
+        if let Some(final_weight) = final_weight {
+            if !is_empty(&elt.istring, EPS_LABEL) || !is_empty(&elt.ostring, EPS_LABEL) {
+                let nextstate = self.state_table.find_id(Element::new(
+                    None,
+                    cdr(&elt.istring, EPS_LABEL),
+                    cdr(&elt.ostring, EPS_LABEL),
+                ));
+                trs.push(Tr::new(
+                    car(&elt.istring, EPS_LABEL),
+                    car(&elt.ostring, EPS_LABEL),
+                    final_weight,
+                    nextstate,
+                ));
+            }
+        }
+        Ok(TrsVec::from(trs))
+    }
+
+    fn compute_final_weight(&self, id: StateId) -> Result<Option<W>> {
+        let elt = self.state_table.find_tuple(id);
+        if !is_empty(&elt.istring, EPS_LABEL) || !is_empty(&elt.ostring, EPS_LABEL) {
+            return Ok(None);
+        }
+        match elt.state {
+            Some(state) => self.fst.borrow().final_weight(state),
+            None => Ok(Some(W::one())),
+        }
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::Result;
+
+use crate::algorithms::synchronize::SynchronizeFst;
+use crate::fst_traits::{AllocableFst, Fst, MutableFst};
+use crate::semirings::Semiring;
+
+/// Synchronizes a transducer. The result is an equivalent FST in which each
+/// transition has at most one input and one output label, and in which
+/// epsilons only appear at the end of each successful path, `0:0` transitions
+/// apart. The input FST must have bounded delay, otherwise the
+/// algorithm doesn't terminate.
+///
+/// # Example
+///
+/// ```
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::{CoreFst, MutableFst, StateIterator};
+/// # use rustfst::algorithms::synchronize::synchronize;
+/// # use rustfst::{Trs, EPS_LABEL};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// // 1:0 followed by 0:2 : input and output labels are not on the same transition.
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(3);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, EPS_LABEL, 1.0, 1)?;
+/// fst.emplace_tr(1, EPS_LABEL, 2, 2.0, 2)?;
+/// fst.set_final(2, TropicalWeight::one())?;
+///
+/// let synchronized: VectorFst<_> = synchronize(&fst)?;
+///
+/// let trs: Vec<_> = synchronized
+///     .states_iter()
+///     .flat_map(|s| synchronized.get_trs(s).unwrap().trs().to_vec())
+///     .filter(|tr| tr.ilabel != EPS_LABEL || tr.olabel != EPS_LABEL)
+///     .collect();
+/// assert_eq!(trs.len(), 1);
+/// assert_eq!((trs[0].ilabel, trs[0].olabel), (1, 2));
+/// # Ok(())
+/// # }
+/// ```
+pub fn synchronize<W, F1, F2>(fst: &F1) -> Result<F2>
+where
+    W: Semiring,
+    F1: Fst<W>,
+    F2: MutableFst<W> + AllocableFst<W>,
+{
+    let fst: SynchronizeFst<_, F1, _> = SynchronizeFst::new(fst);
+    fst.compute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::CoreFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{FstPath, StateId, Trs, EPS_LABEL};
+
+    fn sorted_paths<F: Fst<TropicalWeight>>(fst: &F) -> Vec<FstPath<TropicalWeight>> {
+        let mut paths: Vec<_> = fst.paths_iter().collect();
+        paths.sort_by(|a, b| {
+            (&a.ilabels, &a.olabels)
+                .partial_cmp(&(&b.ilabels, &b.olabels))
+                .unwrap()
+        });
+        paths
+    }
+
+    /// Checks that on every path, ignoring the `0:0` transitions, no label follows an epsilon
+    /// on the same side.
+    fn check_epsilons_at_end<F: Fst<TropicalWeight>>(
+        fst: &F,
+        state: StateId,
+        ieps: bool,
+        oeps: bool,
+    ) -> Result<()> {
+        for tr in fst.get_trs(state)?.trs() {
+            if tr.ilabel == EPS_LABEL && tr.olabel == EPS_LABEL {
+                check_epsilons_at_end(fst, tr.nextstate, ieps, oeps)?;
+                continue;
+            }
+            assert!(!ieps || tr.ilabel == EPS_LABEL);
+            assert!(!oeps || tr.olabel == EPS_LABEL);
+            check_epsilons_at_end(
+                fst,
+                tr.nextstate,
+                ieps || tr.ilabel == EPS_LABEL,
+                oeps || tr.olabel == EPS_LABEL,
+            )?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_synchronize() -> Result<()> {
+        // 0 -1:0-> 1 -2:3-> 2 -0:4-> 3 -0:5-> 4
+        //  \-6:0-------------------------------^
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(5);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, EPS_LABEL, 1.0, 1)?;
+        fst.emplace_tr(1, 2, 3, 2.0, 2)?;
+        fst.emplace_tr(2, EPS_LABEL, 4, 3.0, 3)?;
+        fst.emplace_tr(3, EPS_LABEL, 5, 4.0, 4)?;
+        fst.emplace_tr(0, 6, EPS_LABEL, 1.5, 4)?;
+        fst.set_final(4, 0.5)?;
+        fst.set_final(2, 1.0)?;
+
+        let synchronized: VectorFst<_> = synchronize(&fst)?;
+
+        assert_eq!(sorted_paths(&synchronized), sorted_paths(&fst));
+        check_epsilons_at_end(&synchronized, synchronized.start().unwrap(), false, false)?;
+        // The labels of the longest path are emitted pairwise as soon as possible.
+        let labels: Vec<_> = {
+            let mut state = synchronized.start().unwrap();
+            let mut labels = vec![];
+            loop {
+                let trs = synchronized.get_trs(state)?;
+                match trs.trs().iter().find(|tr| tr.ilabel != 6) {
+                    Some(tr) => {
+                        if tr.ilabel != EPS_LABEL || tr.olabel != EPS_LABEL {
+                            labels.push((tr.ilabel, tr.olabel));
+                        }
+                        state = tr.nextstate;
+                    }
+                    None => break,
+                }
+            }
+            labels
+        };
+        assert_eq!(labels, vec![(1, 3), (2, 4), (EPS_LABEL, 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_synchronize_lazy() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, EPS_LABEL, 2, 1.0, 1)?;
+        fst.emplace_tr(1, 1, 1, 2.0, 1)?;
+        fst.emplace_tr(1, 3, 4, 1.0, 2)?;
+        fst.set_final(2, TropicalWeight::one())?;
+
+        let lazy = SynchronizeFst::new(fst.clone());
+        let computed: VectorFst<_> = lazy.compute()?;
+        let static_fst: VectorFst<_> = synchronize(&fst)?;
+        assert_eq!(computed, static_fst);
+        assert_eq!(lazy.start(), computed.start());
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::fst_properties::{compat_properties, compute_fst_properties, FstProperties};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{Label, StateId, Trs};
+
+/// Largest label that can be represented in the OpenFst binary format. Bigger labels are
+/// the result of negative labels being read.
+const MAX_LABEL: Label = i32::MAX as Label;
+
+/// Inconsistency found in an FST by `verify`. The transitions are identified by the state
+/// they leave and their position in the list of trs of this state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The start state is not a state of the FST.
+    InvalidStartState { start: StateId, num_states: usize },
+    /// The destination state of a tr is not a state of the FST.
+    InvalidNextState {
+        state: StateId,
+        tr: usize,
+        nextstate: StateId,
+    },
+    /// The input label of a tr is negative.
+    InvalidInputLabel {
+        state: StateId,
+        tr: usize,
+        label: Label,
+    },
+    /// The output label of a tr is negative.
+    InvalidOutputLabel {
+        state: StateId,
+        tr: usize,
+        label: Label,
+    },
+    /// The weight of a tr is not a valid weight (NaN).
+    InvalidTrWeight { state: StateId, tr: usize },
+    /// The final weight of a state is not a valid weight (NaN).
+    InvalidFinalWeight { state: StateId },
+    /// The properties stored in the FST disagree with the ones computed from its structure.
+    IncompatibleProperties {
+        stored: FstProperties,
+        computed: FstProperties,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::InvalidStartState { start, num_states } => write!(
+                f,
+                "Verify : start state {} is out of range (number of states : {})",
+                start, num_states
+            ),
+            VerifyError::InvalidNextState {
+                state,
+                tr,
+                nextstate,
+            } => write!(
+                f,
+                "Verify : tr {} of state {} has an out of range next state {}",
+                tr, state, nextstate
+            ),
+            VerifyError::InvalidInputLabel { state, tr, label } => write!(
+                f,
+                "Verify : tr {} of state {} has a negative input label {}",
+                tr, state, *label as i32
+            ),
+            VerifyError::InvalidOutputLabel { state, tr, label } => write!(
+                f,
+                "Verify : tr {} of state {} has a negative output label {}",
+                tr, state, *label as i32
+            ),
+            VerifyError::InvalidTrWeight { state, tr } => write!(
+                f,
+                "Verify : tr {} of state {} has an invalid weight",
+                tr, state
+            ),
+            VerifyError::InvalidFinalWeight { state } => {
+                write!(f, "Verify : state {} has an invalid final weight", state)
+            }
+            VerifyError::IncompatibleProperties { stored, computed } => write!(
+                f,
+                "Verify : stored properties {:?} are incompatible with the computed ones {:?}",
+                stored, computed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// A weight is invalid when it is not equal to itself, which is the case of NaN float weights.
+#[allow(clippy::eq_op)]
+fn is_invalid_weight<W: Semiring>(weight: &W) -> bool {
+    weight != weight
+}
+
+/// Checks the sanity of an FST : the start state and the next states must be in range,
+/// the labels must not be negative, the weights must not be NaN and the stored properties
+/// must agree with the structure of the FST. Returns the list of all the problems found,
+/// which is empty if the FST is valid.
+///
+/// The properties are only checked when the FST has no out of range state as computing them
+/// requires to traverse it.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::fst_impls::VectorFst;
+/// # use rustfst::fst_traits::MutableFst;
+/// # use rustfst::algorithms::{verify, VerifyError};
+/// # use rustfst::Tr;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// let s0 = fst.add_state();
+/// let s1 = fst.add_state();
+/// fst.set_start(s0)?;
+/// fst.set_final(s1, TropicalWeight::one())?;
+/// fst.add_tr(s0, Tr::new(1, 1, TropicalWeight::one(), s1))?;
+/// assert!(verify(&fst)?.is_empty());
+///
+/// unsafe { fst.add_tr_unchecked(s1, Tr::new(2, 2, TropicalWeight::one(), 5)) };
+/// assert_eq!(
+///     verify(&fst)?,
+///     vec![VerifyError::InvalidNextState { state: s1, tr: 0, nextstate: 5 }]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn verify<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<Vec<VerifyError>> {
+    let num_states = fst.num_states();
+    let mut errors = vec![];
+    if let Some(start) = fst.start() {
+        if start as usize >= num_states {
+            errors.push(VerifyError::InvalidStartState { start, num_states });
+        }
+    }
+    let mut structure_valid = errors.is_empty();
+    for state in fst.states_range() {
+        for (idx, tr) in fst.get_trs(state)?.trs().iter().enumerate() {
+            if tr.ilabel > MAX_LABEL {
+                errors.push(VerifyError::InvalidInputLabel {
+                    state,
+                    tr: idx,
+                    label: tr.ilabel,
+                });
+            }
+            if tr.olabel > MAX_LABEL {
+                errors.push(VerifyError::InvalidOutputLabel {
+                    state,
+                    tr: idx,
+                    label: tr.olabel,
+                });
+            }
+            if is_invalid_weight(&tr.weight) {
+                errors.push(VerifyError::InvalidTrWeight { state, tr: idx });
+            }
+            if tr.nextstate as usize >= num_states {
+                structure_valid = false;
+                errors.push(VerifyError::InvalidNextState {
+                    state,
+                    tr: idx,
+                    nextstate: tr.nextstate,
+                });
+            }
+        }
+        if let Some(final_weight) = fst.final_weight(state)? {
+            if is_invalid_weight(&final_weight) {
+                errors.push(VerifyError::InvalidFinalWeight { state });
+            }
+        }
+    }
+
+    if structure_valid {
+        let stored = fst.properties();
+        let mut known = FstProperties::empty();
+        let computed =
+            compute_fst_properties(fst, FstProperties::all_properties(), &mut known, false)?;
+        if !compat_properties(stored, computed) {
+            errors.push(VerifyError::IncompatibleProperties { stored, computed });
+        }
+    }
+
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use crate::{Tr, NO_LABEL};
+
+    #[test]
+    fn test_verify_valid_fst() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 2, 1.0, 1)?;
+        fst.emplace_tr(1, 0, 0, 2.0, 2)?;
+        fst.emplace_tr(2, 3, 3, 1.0, 0)?;
+        fst.set_final(2, 0.5)?;
+        assert_eq!(verify(&fst)?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_invalid_fst() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, NO_LABEL, 1.0, 1)?;
+        fst.emplace_tr(0, NO_LABEL - 4, 2, f32::NAN, 1)?;
+        unsafe { fst.add_tr_unchecked(1, Tr::new(1, 1, TropicalWeight::one(), 3)) };
+        fst.set_final(1, f32::NAN)?;
+
+        assert_eq!(
+            verify(&fst)?,
+            vec![
+                VerifyError::InvalidOutputLabel {
+                    state: 0,
+                    tr: 0,
+                    label: NO_LABEL
+                },
+                VerifyError::InvalidInputLabel {
+                    state: 0,
+                    tr: 1,
+                    label: NO_LABEL - 4
+                },
+                VerifyError::InvalidTrWeight { state: 0, tr: 1 },
+                VerifyError::InvalidNextState {
+                    state: 1,
+                    tr: 0,
+                    nextstate: 3
+                },
+                VerifyError::InvalidFinalWeight { state: 1 },
+            ]
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                VerifyError::InvalidInputLabel {
+                    state: 0,
+                    tr: 1,
+                    label: NO_LABEL - 4
+                }
+            ),
+            "Verify : tr 1 of state 0 has a negative input label -5"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_incompatible_properties() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 2, 1.0, 1)?;
+        fst.set_final(1, 0.0)?;
+        fst.set_properties(FstProperties::ACCEPTOR);
+
+        let errors = verify(&fst)?;
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0],
+            VerifyError::IncompatibleProperties { .. }
+        ));
+        Ok(())
+    }
+}
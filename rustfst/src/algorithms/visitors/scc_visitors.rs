@@ -74,7 +74,7 @@ impl<'a, W: Semiring, F: 'a + ExpandedFst<W>> Visitor<'a, W, F> for SccVisitor<'
             }
         } else {
             if let Some(ref mut access) = self.access {
-                access[s] = true;
+                access[s] = false;
             }
             self.props |= FstProperties::NOT_ACCESSIBLE;
             self.props &= !FstProperties::ACCESSIBLE;
@@ -180,3 +180,31 @@ impl<'a, W: Semiring, F: 'a + ExpandedFst<W>> Visitor<'a, W, F> for SccVisitor<'
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::dfs_visit::dfs_visit;
+    use crate::algorithms::tr_filters::AnyTrFilter;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::TropicalWeight;
+    use anyhow::Result;
+
+    #[test]
+    fn test_scc_visitor_access() -> Result<()> {
+        // State 2 can't be reached from the start state.
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 1.0, 1)?;
+        fst.emplace_tr(2, 1, 1, 1.0, 1)?;
+        fst.set_final(1, TropicalWeight::one())?;
+
+        let mut visitor = SccVisitor::new(&fst, false, true);
+        dfs_visit(&fst, &mut visitor, &AnyTrFilter {}, false);
+        assert_eq!(visitor.access, Some(vec![true, true, false]));
+        assert!(visitor.props.contains(FstProperties::NOT_ACCESSIBLE));
+        Ok(())
+    }
+}
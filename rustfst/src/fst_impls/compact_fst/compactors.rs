@@ -0,0 +1,301 @@
+use std::fmt::Debug;
+use std::io::Write;
+
+use anyhow::Result;
+use nom::IResult;
+
+use crate::fst_properties::FstProperties;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::parsers::{parse_bin_i32, write_bin_i32};
+use crate::semirings::{Semiring, SerializableSemiring};
+use crate::{Label, StateId, Tr, NO_LABEL, NO_STATE_ID};
+
+/// Defines how the trs of a `CompactFst` are stored. Each tr is compacted into an `Element`
+/// and expanded back when the trs of a state are retrieved.
+///
+/// The final weight of a state is stored as its first element, compacted from the tr
+/// `Tr(NO_LABEL, NO_LABEL, final_weight, NO_STATE_ID)`.
+pub trait Compactor<W: Semiring>: Debug + Clone + PartialEq + 'static {
+    /// Compact representation of a tr.
+    type Element: Debug + Clone + PartialEq;
+
+    /// Name of the compactor. The type of the FST in the binary format is `compact_` followed
+    /// by this name.
+    fn compactor_type() -> String;
+
+    /// Number of elements per state if it is fixed, `None` otherwise.
+    fn size() -> Option<usize>;
+
+    /// Properties an FST must have to be compacted.
+    fn properties() -> FstProperties;
+
+    /// Compacts a tr leaving `state`.
+    fn compact(state: StateId, tr: &Tr<W>) -> Self::Element;
+
+    /// Expands an element stored for `state` into a tr.
+    fn expand(state: StateId, element: &Self::Element) -> Tr<W>;
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring;
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring;
+}
+
+fn parse_label(i: &[u8]) -> IResult<&[u8], Label, NomCustomError<&[u8]>> {
+    let (i, label) = parse_bin_i32(i)?;
+    Ok((i, label as Label))
+}
+
+fn parse_state(i: &[u8]) -> IResult<&[u8], StateId, NomCustomError<&[u8]>> {
+    let (i, state) = parse_bin_i32(i)?;
+    Ok((i, state as StateId))
+}
+
+fn next_state_in_string(state: StateId, label: Label) -> StateId {
+    if label != NO_LABEL {
+        state + 1
+    } else {
+        NO_STATE_ID
+    }
+}
+
+/// Compactor for unweighted string FSTs : only the label of each tr is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StringCompactor;
+
+impl<W: Semiring> Compactor<W> for StringCompactor {
+    type Element = Label;
+
+    fn compactor_type() -> String {
+        "string".to_string()
+    }
+
+    fn size() -> Option<usize> {
+        Some(1)
+    }
+
+    fn properties() -> FstProperties {
+        FstProperties::STRING | FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED
+    }
+
+    fn compact(_state: StateId, tr: &Tr<W>) -> Self::Element {
+        tr.ilabel
+    }
+
+    fn expand(state: StateId, element: &Self::Element) -> Tr<W> {
+        Tr::new(
+            *element,
+            *element,
+            W::one(),
+            next_state_in_string(state, *element),
+        )
+    }
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring,
+    {
+        parse_label(i)
+    }
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring,
+    {
+        write_bin_i32(output, *element as i32)
+    }
+}
+
+/// Compactor for weighted string FSTs : the label and the weight of each tr are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WeightedStringCompactor;
+
+impl<W: Semiring> Compactor<W> for WeightedStringCompactor {
+    type Element = (Label, W);
+
+    fn compactor_type() -> String {
+        "weighted_string".to_string()
+    }
+
+    fn size() -> Option<usize> {
+        Some(1)
+    }
+
+    fn properties() -> FstProperties {
+        FstProperties::STRING | FstProperties::ACCEPTOR
+    }
+
+    fn compact(_state: StateId, tr: &Tr<W>) -> Self::Element {
+        (tr.ilabel, tr.weight.clone())
+    }
+
+    fn expand(state: StateId, element: &Self::Element) -> Tr<W> {
+        Tr::new(
+            element.0,
+            element.0,
+            element.1.clone(),
+            next_state_in_string(state, element.0),
+        )
+    }
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring,
+    {
+        let (i, label) = parse_label(i)?;
+        let (i, weight) = W::parse_binary(i)?;
+        Ok((i, (label, weight)))
+    }
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring,
+    {
+        write_bin_i32(output, element.0 as i32)?;
+        element.1.write_binary(output)
+    }
+}
+
+/// Compactor for weighted acceptors : the label, the weight and the next state of each tr
+/// are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AcceptorCompactor;
+
+impl<W: Semiring> Compactor<W> for AcceptorCompactor {
+    type Element = (Label, W, StateId);
+
+    fn compactor_type() -> String {
+        "acceptor".to_string()
+    }
+
+    fn size() -> Option<usize> {
+        None
+    }
+
+    fn properties() -> FstProperties {
+        FstProperties::ACCEPTOR
+    }
+
+    fn compact(_state: StateId, tr: &Tr<W>) -> Self::Element {
+        (tr.ilabel, tr.weight.clone(), tr.nextstate)
+    }
+
+    fn expand(_state: StateId, element: &Self::Element) -> Tr<W> {
+        Tr::new(element.0, element.0, element.1.clone(), element.2)
+    }
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring,
+    {
+        let (i, label) = parse_label(i)?;
+        let (i, weight) = W::parse_binary(i)?;
+        let (i, nextstate) = parse_state(i)?;
+        Ok((i, (label, weight, nextstate)))
+    }
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring,
+    {
+        write_bin_i32(output, element.0 as i32)?;
+        element.1.write_binary(output)?;
+        write_bin_i32(output, element.2 as i32)
+    }
+}
+
+/// Compactor for unweighted FSTs : the input label, the output label and the next state of
+/// each tr are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UnweightedCompactor;
+
+impl<W: Semiring> Compactor<W> for UnweightedCompactor {
+    type Element = (Label, Label, StateId);
+
+    fn compactor_type() -> String {
+        "unweighted".to_string()
+    }
+
+    fn size() -> Option<usize> {
+        None
+    }
+
+    fn properties() -> FstProperties {
+        FstProperties::UNWEIGHTED
+    }
+
+    fn compact(_state: StateId, tr: &Tr<W>) -> Self::Element {
+        (tr.ilabel, tr.olabel, tr.nextstate)
+    }
+
+    fn expand(_state: StateId, element: &Self::Element) -> Tr<W> {
+        Tr::new(element.0, element.1, W::one(), element.2)
+    }
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring,
+    {
+        let (i, ilabel) = parse_label(i)?;
+        let (i, olabel) = parse_label(i)?;
+        let (i, nextstate) = parse_state(i)?;
+        Ok((i, (ilabel, olabel, nextstate)))
+    }
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring,
+    {
+        write_bin_i32(output, element.0 as i32)?;
+        write_bin_i32(output, element.1 as i32)?;
+        write_bin_i32(output, element.2 as i32)
+    }
+}
+
+/// Compactor for unweighted acceptors : the label and the next state of each tr are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UnweightedAcceptorCompactor;
+
+impl<W: Semiring> Compactor<W> for UnweightedAcceptorCompactor {
+    type Element = (Label, StateId);
+
+    fn compactor_type() -> String {
+        "unweighted_acceptor".to_string()
+    }
+
+    fn size() -> Option<usize> {
+        None
+    }
+
+    fn properties() -> FstProperties {
+        FstProperties::ACCEPTOR | FstProperties::UNWEIGHTED
+    }
+
+    fn compact(_state: StateId, tr: &Tr<W>) -> Self::Element {
+        (tr.ilabel, tr.nextstate)
+    }
+
+    fn expand(_state: StateId, element: &Self::Element) -> Tr<W> {
+        Tr::new(element.0, element.0, W::one(), element.1)
+    }
+
+    fn parse_element(i: &[u8]) -> IResult<&[u8], Self::Element, NomCustomError<&[u8]>>
+    where
+        W: SerializableSemiring,
+    {
+        let (i, label) = parse_label(i)?;
+        let (i, nextstate) = parse_state(i)?;
+        Ok((i, (label, nextstate)))
+    }
+
+    fn write_element<O: Write>(element: &Self::Element, output: &mut O) -> Result<()>
+    where
+        W: SerializableSemiring,
+    {
+        write_bin_i32(output, element.0 as i32)?;
+        write_bin_i32(output, element.1 as i32)
+    }
+}
@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::fst_impls::compact_fst::Compactor;
+use crate::fst_properties::properties::EXPANDED;
+use crate::fst_properties::{compute_fst_properties, FstProperties};
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+use crate::{StateId, SymbolTable, Tr, NO_LABEL, NO_STATE_ID};
+
+/// Immutable FST whose trs are stored in a compact form defined by a `Compactor`.
+/// For instance, only the labels are stored for a string FST with the `StringCompactor`.
+///
+/// The trs are expanded each time they are retrieved.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::fst_impls::{CompactFst, StringCompactor, VectorFst};
+/// # use rustfst::fst_traits::CoreFst;
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::utils::acceptor;
+/// # use rustfst::Trs;
+/// # fn main() -> Result<()> {
+/// let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2, 3], TropicalWeight::one());
+/// let compact_fst = CompactFst::<_, StringCompactor>::from_fst(&fst)?;
+///
+/// assert_eq!(compact_fst.get_trs(1)?.trs(), fst.get_trs(1)?.trs());
+/// assert_eq!(compact_fst.final_weight(3)?, Some(TropicalWeight::one()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompactFst<W: Semiring, C: Compactor<W>> {
+    /// Position of the first element of each state in `compacts`, followed by the total number
+    /// of elements. `None` if the compactor has a fixed size.
+    pub(crate) states: Option<Vec<u32>>,
+    pub(crate) compacts: Vec<C::Element>,
+    pub(crate) num_states: usize,
+    pub(crate) num_trs: usize,
+    pub(crate) start: Option<StateId>,
+    pub(crate) isymt: Option<Arc<SymbolTable>>,
+    pub(crate) osymt: Option<Arc<SymbolTable>>,
+    pub(crate) properties: FstProperties,
+    pub(crate) w: PhantomData<(W, C)>,
+}
+
+impl<W: Semiring, C: Compactor<W>> CompactFst<W, C> {
+    pub(crate) fn static_properties() -> u64 {
+        EXPANDED
+    }
+
+    /// Compacts an FST. An error is returned if the FST doesn't have the properties required by
+    /// the compactor, e.g. if it is not a string FST for the `StringCompactor`.
+    pub fn from_fst<F: ExpandedFst<W>>(fst: &F) -> Result<Self> {
+        let mut known = FstProperties::empty();
+        let properties =
+            compute_fst_properties(fst, FstProperties::all_properties(), &mut known, true)?;
+        if !properties.contains(C::properties()) {
+            bail!(
+                "The FST is not compatible with the compactor {:?} : it needs the properties {:?}",
+                C::compactor_type(),
+                C::properties()
+            );
+        }
+
+        let num_states = fst.num_states();
+        let mut states = Vec::with_capacity(num_states + 1);
+        let mut compacts = Vec::new();
+        let mut num_trs = 0;
+        for state in 0..(num_states as StateId) {
+            let pos = compacts.len();
+            states.push(pos as u32);
+            if let Some(final_weight) = fst.final_weight(state)? {
+                compacts.push(C::compact(
+                    state,
+                    &Tr::new(NO_LABEL, NO_LABEL, final_weight, NO_STATE_ID),
+                ));
+            }
+            let trs = fst.get_trs(state)?;
+            num_trs += trs.len();
+            compacts.extend(trs.iter().map(|tr| C::compact(state, tr)));
+            if let Some(size) = C::size() {
+                if compacts.len() - pos != size {
+                    bail!(
+                        "The FST is not compatible with the compactor {:?} : state {} is stored with {} elements instead of {}",
+                        C::compactor_type(),
+                        state,
+                        compacts.len() - pos,
+                        size
+                    );
+                }
+            }
+        }
+        states.push(compacts.len() as u32);
+
+        Ok(Self {
+            states: if C::size().is_some() {
+                None
+            } else {
+                Some(states)
+            },
+            compacts,
+            num_states,
+            num_trs,
+            start: fst.start(),
+            isymt: fst.input_symbols().cloned(),
+            osymt: fst.output_symbols().cloned(),
+            properties: properties | C::properties(),
+            w: PhantomData,
+        })
+    }
+
+    /// Range of the elements of a state in `compacts`.
+    pub(crate) fn range(&self, state: StateId) -> Result<Range<usize>> {
+        if (state as usize) >= self.num_states {
+            bail!("State {:?} doesn't exist", state);
+        }
+        Ok(unsafe { self.range_unchecked(state) })
+    }
+
+    pub(crate) unsafe fn range_unchecked(&self, state: StateId) -> Range<usize> {
+        let state = state as usize;
+        match (&self.states, C::size()) {
+            (Some(states), _) => {
+                *states.get_unchecked(state) as usize..*states.get_unchecked(state + 1) as usize
+            }
+            (None, Some(size)) => state * size..(state + 1) * size,
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// The final weight of a state is stored as its first element.
+    pub(crate) fn final_weight_in(&self, state: StateId, range: &Range<usize>) -> Option<W> {
+        self.compacts[range.clone()]
+            .first()
+            .map(|element| C::expand(state, element))
+            .filter(|tr| tr.ilabel == NO_LABEL)
+            .map(|tr| tr.weight)
+    }
+
+    pub(crate) fn num_trs_in(&self, state: StateId, range: Range<usize>) -> usize {
+        let has_final = self.final_weight_in(state, &range).is_some();
+        range.len() - has_final as usize
+    }
+
+    pub(crate) fn trs_in(&self, state: StateId, range: Range<usize>) -> Vec<Tr<W>> {
+        self.compacts[range]
+            .iter()
+            .map(|element| C::expand(state, element))
+            .filter(|tr| tr.ilabel != NO_LABEL)
+            .collect()
+    }
+}
+
+impl<W: Semiring, C: Compactor<W>> PartialEq for CompactFst<W, C> {
+    fn eq(&self, other: &Self) -> bool {
+        // Indended: Doesn't check symt and properties
+        self.num_states == other.num_states
+            && self.states == other.states
+            && self.compacts == other.compacts
+            && self.start == other.start
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::fst_impls::compact_fst::Compactor;
+use crate::fst_impls::CompactFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{CoreFst, Fst};
+use crate::semirings::Semiring;
+use crate::{StateId, SymbolTable, TrsVec, EPS_LABEL};
+
+impl<W: Semiring, C: Compactor<W>> Fst<W> for CompactFst<W, C> {
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.isymt.as_ref()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.osymt.as_ref()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.isymt = Some(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.osymt = Some(symt);
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.isymt.take()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.osymt.take()
+    }
+}
+
+impl<W: Semiring, C: Compactor<W>> CoreFst<W> for CompactFst<W, C> {
+    type TRS = TrsVec<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.start
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        let range = self.range(state_id)?;
+        Ok(self.final_weight_in(state_id, &range))
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        self.final_weight_in(state_id, &self.range_unchecked(state_id))
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        let range = self.range(s)?;
+        Ok(self.num_trs_in(s, range))
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.num_trs_in(s, self.range_unchecked(s))
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        let range = self.range(state_id)?;
+        Ok(TrsVec(Arc::new(self.trs_in(state_id, range))))
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        TrsVec(Arc::new(
+            self.trs_in(state_id, self.range_unchecked(state_id)),
+        ))
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        let range = self.range(state)?;
+        Ok(self
+            .trs_in(state, range)
+            .iter()
+            .filter(|tr| tr.ilabel == EPS_LABEL)
+            .count())
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        let range = self.range(state)?;
+        Ok(self
+            .trs_in(state, range)
+            .iter()
+            .filter(|tr| tr.olabel == EPS_LABEL)
+            .count())
+    }
+}
@@ -0,0 +1,55 @@
+use std::iter::Map;
+use std::ops::Range;
+
+use crate::fst_impls::compact_fst::Compactor;
+use crate::fst_impls::CompactFst;
+use crate::fst_traits::{FstIntoIterator, FstIterData, FstIterator, StateIterator};
+use crate::semirings::Semiring;
+use crate::{StateId, Tr, TrsVec};
+
+impl<W, C> FstIntoIterator<W> for CompactFst<W, C>
+where
+    W: Semiring + 'static,
+    C: Compactor<W>,
+{
+    type TrsIter = std::vec::IntoIter<Tr<W>>;
+    type FstIter = Box<dyn Iterator<Item = FstIterData<W, Self::TrsIter>>>;
+
+    fn fst_into_iter(self) -> Self::FstIter {
+        Box::new(self.states_iter().map(move |state_id| {
+            let range = unsafe { self.range_unchecked(state_id) };
+            let trs = self.trs_in(state_id, range.clone());
+            FstIterData {
+                state_id,
+                final_weight: self.final_weight_in(state_id, &range),
+                num_trs: trs.len(),
+                trs: trs.into_iter(),
+            }
+        }))
+    }
+}
+
+impl<'a, W: Semiring, C: Compactor<W>> StateIterator<'a> for CompactFst<W, C> {
+    type Iter = Range<StateId>;
+    fn states_iter(&'a self) -> Self::Iter {
+        0..(self.num_states as StateId)
+    }
+}
+
+type StateToData<'a, W> = Box<dyn FnMut(StateId) -> FstIterData<W, TrsVec<W>> + 'a>;
+
+impl<'a, W: Semiring, C: Compactor<W>> FstIterator<'a, W> for CompactFst<W, C> {
+    type FstIter = Map<Range<StateId>, StateToData<'a, W>>;
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.states_iter().map(Box::new(move |state_id| {
+            let range = unsafe { self.range_unchecked(state_id) };
+            let trs = self.trs_in(state_id, range.clone());
+            FstIterData {
+                state_id,
+                final_weight: self.final_weight_in(state_id, &range),
+                num_trs: trs.len(),
+                trs: TrsVec::from(trs),
+            }
+        }))
+    }
+}
@@ -0,0 +1,18 @@
+pub use self::compactors::{
+    AcceptorCompactor, Compactor, StringCompactor, UnweightedAcceptorCompactor,
+    UnweightedCompactor, WeightedStringCompactor,
+};
+pub use self::data_structure::CompactFst;
+
+mod compactors;
+mod data_structure;
+mod expanded_fst;
+mod fst;
+mod iterators;
+mod misc;
+mod serializable_fst;
+
+pub(super) static COMPACT_MIN_FILE_VERSION: i32 = 1;
+pub(super) static COMPACT_ALIGNED_FILE_VERSION: i32 = 1;
+pub(super) static COMPACT_FILE_VERSION: i32 = 2;
+pub(super) static COMPACT_ARCH_ALIGNMENT: usize = 16;
@@ -0,0 +1,350 @@
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use nom::bytes::complete::take;
+use nom::multi::count;
+use nom::IResult;
+
+use crate::fst_impls::compact_fst::{
+    Compactor, COMPACT_ALIGNED_FILE_VERSION, COMPACT_ARCH_ALIGNMENT, COMPACT_FILE_VERSION,
+    COMPACT_MIN_FILE_VERSION,
+};
+use crate::fst_impls::{CompactFst, VectorFst};
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{Fst, SerializableFst};
+use crate::parsers::bin_fst::fst_header::{FstFlags, FstHeader, OpenFstString, FST_MAGIC_NUMBER};
+use crate::parsers::bin_fst::utils_parsing::parse_start_state;
+use crate::parsers::bin_fst::utils_serialization::CountingWriter;
+use crate::parsers::nom_utils::NomCustomError;
+use crate::parsers::text_fst::ParsedTextFst;
+use crate::parsers::{parse_bin_u32, write_bin_u32};
+use crate::semirings::SerializableSemiring;
+use crate::Tr;
+
+impl<W: SerializableSemiring, C: Compactor<W>> SerializableFst<W> for CompactFst<W, C> {
+    fn fst_type() -> String {
+        format!("compact_{}", C::compactor_type())
+    }
+
+    fn load(data: &[u8]) -> Result<Self> {
+        let (_, parsed_fst) = parse_compact_fst(data)
+            .map_err(|_| format_err!("Error while parsing binary CompactFst"))?;
+
+        if let Some(states) = &parsed_fst.states {
+            if states.windows(2).any(|w| w[0] > w[1]) {
+                bail!("Error while parsing binary CompactFst : the states are not sorted");
+            }
+        }
+
+        Ok(parsed_fst)
+    }
+
+    fn store<O: Write>(&self, output: O) -> Result<()> {
+        self.store_with_alignment(output, false)
+    }
+
+    fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self> {
+        let fst = VectorFst::from_parsed_fst_text(parsed_fst_text)?;
+        Self::from_fst(&fst)
+    }
+
+    fn read_text_from<R: BufRead>(reader: R) -> Result<Self> {
+        let fst = VectorFst::read_text_from(reader)?;
+        Self::from_fst(&fst)
+    }
+}
+
+impl<W: SerializableSemiring, C: Compactor<W>> CompactFst<W, C> {
+    /// Stores the FST in the aligned binary format : the states and the compact elements are
+    /// padded to start on a multiple of 16 bytes, as OpenFst does with `--fst_align`.
+    pub fn store_aligned<O: Write>(&self, output: O) -> Result<()> {
+        self.store_with_alignment(output, true)
+    }
+
+    /// Writes the FST to a file in the aligned binary format. See `store_aligned`.
+    pub fn write_aligned<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
+        let output = File::create(path_bin_fst.as_ref()).with_context(|| {
+            format!(
+                "Cannot create CompactFst binary file : {:?}",
+                path_bin_fst.as_ref()
+            )
+        })?;
+        self.store_aligned(BufWriter::new(output))
+    }
+
+    fn store_with_alignment<O: Write>(&self, output: O, aligned: bool) -> Result<()> {
+        let mut output = CountingWriter::new(output);
+        let mut flags = FstFlags::empty();
+        if self.input_symbols().is_some() {
+            flags |= FstFlags::HAS_ISYMBOLS;
+        }
+        if self.output_symbols().is_some() {
+            flags |= FstFlags::HAS_OSYMBOLS;
+        }
+        if aligned {
+            flags |= FstFlags::IS_ALIGNED;
+        }
+
+        let hdr = FstHeader {
+            magic_number: FST_MAGIC_NUMBER,
+            fst_type: OpenFstString::new(Self::fst_type()),
+            tr_type: OpenFstString::new(Tr::<W>::tr_type()),
+            version: COMPACT_FILE_VERSION,
+            flags,
+            properties: self.properties.bits() | CompactFst::<W, C>::static_properties(),
+            start: self.start.map(|v| v as i64).unwrap_or(-1),
+            num_states: self.num_states as i64,
+            num_trs: self.num_trs as i64,
+            isymt: self.input_symbols().cloned(),
+            osymt: self.output_symbols().cloned(),
+        };
+        hdr.write(&mut output)?;
+
+        if let Some(states) = &self.states {
+            if aligned {
+                output.align(COMPACT_ARCH_ALIGNMENT)?;
+            }
+            for pos in states {
+                write_bin_u32(&mut output, *pos)?;
+            }
+        }
+        if aligned {
+            output.align(COMPACT_ARCH_ALIGNMENT)?;
+        }
+        for element in &self.compacts {
+            C::write_element(element, &mut output)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn align_input(i: &[u8], stream_len: usize) -> IResult<&[u8], (), NomCustomError<&[u8]>> {
+    let pos = stream_len - i.len();
+    let padding = (COMPACT_ARCH_ALIGNMENT - pos % COMPACT_ARCH_ALIGNMENT) % COMPACT_ARCH_ALIGNMENT;
+    let (i, _) = take(padding)(i)?;
+    Ok((i, ()))
+}
+
+fn parse_compact_fst<W: SerializableSemiring, C: Compactor<W>>(
+    i: &[u8],
+) -> IResult<&[u8], CompactFst<W, C>, NomCustomError<&[u8]>> {
+    let stream_len = i.len();
+
+    let (mut i, hdr) = FstHeader::parse_checked(
+        i,
+        COMPACT_MIN_FILE_VERSION,
+        CompactFst::<W, C>::fst_type(),
+        Tr::<W>::tr_type(),
+    )?;
+    let aligned =
+        hdr.version == COMPACT_ALIGNED_FILE_VERSION || hdr.flags.contains(FstFlags::IS_ALIGNED);
+    let num_states = hdr.num_states as usize;
+
+    let (states, ncompacts) = match C::size() {
+        Some(size) => (None, num_states * size),
+        None => {
+            if aligned {
+                i = align_input(i, stream_len)?.0;
+            }
+            let (i_states, states) = count(parse_bin_u32, num_states + 1)(i)?;
+            i = i_states;
+            let ncompacts = states[num_states] as usize;
+            (Some(states), ncompacts)
+        }
+    };
+
+    if aligned {
+        i = align_input(i, stream_len)?.0;
+    }
+    let (i, compacts) = count(C::parse_element, ncompacts)(i)?;
+
+    Ok((
+        i,
+        CompactFst {
+            states,
+            compacts,
+            num_states,
+            num_trs: hdr.num_trs as usize,
+            start: parse_start_state(hdr.start),
+            isymt: hdr.isymt,
+            osymt: hdr.osymt,
+            properties: FstProperties::from_bits_truncate(hdr.properties),
+            w: PhantomData,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fst_impls::compact_fst::{
+        AcceptorCompactor, StringCompactor, UnweightedAcceptorCompactor, UnweightedCompactor,
+        WeightedStringCompactor,
+    };
+    use crate::fst_traits::{CoreFst, ExpandedFst, MutableFst, StateIterator};
+    use crate::semirings::{Semiring, TropicalWeight};
+    use crate::utils::{acceptor, transducer};
+
+    fn check_compact_fst<C: Compactor<TropicalWeight>>(
+        fst: &VectorFst<TropicalWeight>,
+    ) -> Result<()> {
+        let compact_fst = CompactFst::<_, C>::from_fst(fst)?;
+        assert_eq!(compact_fst.num_states(), fst.num_states());
+        assert_eq!(compact_fst.start(), fst.start());
+        for s in fst.states_iter() {
+            assert_eq!(compact_fst.final_weight(s)?, fst.final_weight(s)?);
+            assert_eq!(compact_fst.num_trs(s)?, fst.num_trs(s)?);
+            assert_eq!(compact_fst.get_trs(s)?, fst.get_trs(s)?);
+            assert_eq!(
+                compact_fst.num_input_epsilons(s)?,
+                fst.num_input_epsilons(s)?
+            );
+        }
+        let expanded: VectorFst<_> = crate::algorithms::fst_convert_from_ref(&compact_fst);
+        assert_eq!(&expanded, fst);
+
+        let mut buffer = vec![];
+        compact_fst.store(&mut buffer)?;
+        let loaded = CompactFst::<_, C>::load(&buffer)?;
+        assert_eq!(loaded, compact_fst);
+        assert_eq!(loaded.num_trs, compact_fst.num_trs);
+
+        let mut buffer = vec![];
+        compact_fst.store_aligned(&mut buffer)?;
+        assert_eq!(CompactFst::<_, C>::load(&buffer)?, compact_fst);
+        Ok(())
+    }
+
+    fn weighted_acceptor() -> Result<VectorFst<TropicalWeight>> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.emplace_tr(0, 1, 1, 0.5, 1)?;
+        fst.emplace_tr(0, 2, 2, 1.5, 2)?;
+        fst.emplace_tr(1, 0, 0, 2.0, 2)?;
+        fst.emplace_tr(2, 3, 3, 1.0, 0)?;
+        fst.set_final(1, 3.0)?;
+        fst.set_final(2, TropicalWeight::one())?;
+        Ok(fst)
+    }
+
+    #[test]
+    fn test_compact_fst_string() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2, 0, 3], TropicalWeight::one());
+        check_compact_fst::<StringCompactor>(&fst)?;
+        check_compact_fst::<WeightedStringCompactor>(&fst)?;
+        check_compact_fst::<AcceptorCompactor>(&fst)?;
+        check_compact_fst::<UnweightedCompactor>(&fst)?;
+        check_compact_fst::<UnweightedAcceptorCompactor>(&fst)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_weighted_string() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::new(2.0));
+        check_compact_fst::<WeightedStringCompactor>(&fst)?;
+        check_compact_fst::<AcceptorCompactor>(&fst)?;
+        assert!(CompactFst::<_, StringCompactor>::from_fst(&fst).is_err());
+        assert!(CompactFst::<_, UnweightedCompactor>::from_fst(&fst).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_acceptor() -> Result<()> {
+        let fst = weighted_acceptor()?;
+        check_compact_fst::<AcceptorCompactor>(&fst)?;
+        assert!(CompactFst::<_, WeightedStringCompactor>::from_fst(&fst).is_err());
+        assert!(CompactFst::<_, UnweightedAcceptorCompactor>::from_fst(&fst).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_unweighted() -> Result<()> {
+        let mut fst: VectorFst<TropicalWeight> =
+            transducer(&[1, 2], &[3, 0], TropicalWeight::one());
+        fst.emplace_tr(1, 4, 5, TropicalWeight::one(), 0)?;
+        check_compact_fst::<UnweightedCompactor>(&fst)?;
+        assert!(CompactFst::<_, UnweightedAcceptorCompactor>::from_fst(&fst).is_err());
+        assert!(CompactFst::<_, AcceptorCompactor>::from_fst(&fst).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_empty() -> Result<()> {
+        let fst = VectorFst::<TropicalWeight>::new();
+        check_compact_fst::<StringCompactor>(&fst)?;
+        check_compact_fst::<AcceptorCompactor>(&fst)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_string_binary_format() -> Result<()> {
+        let fst: VectorFst<TropicalWeight> = acceptor(&[1, 2], TropicalWeight::one());
+        let compact_fst = CompactFst::<_, StringCompactor>::from_fst(&fst)?;
+        let mut buffer = vec![];
+        compact_fst.store(&mut buffer)?;
+
+        // One label per state, the final state being stored as -1.
+        let mut expected = vec![];
+        for label in &[1i32, 2, -1] {
+            expected.extend_from_slice(&label.to_le_bytes());
+        }
+        assert!(buffer.ends_with(&expected));
+
+        let mut hdr_buffer = vec![];
+        let hdr = FstHeader::parse_checked(
+            &buffer,
+            1,
+            "compact_string",
+            Tr::<TropicalWeight>::tr_type(),
+        )
+        .map_err(|_| format_err!("Can't parse header"))?
+        .1;
+        hdr.write(&mut hdr_buffer)?;
+        assert_eq!(hdr_buffer.len() + expected.len(), buffer.len());
+        assert_eq!(hdr.num_states, 3);
+        assert_eq!(hdr.num_trs, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_fst_aligned_binary_format() -> Result<()> {
+        let fst = weighted_acceptor()?;
+        let compact_fst = CompactFst::<_, AcceptorCompactor>::from_fst(&fst)?;
+        let mut buffer = vec![];
+        compact_fst.store(&mut buffer)?;
+
+        // Same content in the aligned format, as written by OpenFst with `--fst_align`.
+        let (rest, mut hdr) = FstHeader::parse_checked(
+            &buffer,
+            1,
+            "compact_acceptor",
+            Tr::<TropicalWeight>::tr_type(),
+        )
+        .map_err(|_| format_err!("Can't parse header"))?;
+        let states_len = 4 * (fst.num_states() + 1);
+        let (states, compacts) = rest.split_at(states_len);
+        hdr.flags |= FstFlags::IS_ALIGNED;
+        let mut aligned = vec![];
+        hdr.write(&mut aligned)?;
+        for section in &[states, compacts] {
+            let padding = (16 - aligned.len() % 16) % 16;
+            aligned.resize(aligned.len() + padding, 0);
+            aligned.extend_from_slice(section);
+        }
+
+        assert_eq!(
+            CompactFst::<_, AcceptorCompactor>::load(&aligned)?,
+            compact_fst
+        );
+
+        let mut stored_aligned = vec![];
+        compact_fst.store_aligned(&mut stored_aligned)?;
+        assert_eq!(stored_aligned, aligned);
+        Ok(())
+    }
+}
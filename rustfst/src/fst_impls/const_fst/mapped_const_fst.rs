@@ -0,0 +1,648 @@
+use std::fmt;
+use std::iter::Map;
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{format_err, Result};
+
+use crate::fst_impls::const_fst::{
+    CONST_ALIGNED_FILE_VERSION, CONST_ARCH_ALIGNMENT, CONST_MIN_FILE_VERSION,
+};
+use crate::fst_impls::ConstFst;
+use crate::fst_properties::FstProperties;
+use crate::fst_traits::{
+    CoreFst, ExpandedFst, Fst, FstIntoIterator, FstIterData, FstIterator, SerializableFst,
+    StateIterator,
+};
+use crate::parsers::bin_fst::fst_header::{FstFlags, FstHeader};
+use crate::parsers::bin_fst::utils_parsing::parse_start_state;
+use crate::semirings::MappableSemiring;
+use crate::{StateId, SymbolTable, Tr, Trs, TrsVec};
+
+/// Buffer containing a serialized ConstFst.
+type Storage = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Bytes of the `Storage`. The slice returned by `AsRef` is resolved once, when the FST is
+/// created, and that same slice is validated and then used for every access : an `AsRef`
+/// implementation returning a different slice on a later call can't bypass the checks.
+#[derive(Clone)]
+struct MappedBytes {
+    // Keeps the bytes alive. The owner is behind an `Arc`, so it is never moved nor
+    // mutably borrowed while the FST is alive.
+    _storage: Storage,
+    ptr: *const u8,
+    len: usize,
+}
+
+// Safety: the bytes are only read and the storage is `Send + Sync`.
+unsafe impl Send for MappedBytes {}
+unsafe impl Sync for MappedBytes {}
+
+impl MappedBytes {
+    fn new(storage: Storage) -> Self {
+        let bytes = (*storage).as_ref();
+        let (ptr, len) = (bytes.as_ptr(), bytes.len());
+        Self {
+            _storage: storage,
+            ptr,
+            len,
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: the slice was returned by the storage, which is kept alive by `self`.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// In-memory representation of a state in the OpenFst const format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+struct MappedConstState<W> {
+    final_weight: W,
+    pos: u32,
+    ntrs: u32,
+    niepsilons: u32,
+    noepsilons: u32,
+}
+
+/// Immutable FST reading its states and trs in place from a buffer containing a ConstFst
+/// serialized in the aligned binary format (see `ConstFst::store_aligned`). Contrary to
+/// `ConstFst::load`, nothing is copied : loading is done in constant time (apart from the
+/// symbol tables) and the buffer can be a memory-mapped file shared by several processes
+/// through the page cache.
+///
+/// The buffer must not be modified while the FST is alive. The weights must implement
+/// `MappableSemiring` and the labels and state ids must be 32 bits wide, which is the case
+/// with the default `state-label-u32` feature.
+///
+/// # Example
+///
+/// ```
+/// # use anyhow::Result;
+/// # use rustfst::fst_impls::{ConstFst, MappedConstFst, VectorFst};
+/// # use rustfst::fst_traits::{CoreFst, MutableFst};
+/// # use rustfst::semirings::{Semiring, TropicalWeight};
+/// # use rustfst::Trs;
+/// # fn main() -> Result<()> {
+/// let mut fst = VectorFst::<TropicalWeight>::new();
+/// fst.add_states(2);
+/// fst.set_start(0)?;
+/// fst.emplace_tr(0, 1, 2, 0.5, 1)?;
+/// fst.set_final(1, TropicalWeight::one())?;
+/// let const_fst: ConstFst<_> = fst.into();
+///
+/// let mut bytes = vec![];
+/// const_fst.store_aligned(&mut bytes)?;
+///
+/// // With a real file, the buffer would rather be e.g. a `memmap2::Mmap`, which is
+/// // aligned on a page. Here the bytes are copied in a buffer aligned on 16 bytes.
+/// #[repr(align(16))]
+/// #[derive(Clone, Copy)]
+/// struct Block([u8; 16]);
+/// struct AlignedBuffer(Vec<Block>, usize);
+/// impl AsRef<[u8]> for AlignedBuffer {
+///     fn as_ref(&self) -> &[u8] {
+///         unsafe { std::slice::from_raw_parts(self.0.as_ptr() as *const u8, self.1) }
+///     }
+/// }
+/// let mut blocks = vec![Block([0; 16]); bytes.chunks(16).len()];
+/// for (block, chunk) in blocks.iter_mut().zip(bytes.chunks(16)) {
+///     block.0[..chunk.len()].copy_from_slice(chunk);
+/// }
+/// let buffer = AlignedBuffer(blocks, bytes.len());
+///
+/// let mapped_fst = MappedConstFst::<TropicalWeight>::new(buffer)?;
+/// assert_eq!(mapped_fst.start(), Some(0));
+/// assert_eq!(mapped_fst.get_trs(0)?.trs(), const_fst.get_trs(0)?.trs());
+/// assert_eq!(mapped_fst.final_weight(1)?, Some(TropicalWeight::one()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MappedConstFst<W> {
+    bytes: MappedBytes,
+    states_offset: usize,
+    num_states: usize,
+    trs_offset: usize,
+    num_trs: usize,
+    start: Option<StateId>,
+    isymt: Option<Arc<SymbolTable>>,
+    osymt: Option<Arc<SymbolTable>>,
+    properties: FstProperties,
+    w: PhantomData<W>,
+}
+
+/// Returns the elements of type `T` stored in `bytes` at `offset`.
+///
+/// # Safety
+///
+/// The bytes at `offset` must be properly aligned and contain `n` valid values of type `T`.
+unsafe fn cast_slice<T>(bytes: &[u8], offset: usize, n: usize) -> &[T] {
+    if n == 0 {
+        return &[];
+    }
+    std::slice::from_raw_parts(bytes.as_ptr().add(offset) as *const T, n)
+}
+
+/// Checks that `n` elements of type `T` can be read in place at `offset` and returns the
+/// offset of the end of the section.
+fn check_section<T>(bytes: &[u8], offset: usize, n: usize, name: &str) -> Result<usize> {
+    let end = size_of::<T>()
+        .checked_mul(n)
+        .and_then(|len| len.checked_add(offset))
+        .ok_or_else(|| format_err!("MappedConstFst : Too many {}", name))?;
+    if end > bytes.len() {
+        bail!(
+            "MappedConstFst : Unexpected end of buffer while reading the {}",
+            name
+        );
+    }
+    if n > 0 && (bytes.as_ptr() as usize + offset) & (align_of::<T>() - 1) != 0 {
+        bail!(
+            "MappedConstFst : The {} are not aligned, the FST must be stored with `store_aligned` in a buffer aligned on {} bytes",
+            name,
+            CONST_ARCH_ALIGNMENT
+        );
+    }
+    Ok(end)
+}
+
+fn align_offset(offset: usize) -> usize {
+    // The alignment is a power of two.
+    (offset + CONST_ARCH_ALIGNMENT - 1) & !(CONST_ARCH_ALIGNMENT - 1)
+}
+
+impl<W: MappableSemiring> MappedConstFst<W> {
+    /// Creates the FST from a buffer containing a ConstFst in the aligned binary format.
+    /// Only the header is parsed : the states and the trs are read in place. The buffer
+    /// must be aligned on 16 bytes, which is the case of memory-mapped files.
+    pub fn new<S: AsRef<[u8]> + Send + Sync + 'static>(storage: S) -> Result<Self> {
+        Self::from_storage(Arc::new(storage))
+    }
+
+    fn from_storage(storage: Storage) -> Result<Self> {
+        if cfg!(target_endian = "big") {
+            bail!("MappedConstFst : Only supported on little-endian targets");
+        }
+        if size_of::<Tr<W>>() != size_of::<W>() + 12
+            || size_of::<MappedConstState<W>>() != size_of::<W>() + 16
+        {
+            bail!("MappedConstFst : The layout of the trs doesn't match the binary format. Labels and state ids must be 32 bits wide.");
+        }
+
+        let mapped_bytes = MappedBytes::new(storage);
+        let bytes = mapped_bytes.bytes();
+        let (i, hdr) = FstHeader::parse_checked(
+            bytes,
+            CONST_MIN_FILE_VERSION,
+            ConstFst::<W>::fst_type(),
+            Tr::<W>::tr_type(),
+        )
+        .map_err(|e| format_err!("Error while parsing MappedConstFst header : {:?}", e))?;
+        let aligned =
+            hdr.version == CONST_ALIGNED_FILE_VERSION || hdr.flags.contains(FstFlags::IS_ALIGNED);
+        if !aligned {
+            bail!("MappedConstFst : The FST must be stored in the aligned format with `store_aligned`");
+        }
+
+        let num_states = hdr.num_states as usize;
+        let num_trs = hdr.num_trs as usize;
+        let mut offset = bytes.len() - i.len();
+        if num_states > 0 {
+            offset = align_offset(offset);
+        }
+        let states_offset = offset;
+        offset = check_section::<MappedConstState<W>>(bytes, offset, num_states, "states")?;
+        if num_trs > 0 {
+            offset = align_offset(offset);
+        }
+        let trs_offset = offset;
+        check_section::<Tr<W>>(bytes, offset, num_trs, "trs")?;
+
+        let fst = MappedConstFst {
+            bytes: mapped_bytes.clone(),
+            states_offset,
+            num_states,
+            trs_offset,
+            num_trs,
+            start: parse_start_state(hdr.start),
+            isymt: hdr.isymt,
+            osymt: hdr.osymt,
+            properties: FstProperties::from_bits_truncate(hdr.properties),
+            w: PhantomData,
+        };
+
+        // The ranges of trs are checked once here to be able to access them without bound checks.
+        for (state, s) in fst.states().iter().enumerate() {
+            if s.pos as usize + s.ntrs as usize > num_trs {
+                bail!(
+                    "MappedConstFst : The trs of state {} are out of bounds",
+                    state
+                );
+            }
+        }
+        if let Some(start) = fst.start {
+            if start as usize >= num_states {
+                bail!("MappedConstFst : The start state {} doesn't exist", start);
+            }
+        }
+
+        Ok(fst)
+    }
+
+    fn states(&self) -> &[MappedConstState<W>] {
+        // Safety: alignment and size checked at construction and MappableSemiring guarantees
+        // that the bytes are valid weights.
+        unsafe { cast_slice(self.bytes.bytes(), self.states_offset, self.num_states) }
+    }
+
+    fn state(&self, state_id: StateId) -> Result<&MappedConstState<W>> {
+        self.states()
+            .get(state_id as usize)
+            .ok_or_else(|| format_err!("State {:?} doesn't exist", state_id))
+    }
+
+    fn trs_of(&self, state: &MappedConstState<W>) -> TrsMapped<W> {
+        TrsMapped {
+            bytes: self.bytes.clone(),
+            offset: self.trs_offset + state.pos as usize * size_of::<Tr<W>>(),
+            n: state.ntrs as usize,
+            w: PhantomData,
+        }
+    }
+
+    fn final_weight_of(state: &MappedConstState<W>) -> Option<W> {
+        if state.final_weight.is_zero() {
+            None
+        } else {
+            Some(state.final_weight)
+        }
+    }
+}
+
+impl<W: MappableSemiring> CoreFst<W> for MappedConstFst<W> {
+    type TRS = TrsMapped<W>;
+
+    fn start(&self) -> Option<StateId> {
+        self.start
+    }
+
+    fn final_weight(&self, state_id: StateId) -> Result<Option<W>> {
+        Ok(Self::final_weight_of(self.state(state_id)?))
+    }
+
+    unsafe fn final_weight_unchecked(&self, state_id: StateId) -> Option<W> {
+        Self::final_weight_of(self.states().get_unchecked(state_id as usize))
+    }
+
+    fn num_trs(&self, s: StateId) -> Result<usize> {
+        Ok(self.state(s)?.ntrs as usize)
+    }
+
+    unsafe fn num_trs_unchecked(&self, s: StateId) -> usize {
+        self.states().get_unchecked(s as usize).ntrs as usize
+    }
+
+    fn get_trs(&self, state_id: StateId) -> Result<Self::TRS> {
+        Ok(self.trs_of(self.state(state_id)?))
+    }
+
+    unsafe fn get_trs_unchecked(&self, state_id: StateId) -> Self::TRS {
+        self.trs_of(self.states().get_unchecked(state_id as usize))
+    }
+
+    fn properties(&self) -> FstProperties {
+        self.properties
+    }
+
+    fn num_input_epsilons(&self, state: StateId) -> Result<usize> {
+        Ok(self.state(state)?.niepsilons as usize)
+    }
+
+    fn num_output_epsilons(&self, state: StateId) -> Result<usize> {
+        Ok(self.state(state)?.noepsilons as usize)
+    }
+}
+
+impl<'a, W> StateIterator<'a> for MappedConstFst<W> {
+    type Iter = Range<StateId>;
+    fn states_iter(&'a self) -> Self::Iter {
+        0..(self.num_states as StateId)
+    }
+}
+
+type StateToData<'a, W> = Box<dyn FnMut(StateId) -> FstIterData<W, TrsMapped<W>> + 'a>;
+
+impl<'a, W: MappableSemiring> FstIterator<'a, W> for MappedConstFst<W> {
+    type FstIter = Map<Range<StateId>, StateToData<'a, W>>;
+    fn fst_iter(&'a self) -> Self::FstIter {
+        self.states_iter().map(Box::new(move |state_id| {
+            let state = unsafe { self.states().get_unchecked(state_id as usize) };
+            FstIterData {
+                state_id,
+                trs: self.trs_of(state),
+                final_weight: Self::final_weight_of(state),
+                num_trs: state.ntrs as usize,
+            }
+        }))
+    }
+}
+
+impl<W: MappableSemiring> FstIntoIterator<W> for MappedConstFst<W> {
+    type TrsIter = std::vec::IntoIter<Tr<W>>;
+    type FstIter = Box<dyn Iterator<Item = FstIterData<W, Self::TrsIter>>>;
+
+    /// The trs are copied as the buffer is shared.
+    fn fst_into_iter(self) -> Self::FstIter {
+        Box::new(self.states_iter().map(move |state_id| {
+            let state = unsafe { self.states().get_unchecked(state_id as usize) };
+            FstIterData {
+                state_id,
+                trs: self.trs_of(state).to_vec().into_iter(),
+                final_weight: Self::final_weight_of(state),
+                num_trs: state.ntrs as usize,
+            }
+        }))
+    }
+}
+
+impl<W: MappableSemiring> Fst<W> for MappedConstFst<W> {
+    fn input_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.isymt.as_ref()
+    }
+
+    fn output_symbols(&self) -> Option<&Arc<SymbolTable>> {
+        self.osymt.as_ref()
+    }
+
+    fn set_input_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.isymt = Some(symt)
+    }
+
+    fn set_output_symbols(&mut self, symt: Arc<SymbolTable>) {
+        self.osymt = Some(symt);
+    }
+
+    fn take_input_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.isymt.take()
+    }
+
+    fn take_output_symbols(&mut self) -> Option<Arc<SymbolTable>> {
+        self.osymt.take()
+    }
+}
+
+impl<W: MappableSemiring> ExpandedFst<W> for MappedConstFst<W> {
+    fn num_states(&self) -> usize {
+        self.num_states
+    }
+}
+
+impl<W: MappableSemiring> PartialEq for MappedConstFst<W> {
+    fn eq(&self, other: &Self) -> bool {
+        // Intended: Doesn't check symt and properties
+        self.states() == other.states()
+            && self.trs_of_all() == other.trs_of_all()
+            && self.start == other.start
+    }
+}
+
+impl<W: MappableSemiring> MappedConstFst<W> {
+    fn trs_of_all(&self) -> &[Tr<W>] {
+        // Safety: checked at construction.
+        unsafe { cast_slice(self.bytes.bytes(), self.trs_offset, self.num_trs) }
+    }
+}
+
+impl<W: MappableSemiring> fmt::Debug for MappedConstFst<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedConstFst")
+            .field("num_states", &self.num_states)
+            .field("num_trs", &self.num_trs)
+            .field("start", &self.start)
+            .field("isymt", &self.isymt)
+            .field("osymt", &self.osymt)
+            .field("properties", &self.properties)
+            .finish()
+    }
+}
+
+/// Trs leaving a state of a `MappedConstFst`, read in place from the buffer.
+pub struct TrsMapped<W> {
+    bytes: MappedBytes,
+    offset: usize,
+    n: usize,
+    w: PhantomData<W>,
+}
+
+impl<W: MappableSemiring> Trs<W> for TrsMapped<W> {
+    fn trs(&self) -> &[Tr<W>] {
+        // Safety: the range of trs of each state is checked when the FST is created.
+        unsafe { cast_slice(self.bytes.bytes(), self.offset, self.n) }
+    }
+
+    fn to_trs_vec(&self) -> TrsVec<W> {
+        TrsVec::from(self.trs().to_vec())
+    }
+
+    // Doesn't clone the data, only the Arc
+    fn shallow_clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            offset: self.offset,
+            n: self.n,
+            w: PhantomData,
+        }
+    }
+}
+
+impl<W: MappableSemiring> std::ops::Deref for TrsMapped<W> {
+    type Target = [Tr<W>];
+    fn deref(&self) -> &Self::Target {
+        self.trs()
+    }
+}
+
+impl<W: MappableSemiring> fmt::Debug for TrsMapped<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.trs()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::fst_convert_from_ref;
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{LogWeight, Semiring, TropicalWeight};
+    use crate::utils::transducer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[repr(align(16))]
+    #[derive(Clone, Copy)]
+    struct Block([u8; 16]);
+
+    /// Buffer aligned on 16 bytes.
+    struct AlignedBuffer {
+        data: Vec<Block>,
+        len: usize,
+    }
+
+    impl AlignedBuffer {
+        fn new(bytes: &[u8]) -> Self {
+            let mut data = vec![Block([0; 16]); bytes.chunks(16).len()];
+            for (block, chunk) in data.iter_mut().zip(bytes.chunks(16)) {
+                block.0[..chunk.len()].copy_from_slice(chunk);
+            }
+            Self {
+                data,
+                len: bytes.len(),
+            }
+        }
+    }
+
+    impl AsRef<[u8]> for AlignedBuffer {
+        fn as_ref(&self) -> &[u8] {
+            unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const u8, self.len) }
+        }
+    }
+
+    fn test_fst<W: Semiring>() -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(4);
+        fst.set_start(1)?;
+        fst.emplace_tr(1, 1, 2, W::one(), 0)?;
+        fst.emplace_tr(1, 0, 3, W::one(), 2)?;
+        fst.emplace_tr(0, 4, 0, W::one(), 3)?;
+        fst.set_final(3, W::one())?;
+        fst.set_final(2, W::one())?;
+        let mut symt = SymbolTable::new();
+        symt.add_symbol("a");
+        fst.set_input_symbols(Arc::new(symt));
+        Ok(fst)
+    }
+
+    fn check_mapped_fst<W: MappableSemiring>(fst: &ConstFst<W>) -> Result<()> {
+        let mut bytes = vec![];
+        fst.store_aligned(&mut bytes)?;
+        let buffer = AlignedBuffer::new(&bytes);
+        let buffer_range = buffer.as_ref().as_ptr_range();
+        let mapped_fst = MappedConstFst::<W>::new(buffer)?;
+
+        assert_eq!(mapped_fst.start(), fst.start());
+        assert_eq!(mapped_fst.num_states(), fst.num_states());
+        assert_eq!(mapped_fst.properties(), fst.properties());
+        assert_eq!(mapped_fst.input_symbols(), fst.input_symbols());
+        assert_eq!(mapped_fst.output_symbols(), fst.output_symbols());
+        for s in fst.states_iter() {
+            assert_eq!(mapped_fst.final_weight(s)?, fst.final_weight(s)?);
+            assert_eq!(mapped_fst.get_trs(s)?.trs(), fst.get_trs(s)?.trs());
+            assert_eq!(
+                mapped_fst.num_input_epsilons(s)?,
+                fst.num_input_epsilons(s)?
+            );
+            assert_eq!(
+                mapped_fst.num_output_epsilons(s)?,
+                fst.num_output_epsilons(s)?
+            );
+            // The trs are read in place.
+            let trs = mapped_fst.get_trs(s)?;
+            assert!(trs.is_empty() || buffer_range.contains(&(trs.as_ptr() as *const u8)));
+        }
+        assert!(mapped_fst
+            .final_weight(fst.num_states() as StateId)
+            .is_err());
+
+        let vector_fst: VectorFst<W> = fst_convert_from_ref(&mapped_fst);
+        let expected: VectorFst<W> = fst_convert_from_ref(fst);
+        assert_eq!(vector_fst, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mapped_const_fst() -> Result<()> {
+        check_mapped_fst::<TropicalWeight>(&test_fst()?.into())?;
+        check_mapped_fst::<LogWeight>(&test_fst()?.into())?;
+        let fst: VectorFst<TropicalWeight> = transducer(&[1, 2, 3], &[4, 5], 0.5.into());
+        check_mapped_fst::<TropicalWeight>(&fst.into())?;
+        check_mapped_fst::<TropicalWeight>(&ConstFst::from(VectorFst::new()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_mapped_const_fst_errors() -> Result<()> {
+        let fst: ConstFst<TropicalWeight> = test_fst()?.into();
+
+        let mut bytes = vec![];
+        fst.store(&mut bytes)?;
+        assert!(MappedConstFst::<TropicalWeight>::new(AlignedBuffer::new(&bytes)).is_err());
+
+        let mut bytes = vec![];
+        fst.store_aligned(&mut bytes)?;
+        bytes.pop();
+        assert!(MappedConstFst::<TropicalWeight>::new(AlignedBuffer::new(&bytes)).is_err());
+        assert!(MappedConstFst::<LogWeight>::new(AlignedBuffer::new(&bytes)).is_err());
+
+        // Misaligned buffer.
+        let mut bytes = vec![];
+        fst.store_aligned(&mut bytes)?;
+        let mut shifted = vec![0u8];
+        shifted.extend_from_slice(&bytes);
+        let buffer = AlignedBuffer::new(&shifted);
+        let storage: Storage = Arc::new(buffer);
+        struct Shifted(Storage);
+        impl AsRef<[u8]> for Shifted {
+            fn as_ref(&self) -> &[u8] {
+                &(*self.0).as_ref()[1..]
+            }
+        }
+        assert!(MappedConstFst::<TropicalWeight>::new(Shifted(storage)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mapped_const_fst_storage_resolved_once() -> Result<()> {
+        // `AsRef` returns the valid buffer only on the first call : it must not be called again.
+        struct Switching {
+            buffer: AlignedBuffer,
+            calls: AtomicUsize,
+        }
+        impl AsRef<[u8]> for Switching {
+            fn as_ref(&self) -> &[u8] {
+                if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    self.buffer.as_ref()
+                } else {
+                    &[]
+                }
+            }
+        }
+
+        let fst: ConstFst<TropicalWeight> = test_fst()?.into();
+        let mut bytes = vec![];
+        fst.store_aligned(&mut bytes)?;
+        let mapped_fst = MappedConstFst::<TropicalWeight>::new(Switching {
+            buffer: AlignedBuffer::new(&bytes),
+            calls: AtomicUsize::new(0),
+        })?;
+        for s in fst.states_iter() {
+            assert_eq!(mapped_fst.get_trs(s)?.trs(), fst.get_trs(s)?.trs());
+            assert_eq!(mapped_fst.final_weight(s)?, fst.final_weight(s)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aligned_const_fst_roundtrip() -> Result<()> {
+        let fst: ConstFst<TropicalWeight> = test_fst()?.into();
+        let mut bytes = vec![];
+        fst.store_aligned(&mut bytes)?;
+        let loaded = ConstFst::<TropicalWeight>::load(&bytes)?;
+        assert_eq!(loaded, fst);
+        assert_eq!(loaded.input_symbols(), fst.input_symbols());
+        Ok(())
+    }
+}
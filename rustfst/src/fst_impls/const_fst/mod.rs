@@ -1,10 +1,12 @@
 pub use self::data_structure::ConstFst;
+pub use self::mapped_const_fst::{MappedConstFst, TrsMapped};
 
 mod converters;
 mod data_structure;
 mod expanded_fst;
 mod fst;
 mod iterators;
+mod mapped_const_fst;
 mod misc;
 mod serializable_fst;
 
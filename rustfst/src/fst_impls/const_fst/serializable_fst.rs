@@ -1,9 +1,13 @@
-use std::io::Write;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use itertools::Itertools;
 use nom::bytes::complete::take;
+use nom::combinator::map_res;
 use nom::multi::count;
 use nom::IResult;
 
@@ -11,13 +15,14 @@ use crate::fst_impls::const_fst::data_structure::ConstState;
 use crate::fst_impls::const_fst::{
     CONST_ALIGNED_FILE_VERSION, CONST_ARCH_ALIGNMENT, CONST_FILE_VERSION, CONST_MIN_FILE_VERSION,
 };
-use crate::fst_impls::ConstFst;
+use crate::fst_impls::{ConstFst, VectorFst};
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::{ExpandedFst, Fst, SerializableFst};
 use crate::parsers::bin_fst::fst_header::{FstFlags, FstHeader, OpenFstString, FST_MAGIC_NUMBER};
 use crate::parsers::bin_fst::utils_parsing::{
     parse_bin_fst_tr, parse_final_weight, parse_start_state,
 };
+use crate::parsers::bin_fst::utils_serialization::CountingWriter;
 use crate::parsers::nom_utils::NomCustomError;
 use crate::parsers::parse_bin_i32;
 use crate::parsers::text_fst::ParsedTextFst;
@@ -34,53 +39,23 @@ impl<W: SerializableSemiring> SerializableFst<W> for ConstFst<W> {
         let (_, parsed_fst) = parse_const_fst(data)
             .map_err(|_| format_err!("Error while parsing binary ConstFst"))?;
 
-        Ok(parsed_fst)
-    }
-
-    fn store<O: Write>(&self, mut output: O) -> Result<()> {
-        let mut flags = FstFlags::empty();
-        if self.input_symbols().is_some() {
-            flags |= FstFlags::HAS_ISYMBOLS;
-        }
-        if self.output_symbols().is_some() {
-            flags |= FstFlags::HAS_OSYMBOLS;
+        // The trs of the states are read from their positions : these must be checked to
+        // avoid panicking later on.
+        for (s, state) in parsed_fst.states.iter().enumerate() {
+            let end = state.pos.checked_add(state.ntrs);
+            if !matches!(end, Some(end) if end <= parsed_fst.trs.len()) {
+                bail!(
+                    "Error while parsing binary ConstFst : trs of state {} are out of range",
+                    s
+                );
+            }
         }
 
-        let hdr = FstHeader {
-            magic_number: FST_MAGIC_NUMBER,
-            fst_type: OpenFstString::new(Self::fst_type()),
-            tr_type: OpenFstString::new(Tr::<W>::tr_type()),
-            version: CONST_FILE_VERSION,
-            // TODO: Set flags if the content is aligned
-            flags,
-            properties: self.properties.bits() | ConstFst::<W>::static_properties(),
-            start: self.start.map(|v| v as i64).unwrap_or(-1),
-            num_states: self.num_states() as i64,
-            num_trs: self.trs.len() as i64,
-            isymt: self.input_symbols().cloned(),
-            osymt: self.output_symbols().cloned(),
-        };
-        hdr.write(&mut output)?;
-
-        let zero = W::zero();
-        for const_state in &self.states {
-            let f_weight = const_state.final_weight.as_ref().unwrap_or(&zero);
-            f_weight.write_binary(&mut output)?;
-
-            write_bin_i32(&mut output, const_state.pos as i32)?;
-            write_bin_i32(&mut output, const_state.ntrs as i32)?;
-            write_bin_i32(&mut output, const_state.niepsilons as i32)?;
-            write_bin_i32(&mut output, const_state.noepsilons as i32)?;
-        }
-
-        for tr in &*self.trs {
-            write_bin_i32(&mut output, tr.ilabel as i32)?;
-            write_bin_i32(&mut output, tr.olabel as i32)?;
-            tr.weight.write_binary(&mut output)?;
-            write_bin_i32(&mut output, tr.nextstate as i32)?;
-        }
+        Ok(parsed_fst)
+    }
 
-        Ok(())
+    fn store<O: Write>(&self, output: O) -> Result<()> {
+        self.store_with_alignment(output, false)
     }
 
     fn from_parsed_fst_text(mut parsed_fst_text: ParsedTextFst<W>) -> Result<Self> {
@@ -173,25 +148,105 @@ impl<W: SerializableSemiring> SerializableFst<W> for ConstFst<W> {
 
         Ok(fst)
     }
+
+    fn read_text_from<R: BufRead>(reader: R) -> Result<Self> {
+        Ok(VectorFst::read_text_from(reader)?.into())
+    }
+}
+
+impl<W: SerializableSemiring> ConstFst<W> {
+    /// Stores the FST in the aligned binary format : the states and the transitions are
+    /// padded to start on a multiple of 16 bytes. Such a file can be used without being
+    /// parsed by a `MappedConstFst`.
+    pub fn store_aligned<O: Write>(&self, output: O) -> Result<()> {
+        self.store_with_alignment(output, true)
+    }
+
+    /// Writes the FST to a file in the aligned binary format. See `store_aligned`.
+    pub fn write_aligned<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
+        let output = File::create(path_bin_fst.as_ref()).with_context(|| {
+            format!(
+                "Cannot create ConstFst binary file : {:?}",
+                path_bin_fst.as_ref()
+            )
+        })?;
+        self.store_aligned(BufWriter::new(output))
+    }
+
+    fn store_with_alignment<O: Write>(&self, output: O, aligned: bool) -> Result<()> {
+        let mut output = CountingWriter::new(output);
+        let mut flags = FstFlags::empty();
+        if self.input_symbols().is_some() {
+            flags |= FstFlags::HAS_ISYMBOLS;
+        }
+        if self.output_symbols().is_some() {
+            flags |= FstFlags::HAS_OSYMBOLS;
+        }
+        if aligned {
+            flags |= FstFlags::IS_ALIGNED;
+        }
+
+        let hdr = FstHeader {
+            magic_number: FST_MAGIC_NUMBER,
+            fst_type: OpenFstString::new(Self::fst_type()),
+            tr_type: OpenFstString::new(Tr::<W>::tr_type()),
+            version: CONST_FILE_VERSION,
+            flags,
+            properties: self.properties.bits() | ConstFst::<W>::static_properties(),
+            start: self.start.map(|v| v as i64).unwrap_or(-1),
+            num_states: self.num_states() as i64,
+            num_trs: self.trs.len() as i64,
+            isymt: self.input_symbols().cloned(),
+            osymt: self.output_symbols().cloned(),
+        };
+        hdr.write(&mut output)?;
+
+        if aligned && !self.states.is_empty() {
+            output.align(CONST_ARCH_ALIGNMENT)?;
+        }
+        let zero = W::zero();
+        for const_state in &self.states {
+            let f_weight = const_state.final_weight.as_ref().unwrap_or(&zero);
+            f_weight.write_binary(&mut output)?;
+
+            write_bin_i32(&mut output, const_state.pos as i32)?;
+            write_bin_i32(&mut output, const_state.ntrs as i32)?;
+            write_bin_i32(&mut output, const_state.niepsilons as i32)?;
+            write_bin_i32(&mut output, const_state.noepsilons as i32)?;
+        }
+
+        if aligned && !self.trs.is_empty() {
+            output.align(CONST_ARCH_ALIGNMENT)?;
+        }
+        for tr in &*self.trs {
+            write_bin_i32(&mut output, tr.ilabel as i32)?;
+            write_bin_i32(&mut output, tr.olabel as i32)?;
+            tr.weight.write_binary(&mut output)?;
+            write_bin_i32(&mut output, tr.nextstate as i32)?;
+        }
+
+        Ok(())
+    }
 }
 
 fn parse_const_state<W: SerializableSemiring>(
     i: &[u8],
 ) -> IResult<&[u8], ConstState<W>, NomCustomError<&[u8]>> {
     let (i, final_weight) = W::parse_binary(i)?;
-    let (i, pos) = parse_bin_i32(i)?;
-    let (i, ntrs) = parse_bin_i32(i)?;
-    let (i, niepsilons) = parse_bin_i32(i)?;
-    let (i, noepsilons) = parse_bin_i32(i)?;
+    // Negative values can only come from a corrupted file.
+    let (i, pos) = map_res(parse_bin_i32, usize::try_from)(i)?;
+    let (i, ntrs) = map_res(parse_bin_i32, usize::try_from)(i)?;
+    let (i, niepsilons) = map_res(parse_bin_i32, usize::try_from)(i)?;
+    let (i, noepsilons) = map_res(parse_bin_i32, usize::try_from)(i)?;
 
     Ok((
         i,
         ConstState {
             final_weight: parse_final_weight(final_weight),
-            pos: pos as usize,
-            ntrs: ntrs as usize,
-            niepsilons: niepsilons as usize,
-            noepsilons: noepsilons as usize,
+            pos,
+            ntrs,
+            niepsilons,
+            noepsilons,
         },
     ))
 }
@@ -201,13 +256,14 @@ fn parse_const_fst<W: SerializableSemiring>(
 ) -> IResult<&[u8], ConstFst<W>, NomCustomError<&[u8]>> {
     let stream_len = i.len();
 
-    let (mut i, hdr) = FstHeader::parse(
+    let (mut i, hdr) = FstHeader::parse_checked(
         i,
         CONST_MIN_FILE_VERSION,
         ConstFst::<W>::fst_type(),
         Tr::<W>::tr_type(),
     )?;
-    let aligned = hdr.version == CONST_ALIGNED_FILE_VERSION;
+    let aligned =
+        hdr.version == CONST_ALIGNED_FILE_VERSION || hdr.flags.contains(FstFlags::IS_ALIGNED);
     let pos = stream_len - i.len();
 
     // Align input
@@ -235,3 +291,45 @@ fn parse_const_fst<W: SerializableSemiring>(
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::MutableFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    fn stored_fst() -> Result<(Vec<u8>, usize)> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.set_final(1, TropicalWeight::one())?;
+        fst.emplace_tr(0, 1, 2, 1.0, 1)?;
+        let fst: ConstFst<_> = fst.into();
+        let mut data = vec![];
+        fst.store(&mut data)?;
+        // Each state is a final weight followed by four i32 and each tr is four 32 bits values.
+        let header_len = data.len() - 2 * 20 - 16;
+        Ok((data, header_len))
+    }
+
+    #[test]
+    fn test_load_const_fst_trs_out_of_range() -> Result<()> {
+        let (mut data, header_len) = stored_fst()?;
+        assert!(ConstFst::<TropicalWeight>::load(&data).is_ok());
+        // ntrs of the first state.
+        data[header_len + 8..header_len + 12].copy_from_slice(&2i32.to_le_bytes());
+        assert!(ConstFst::<TropicalWeight>::load(&data).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_const_fst_negative_pos() -> Result<()> {
+        let (mut data, header_len) = stored_fst()?;
+        // pos of the first state.
+        data[header_len + 4..header_len + 8].copy_from_slice(&(-1i32).to_le_bytes());
+        assert!(ConstFst::<TropicalWeight>::load(&data).is_err());
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use std::cmp::Ordering;
 use std::sync::Arc;
 
 use anyhow::Result;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::algorithms::tr_unique::tr_compare;
 use crate::fst_impls::vector_fst::{VectorFst, VectorFstState};
@@ -354,6 +356,18 @@ impl<W: Semiring> MutableFst<W> for VectorFst<W> {
         }
     }
 
+    /// DOESN'T MODIFY THE PROPERTIES
+    #[cfg(feature = "parallel")]
+    fn par_sort_trs<F>(&mut self, f: F)
+    where
+        W: Send,
+        F: Fn(&Tr<W>, &Tr<W>) -> Ordering + Sync,
+    {
+        self.states
+            .par_iter_mut()
+            .for_each(|state| Arc::make_mut(&mut state.trs.0).sort_by(&f));
+    }
+
     /// DOESN'T MODIFY THE PROPERTIES
     unsafe fn unique_trs_unchecked(&mut self, state: StateId) {
         let state = &mut self.states.get_unchecked_mut(state as usize);
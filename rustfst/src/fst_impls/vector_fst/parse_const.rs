@@ -72,7 +72,7 @@ fn parse_const_fst<W: SerializableSemiring>(
 ) -> IResult<&[u8], VectorFst<W>, NomCustomError<&[u8]>> {
     let stream_len = i.len();
 
-    let (mut i, hdr) = FstHeader::parse(
+    let (mut i, hdr) = FstHeader::parse_checked(
         i,
         CONST_MIN_FILE_VERSION,
         // Intentional as the ConstFst file is being parsed.
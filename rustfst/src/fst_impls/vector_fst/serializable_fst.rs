@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::io::{BufRead, Write};
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -16,7 +16,7 @@ use crate::parsers::bin_fst::utils_parsing::{
 };
 use crate::parsers::bin_fst::utils_serialization::write_bin_fst_tr;
 use crate::parsers::nom_utils::NomCustomError;
-use crate::parsers::text_fst::ParsedTextFst;
+use crate::parsers::text_fst::{read_text_fst, ParsedTextFst};
 use crate::parsers::write_bin_i64;
 use crate::semirings::SerializableSemiring;
 use crate::{StateId, Tr, Trs, TrsVec, EPS_LABEL};
@@ -122,6 +122,10 @@ impl<W: SerializableSemiring> SerializableFst<W> for VectorFst<W> {
 
         Ok(fst)
     }
+
+    fn read_text_from<R: BufRead>(reader: R) -> Result<Self> {
+        read_text_fst(reader)
+    }
 }
 
 static VECTOR_MIN_FILE_VERSION: i32 = 2;
@@ -156,7 +160,7 @@ fn parse_vector_fst_state<W: SerializableSemiring>(
 fn parse_vector_fst<W: SerializableSemiring>(
     i: &[u8],
 ) -> IResult<&[u8], VectorFst<W>, NomCustomError<&[u8]>> {
-    let (i, header) = FstHeader::parse(
+    let (i, header) = FstHeader::parse_checked(
         i,
         VECTOR_MIN_FILE_VERSION,
         VectorFst::<W>::fst_type(),
@@ -329,4 +329,27 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_load_and_verify() -> Result<()> {
+        let mut fst = VectorFst::<TropicalWeight>::new();
+        let s1 = fst.add_state();
+        let s2 = fst.add_state();
+        fst.set_start(s1)?;
+        fst.set_final(s2, TropicalWeight::one())?;
+        fst.add_tr(s1, Tr::new(1, 2, 1.0, s2))?;
+
+        let mut data = vec![];
+        fst.store(&mut data)?;
+        assert_eq!(VectorFst::<TropicalWeight>::load_and_verify(&data)?, fst);
+
+        // Tr pointing to a state that doesn't exist.
+        unsafe { fst.add_tr_unchecked(s2, Tr::new(1, 2, 1.0, 4)) };
+        let mut data = vec![];
+        fst.store(&mut data)?;
+        assert!(VectorFst::<TropicalWeight>::load(&data).is_ok());
+        let err = VectorFst::<TropicalWeight>::load_and_verify(&data).unwrap_err();
+        assert!(err.to_string().contains("out of range next state 4"));
+        Ok(())
+    }
 }
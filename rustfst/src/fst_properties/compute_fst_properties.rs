@@ -18,6 +18,42 @@ pub fn compute_fst_properties<W: Semiring, F: ExpandedFst<W>>(
     known: &mut FstProperties,
     use_stored: bool,
 ) -> Result<FstProperties> {
+    compute_fst_properties_with(
+        fst,
+        mask,
+        known,
+        use_stored,
+        |fst, check_weighted_cycles, sccs| {
+            let mut states_props = FstProperties::empty();
+            let mut first_final = None;
+            for state in 0..fst.num_states() {
+                let state = state as StateId;
+                states_props |=
+                    compute_state_properties(fst, state, mask, check_weighted_cycles, sccs)?;
+                if first_final.is_none() && fst.is_final(state)? {
+                    first_final = Some(state);
+                }
+            }
+            Ok((states_props, first_final))
+        },
+    )
+}
+
+/// Shared implementation of `compute_fst_properties`. `states_properties` returns the union
+/// of the properties computed by `compute_state_properties` over all the states along with
+/// the first final state.
+pub(crate) fn compute_fst_properties_with<W, F, S>(
+    fst: &F,
+    mask: FstProperties,
+    known: &mut FstProperties,
+    use_stored: bool,
+    states_properties: S,
+) -> Result<FstProperties>
+where
+    W: Semiring,
+    F: ExpandedFst<W>,
+    S: FnOnce(&F, bool, &[i32]) -> Result<(FstProperties, Option<StateId>)>,
+{
     let fst_props = fst.properties();
 
     if use_stored {
@@ -78,132 +114,171 @@ pub fn compute_fst_properties<W: Semiring, F: ExpandedFst<W>>(
             comp_props |= FstProperties::UNWEIGHTED_CYCLES;
         }
 
-        let mut nfinal = 0;
-        for state in 0..fst.num_states() {
-            let state = state as StateId;
-            let mut ilabels = if mask
-                .intersects(FstProperties::I_DETERMINISTIC | FstProperties::NOT_I_DETERMINISTIC)
-            {
-                Some(HashSet::new())
-            } else {
-                None
-            };
-            let mut olabels = if mask
-                .intersects(FstProperties::O_DETERMINISTIC | FstProperties::NOT_O_DETERMINISTIC)
-            {
-                Some(HashSet::new())
-            } else {
-                None
-            };
-
-            let mut prev_tr: Option<&Tr<W>> = None;
-            for tr in fst.get_trs(state)?.trs() {
-                // There is already an outgoing transition with this ilabel
-                if let Some(ilabels_in) = &ilabels {
-                    if ilabels_in.contains(&tr.ilabel) {
-                        comp_props |= FstProperties::NOT_I_DETERMINISTIC;
-                        comp_props &= !FstProperties::I_DETERMINISTIC;
-                    }
-                }
+        let check_weighted_cycles = comp_props.contains(FstProperties::UNWEIGHTED_CYCLES);
+        let (states_props, first_final) = states_properties(fst, check_weighted_cycles, &sccs)?;
+        apply_state_properties(&mut comp_props, states_props);
 
-                // There is already an outgoing transition with this olabel
-                if let Some(olabels_in) = &olabels {
-                    if olabels_in.contains(&tr.olabel) {
-                        comp_props |= FstProperties::NOT_O_DETERMINISTIC;
-                        comp_props &= !FstProperties::O_DETERMINISTIC;
-                    }
-                }
+        // A final state which is not the last one.
+        if let Some(first_final) = first_final {
+            if (first_final as usize) + 1 < fst.num_states() {
+                comp_props |= FstProperties::NOT_STRING;
+                comp_props &= !FstProperties::STRING;
+            }
+        }
 
-                if tr.ilabel != tr.olabel {
-                    comp_props |= FstProperties::NOT_ACCEPTOR;
-                    comp_props &= !FstProperties::ACCEPTOR;
-                }
+        if let Some(start) = fst.start() {
+            if start != 0 {
+                comp_props |= FstProperties::NOT_STRING;
+                comp_props &= !FstProperties::STRING;
+            }
+        }
+    }
 
-                if tr.ilabel == 0 && tr.olabel == 0 {
-                    comp_props |= FstProperties::EPSILONS;
-                    comp_props &= !FstProperties::NO_EPSILONS;
-                }
+    *known = known_properties(comp_props);
+    Ok(comp_props)
+}
 
-                if tr.ilabel == 0 {
-                    comp_props |= FstProperties::I_EPSILONS;
-                    comp_props &= !FstProperties::NO_I_EPSILONS;
-                }
+/// Properties that a state can invalidate, with the property each of them clears.
+const STATE_PROPERTIES: [(FstProperties, FstProperties); 12] = [
+    (
+        FstProperties::NOT_I_DETERMINISTIC,
+        FstProperties::I_DETERMINISTIC,
+    ),
+    (
+        FstProperties::NOT_O_DETERMINISTIC,
+        FstProperties::O_DETERMINISTIC,
+    ),
+    (FstProperties::NOT_ACCEPTOR, FstProperties::ACCEPTOR),
+    (FstProperties::EPSILONS, FstProperties::NO_EPSILONS),
+    (FstProperties::I_EPSILONS, FstProperties::NO_I_EPSILONS),
+    (FstProperties::O_EPSILONS, FstProperties::NO_O_EPSILONS),
+    (
+        FstProperties::NOT_I_LABEL_SORTED,
+        FstProperties::I_LABEL_SORTED,
+    ),
+    (
+        FstProperties::NOT_O_LABEL_SORTED,
+        FstProperties::O_LABEL_SORTED,
+    ),
+    (FstProperties::WEIGHTED, FstProperties::UNWEIGHTED),
+    (
+        FstProperties::WEIGHTED_CYCLES,
+        FstProperties::UNWEIGHTED_CYCLES,
+    ),
+    (FstProperties::NOT_TOP_SORTED, FstProperties::TOP_SORTED),
+    (FstProperties::NOT_STRING, FstProperties::STRING),
+];
 
-                if tr.olabel == 0 {
-                    comp_props |= FstProperties::O_EPSILONS;
-                    comp_props &= !FstProperties::NO_O_EPSILONS;
-                }
+/// Sets in `comp_props` the properties found by `compute_state_properties` and clears their
+/// opposite.
+fn apply_state_properties(comp_props: &mut FstProperties, state_props: FstProperties) {
+    for (found, cleared) in STATE_PROPERTIES.iter() {
+        if state_props.contains(*found) {
+            *comp_props |= *found;
+            *comp_props &= !*cleared;
+        }
+    }
+}
 
-                // Not first transition
-                if let Some(_prev_tr) = prev_tr {
-                    if tr.ilabel < _prev_tr.ilabel {
-                        comp_props |= FstProperties::NOT_I_LABEL_SORTED;
-                        comp_props &= !FstProperties::I_LABEL_SORTED;
-                    }
-
-                    if tr.olabel < _prev_tr.olabel {
-                        comp_props |= FstProperties::NOT_O_LABEL_SORTED;
-                        comp_props &= !FstProperties::O_LABEL_SORTED;
-                    }
-                }
+/// Returns the properties invalidated by the trs and the final weight of a single state.
+pub(crate) fn compute_state_properties<W: Semiring, F: ExpandedFst<W>>(
+    fst: &F,
+    state: StateId,
+    mask: FstProperties,
+    check_weighted_cycles: bool,
+    sccs: &[i32],
+) -> Result<FstProperties> {
+    let mut state_props = FstProperties::empty();
+    let mut ilabels =
+        if mask.intersects(FstProperties::I_DETERMINISTIC | FstProperties::NOT_I_DETERMINISTIC) {
+            Some(HashSet::new())
+        } else {
+            None
+        };
+    let mut olabels =
+        if mask.intersects(FstProperties::O_DETERMINISTIC | FstProperties::NOT_O_DETERMINISTIC) {
+            Some(HashSet::new())
+        } else {
+            None
+        };
 
-                if !tr.weight.is_one() && !tr.weight.is_zero() {
-                    comp_props |= FstProperties::WEIGHTED;
-                    comp_props &= !FstProperties::UNWEIGHTED;
+    let mut prev_tr: Option<&Tr<W>> = None;
+    let trs = fst.get_trs(state)?;
+    for tr in trs.trs() {
+        // There is already an outgoing transition with this ilabel
+        if let Some(ilabels_in) = &ilabels {
+            if ilabels_in.contains(&tr.ilabel) {
+                state_props |= FstProperties::NOT_I_DETERMINISTIC;
+            }
+        }
 
-                    if comp_props.contains(FstProperties::UNWEIGHTED_CYCLES)
-                        && sccs[state as usize] == sccs[tr.nextstate as usize]
-                    {
-                        comp_props |= FstProperties::WEIGHTED_CYCLES;
-                        comp_props &= !FstProperties::UNWEIGHTED_CYCLES;
-                    }
-                }
+        // There is already an outgoing transition with this olabel
+        if let Some(olabels_in) = &olabels {
+            if olabels_in.contains(&tr.olabel) {
+                state_props |= FstProperties::NOT_O_DETERMINISTIC;
+            }
+        }
 
-                if tr.nextstate <= state {
-                    comp_props |= FstProperties::NOT_TOP_SORTED;
-                    comp_props &= !FstProperties::TOP_SORTED;
-                }
+        if tr.ilabel != tr.olabel {
+            state_props |= FstProperties::NOT_ACCEPTOR;
+        }
 
-                if tr.nextstate != state + 1 {
-                    comp_props |= FstProperties::NOT_STRING;
-                    comp_props &= !FstProperties::STRING;
-                }
+        if tr.ilabel == 0 && tr.olabel == 0 {
+            state_props |= FstProperties::EPSILONS;
+        }
 
-                prev_tr = Some(tr);
+        if tr.ilabel == 0 {
+            state_props |= FstProperties::I_EPSILONS;
+        }
 
-                if let Some(ilabels_in) = &mut ilabels {
-                    ilabels_in.insert(tr.ilabel);
-                }
-                if let Some(olabels_in) = &mut olabels {
-                    olabels_in.insert(tr.olabel);
-                }
-            }
+        if tr.olabel == 0 {
+            state_props |= FstProperties::O_EPSILONS;
+        }
 
-            if nfinal > 0 {
-                comp_props |= FstProperties::NOT_STRING;
-                comp_props &= !FstProperties::STRING;
+        // Not first transition
+        if let Some(_prev_tr) = prev_tr {
+            if tr.ilabel < _prev_tr.ilabel {
+                state_props |= FstProperties::NOT_I_LABEL_SORTED;
             }
-            if fst.is_final(state)? {
-                let final_weight = unsafe { fst.final_weight_unchecked(state).unsafe_unwrap() };
-                if !final_weight.is_one() {
-                    comp_props |= FstProperties::WEIGHTED;
-                    comp_props &= !FstProperties::UNWEIGHTED;
-                }
-                nfinal += 1;
-            } else if fst.num_trs(state)? != 1 {
-                comp_props |= FstProperties::NOT_STRING;
-                comp_props &= !FstProperties::STRING;
+
+            if tr.olabel < _prev_tr.olabel {
+                state_props |= FstProperties::NOT_O_LABEL_SORTED;
             }
         }
 
-        if let Some(start) = fst.start() {
-            if start != 0 {
-                comp_props |= FstProperties::NOT_STRING;
-                comp_props &= !FstProperties::STRING;
+        if !tr.weight.is_one() && !tr.weight.is_zero() {
+            state_props |= FstProperties::WEIGHTED;
+
+            if check_weighted_cycles && sccs[state as usize] == sccs[tr.nextstate as usize] {
+                state_props |= FstProperties::WEIGHTED_CYCLES;
             }
         }
+
+        if tr.nextstate <= state {
+            state_props |= FstProperties::NOT_TOP_SORTED;
+        }
+
+        if tr.nextstate != state + 1 {
+            state_props |= FstProperties::NOT_STRING;
+        }
+
+        prev_tr = Some(tr);
+
+        if let Some(ilabels_in) = &mut ilabels {
+            ilabels_in.insert(tr.ilabel);
+        }
+        if let Some(olabels_in) = &mut olabels {
+            olabels_in.insert(tr.olabel);
+        }
     }
 
-    *known = known_properties(comp_props);
-    Ok(comp_props)
+    if fst.is_final(state)? {
+        let final_weight = unsafe { fst.final_weight_unchecked(state).unsafe_unwrap() };
+        if !final_weight.is_one() {
+            state_props |= FstProperties::WEIGHTED;
+        }
+    } else if fst.num_trs(state)? != 1 {
+        state_props |= FstProperties::NOT_STRING;
+    }
+    Ok(state_props)
 }
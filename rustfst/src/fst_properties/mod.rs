@@ -9,5 +9,9 @@ pub mod mutable_properties {
 }
 
 pub use self::compute_fst_properties::compute_fst_properties;
+#[cfg(feature = "parallel")]
+pub(crate) use self::compute_fst_properties::{
+    compute_fst_properties_with, compute_state_properties,
+};
 pub use self::properties::FstProperties;
 pub use self::utils::{compat_properties, known_properties};
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 
+use crate::algorithms::ShortestPathsIterator;
 use crate::fst_properties::FstProperties;
 use crate::fst_traits::final_states_iterator::FinalStatesIterator;
 use crate::fst_traits::iterators::StateIterator;
@@ -317,6 +318,15 @@ pub trait Fst<W: Semiring>:
     {
         StringPathsIterator::new(self)
     }
+
+    /// Returns an Iterator on the paths accepted by the Fst in increasing weight order.
+    /// See `ShortestPathsIterator`.
+    fn shortest_paths_iter(&self) -> Result<ShortestPathsIterator<'_, W, Self>>
+    where
+        Self: std::marker::Sized,
+    {
+        ShortestPathsIterator::new(self)
+    }
 }
 
 #[cfg(test)]
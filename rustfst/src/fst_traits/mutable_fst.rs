@@ -388,6 +388,21 @@ pub trait MutableFst<W: Semiring>: ExpandedFst<W> {
 
     fn sort_trs_unchecked<F: Fn(&Tr<W>, &Tr<W>) -> Ordering>(&mut self, state: StateId, f: F);
 
+    /// Sorts the trs of all the states with `f`. The states are sorted concurrently when the
+    /// implementation supports it, the default one sorting them one after the other.
+    ///
+    /// Be careful as this function doesn't update the FstProperties!
+    #[cfg(feature = "parallel")]
+    fn par_sort_trs<F>(&mut self, f: F)
+    where
+        W: Send,
+        F: Fn(&Tr<W>, &Tr<W>) -> Ordering + Sync,
+    {
+        for state in 0..self.num_states() {
+            self.sort_trs_unchecked(state as StateId, &f);
+        }
+    }
+
     /// Remove duplicate Trs leaving the state `state` with the same `ilabel`, `olabel`, `weight`
     /// and `nextstate`.
     ///
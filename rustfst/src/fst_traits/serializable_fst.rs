@@ -1,11 +1,12 @@
 use std::fs::File;
-use std::io::{BufWriter, LineWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, LineWriter, Write};
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use unsafe_unwrap::UnsafeUnwrap;
 
 use crate::fst_traits::ExpandedFst;
+use crate::parsers::bin_fst::verify_bin_fst;
 use crate::parsers::text_fst::ParsedTextFst;
 use crate::semirings::SerializableSemiring;
 use crate::Trs;
@@ -36,6 +37,21 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
         })?;
         Self::load(&data)
     }
+    /// Loads an FST from the binary format data and checks it with `verify`. Fails if the
+    /// data is corrupted instead of returning an FST on which the algorithms could panic.
+    fn load_and_verify(input: &[u8]) -> Result<Self> {
+        let fst = Self::load(input)?;
+        verify_bin_fst(&fst)?;
+        Ok(fst)
+    }
+
+    /// Loads an FST from a file in binary format and checks it with `verify`.
+    fn read_and_verify<P: AsRef<Path>>(path_bin_fst: P) -> Result<Self> {
+        let fst = Self::read(path_bin_fst)?;
+        verify_bin_fst(&fst)?;
+        Ok(fst)
+    }
+
     /// Writes the FST to a file in binary format.
     fn write<P: AsRef<Path>>(&self, path_bin_fst: P) -> Result<()> {
         let output = std::fs::File::create(path_bin_fst.as_ref()).with_context(|| {
@@ -53,32 +69,60 @@ pub trait SerializableFst<W: SerializableSemiring>: ExpandedFst<W> {
     /// Turns a generic wFST format into the one of the wFST.
     fn from_parsed_fst_text(parsed_fst_text: ParsedTextFst<W>) -> Result<Self>;
 
-    /// Deserializes a wFST in text from a path and returns a loaded wFST.
-    fn from_text_string(fst_string: &str) -> Result<Self> {
-        let parsed_text_fst = ParsedTextFst::from_string(fst_string)?;
+    /// Deserializes a wFST in text from a `BufRead`, one line at a time. Fields may be
+    /// separated by tabulations or spaces and a malformed line is reported with its line
+    /// and column numbers.
+    ///
+    /// The default implementation stores all the parsed rows in a `ParsedTextFst` before
+    /// building the wFST with `from_parsed_fst_text`. The wFSTs of this crate override it to
+    /// add the rows to the wFST, or to a `VectorFst` converted afterwards, as they are read.
+    fn read_text_from<R: BufRead>(reader: R) -> Result<Self> {
+        let parsed_text_fst = ParsedTextFst::from_reader(reader)?;
         Self::from_parsed_fst_text(parsed_text_fst)
     }
 
+    /// Deserializes a wFST in text from a string and returns a loaded wFST.
+    fn from_text_string(fst_string: &str) -> Result<Self> {
+        Self::read_text_from(fst_string.as_bytes())
+    }
+
     /// Deserializes a wFST in text from a path and returns a loaded wFST.
     fn read_text<P: AsRef<Path>>(path_text_fst: P) -> Result<Self> {
-        let parsed_text_fst = ParsedTextFst::from_path(path_text_fst)?;
-        Self::from_parsed_fst_text(parsed_text_fst)
+        let file = File::open(path_text_fst.as_ref()).with_context(|| {
+            format!(
+                "Can't open {}Fst text file : {:?}",
+                Self::fst_type(),
+                path_text_fst.as_ref()
+            )
+        })?;
+        Self::read_text_from(BufReader::new(file))
+    }
+
+    /// Serializes the FST in a text format compatible with OpenFST to a `Write`.
+    fn write_text_to<O: Write>(&self, output: O) -> Result<()> {
+        let mut writer = BufWriter::new(output);
+        write_fst!(self, writer, true, false);
+        writer.flush()?;
+        Ok(())
     }
 
     /// Serializes the FST as a text file in a format compatible with OpenFST.
     fn write_text<P: AsRef<Path>>(&self, path_output: P) -> Result<()> {
-        let buffer = File::create(path_output.as_ref())?;
-        let mut line_writer = LineWriter::new(buffer);
-        write_fst!(self, line_writer, true, false);
-        Ok(())
+        let output = File::create(path_output.as_ref()).with_context(|| {
+            format!(
+                "Cannot create {}Fst text file : {:?}",
+                Self::fst_type(),
+                path_output.as_ref(),
+            )
+        })?;
+        self.write_text_to(output)
     }
 
     /// Writes the text representation of the FST into a String.
     fn text(&self) -> Result<String> {
-        let buffer = Vec::<u8>::new();
-        let mut line_writer = LineWriter::new(buffer);
-        write_fst!(self, line_writer, true, false);
-        Ok(String::from_utf8(line_writer.into_inner()?)?)
+        let mut buffer = Vec::<u8>::new();
+        self.write_text_to(&mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
     }
 
     /// Serializes the FST as a DOT file compatible with GraphViz binaries.
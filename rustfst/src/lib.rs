@@ -263,6 +263,7 @@ mod fst_path;
 mod parsers;
 mod string_path;
 
+pub use crate::parsers::bin_fst::fst_header::FstHeader;
 pub use crate::parsers::nom_utils::NomCustomError;
 
 /// A representable float near .001. (Used in Quantize)
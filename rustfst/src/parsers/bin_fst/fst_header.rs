@@ -25,8 +25,9 @@ bitflags! {
     }
 }
 
+/// Header of an FST in the binary format of OpenFst.
 #[derive(Debug)]
-pub(crate) struct FstHeader {
+pub struct FstHeader {
     pub(crate) magic_number: i32,
     pub(crate) fst_type: OpenFstString,
     pub(crate) tr_type: OpenFstString,
@@ -67,20 +68,13 @@ fn optionally_write_symt<W: Write>(file: &mut W, symt: &Option<Arc<SymbolTable>>
 }
 
 impl FstHeader {
-    pub(crate) fn parse<S1: AsRef<str>, S2: AsRef<str>>(
-        i: &[u8],
-        min_file_version: i32,
-        fst_loading_type: S1,
-        tr_loading_type: S2,
-    ) -> IResult<&[u8], FstHeader, NomCustomError<&[u8]>> {
+    /// Parses the header of a binary FST whatever its fst type, tr type and version are, e.g.
+    /// to find out which type of FST the data must be loaded as.
+    pub fn parse(i: &[u8]) -> IResult<&[u8], FstHeader, NomCustomError<&[u8]>> {
         let (i, magic_number) = verify(parse_bin_i32, |v: &i32| *v == FST_MAGIC_NUMBER)(i)?;
-        let (i, fst_type) = verify(OpenFstString::parse, |v| {
-            v.s.as_str() == fst_loading_type.as_ref()
-        })(i)?;
-        let (i, tr_type) = verify(OpenFstString::parse, |v| {
-            v.s.as_str() == tr_loading_type.as_ref()
-        })(i)?;
-        let (i, version) = verify(parse_bin_i32, |v: &i32| *v >= min_file_version)(i)?;
+        let (i, fst_type) = OpenFstString::parse(i)?;
+        let (i, tr_type) = OpenFstString::parse(i)?;
+        let (i, version) = parse_bin_i32(i)?;
         let (i, flags) = map_res(parse_bin_u32, |v: u32| {
             FstFlags::from_bits(v).ok_or("Could not parse Fst Flags")
         })(i)?;
@@ -110,6 +104,50 @@ impl FstHeader {
         ))
     }
 
+    /// Parses the header of a binary FST and checks that it has the expected fst type, tr type
+    /// and version.
+    pub(crate) fn parse_checked<S1: AsRef<str>, S2: AsRef<str>>(
+        i: &[u8],
+        min_file_version: i32,
+        fst_loading_type: S1,
+        tr_loading_type: S2,
+    ) -> IResult<&[u8], FstHeader, NomCustomError<&[u8]>> {
+        verify(Self::parse, |hdr: &FstHeader| {
+            hdr.fst_type.s.as_str() == fst_loading_type.as_ref()
+                && hdr.tr_type.s.as_str() == tr_loading_type.as_ref()
+                && hdr.version >= min_file_version
+        })(i)
+    }
+
+    /// Loads the header of a binary FST, ignoring the data following it.
+    pub fn load(data: &[u8]) -> Result<FstHeader> {
+        let (_, hdr) =
+            Self::parse(data).map_err(|_| format_err!("Error while parsing binary FST header"))?;
+        Ok(hdr)
+    }
+
+    /// Type of the FST, e.g. `vector` or `const`.
+    pub fn fst_type(&self) -> &str {
+        self.fst_type.s.as_str()
+    }
+
+    /// Type of the trs, e.g. `standard` for the tropical semiring or `log`.
+    pub fn tr_type(&self) -> &str {
+        self.tr_type.s.as_str()
+    }
+
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    pub fn num_states(&self) -> i64 {
+        self.num_states
+    }
+
+    pub fn num_trs(&self) -> i64 {
+        self.num_trs
+    }
+
     pub(crate) fn write<W: Write>(&self, file: &mut W) -> Result<()> {
         //magic_number: i32,
         write_bin_i32(file, self.magic_number)?;
@@ -144,15 +182,9 @@ impl OpenFstString {
         }
     }
     pub(crate) fn parse(i: &[u8]) -> IResult<&[u8], OpenFstString, NomCustomError<&[u8]>> {
-        let (i, n) = parse_bin_i32(i)?;
-        let (i, s) = take(n as usize)(i)?;
-        Ok((
-            i,
-            OpenFstString {
-                n,
-                s: String::from_utf8(s.to_vec()).unwrap(),
-            },
-        ))
+        let (i, n) = verify(parse_bin_i32, |n: &i32| *n >= 0)(i)?;
+        let (i, s) = map_res(take(n as usize), |s: &[u8]| String::from_utf8(s.to_vec()))(i)?;
+        Ok((i, OpenFstString { n, s }))
     }
 
     pub(crate) fn write<W: Write>(&self, file: &mut W) -> Result<()> {
@@ -166,3 +198,36 @@ impl From<OpenFstString> for String {
         os.s
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::{ConstFst, VectorFst};
+    use crate::fst_traits::{MutableFst, SerializableFst};
+    use crate::semirings::{LogWeight, Semiring};
+
+    #[test]
+    fn test_load_fst_header() -> Result<()> {
+        let mut fst = VectorFst::<LogWeight>::new();
+        fst.add_states(2);
+        fst.set_start(0)?;
+        fst.set_final(1, LogWeight::one())?;
+        fst.emplace_tr(0, 1, 2, 0.5, 1)?;
+
+        let mut data = vec![];
+        fst.store(&mut data)?;
+        let header = FstHeader::load(&data)?;
+        assert_eq!(header.fst_type(), "vector");
+        assert_eq!(header.tr_type(), "log");
+        assert_eq!(header.num_states(), 2);
+        assert_eq!(header.num_trs(), 1);
+
+        let mut data = vec![];
+        ConstFst::from(fst).store(&mut data)?;
+        assert_eq!(FstHeader::load(&data)?.fst_type(), "const");
+
+        assert!(FstHeader::load(b"not an fst").is_err());
+        Ok(())
+    }
+}
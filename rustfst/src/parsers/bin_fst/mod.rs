@@ -1,3 +1,20 @@
+use anyhow::Result;
+
+use crate::algorithms::verify;
+use crate::fst_traits::ExpandedFst;
+use crate::semirings::Semiring;
+
 pub(crate) mod fst_header;
 pub(crate) mod utils_parsing;
 pub(crate) mod utils_serialization;
+
+/// Runs `verify` on an FST loaded from the binary format and fails with all the problems
+/// found if it is not valid.
+pub(crate) fn verify_bin_fst<W: Semiring, F: ExpandedFst<W>>(fst: &F) -> Result<()> {
+    let errors = verify(fst)?;
+    if !errors.is_empty() {
+        let errors: Vec<_> = errors.iter().map(|e| e.to_string()).collect();
+        bail!("Invalid binary FST : {}", errors.join(", "));
+    }
+    Ok(())
+}
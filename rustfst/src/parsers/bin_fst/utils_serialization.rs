@@ -30,3 +30,34 @@ pub fn write_final_weight<F: Write, W: SerializableSemiring>(
     };
     Ok(())
 }
+
+/// Keeps track of the number of bytes written, to be able to align the output.
+pub(crate) struct CountingWriter<O: Write> {
+    inner: O,
+    count: usize,
+}
+
+impl<O: Write> CountingWriter<O> {
+    pub(crate) fn new(inner: O) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Pads the output with zeros up to the next multiple of `alignment` bytes.
+    pub(crate) fn align(&mut self, alignment: usize) -> Result<()> {
+        let padding = (alignment - self.count % alignment) % alignment;
+        self.write_all(&vec![0; padding])?;
+        Ok(())
+    }
+}
+
+impl<O: Write> Write for CountingWriter<O> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
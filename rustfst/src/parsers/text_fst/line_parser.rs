@@ -0,0 +1,266 @@
+use std::io::BufRead;
+
+use anyhow::{Context, Result};
+
+use crate::fst_traits::MutableFst;
+use crate::parsers::text_fst::parsed_text_fst::{FinalState, RowParsed, Transition};
+use crate::semirings::SerializableSemiring;
+use crate::{StateId, Tr};
+
+/// Splits a line on tabulations and spaces and returns the fields with their column (1-based).
+fn split_fields(line: &str) -> Vec<(usize, &str)> {
+    let line = line.trim_end_matches(&['\n', '\r'][..]);
+    let mut fields = vec![];
+    let mut start = None;
+    for (idx, c) in line.char_indices() {
+        if c == '\t' || c == ' ' {
+            if let Some(s) = start.take() {
+                fields.push((s + 1, &line[s..idx]));
+            }
+        } else if start.is_none() {
+            start = Some(idx);
+        }
+    }
+    if let Some(s) = start {
+        fields.push((s + 1, &line[s..]));
+    }
+    fields
+}
+
+fn parse_field<T: std::str::FromStr>(
+    field: (usize, &str),
+    line_number: usize,
+    name: &str,
+) -> Result<T> {
+    let (column, value) = field;
+    value.parse().map_err(|_| {
+        format_err!(
+            "Error while parsing text fst at line {}, column {} : invalid {} {:?}",
+            line_number,
+            column,
+            name,
+            value
+        )
+    })
+}
+
+fn parse_weight<W: SerializableSemiring>(field: (usize, &str), line_number: usize) -> Result<W> {
+    let (column, value) = field;
+    match W::parse_text(value) {
+        Ok(("", weight)) => Ok(weight),
+        _ => bail!(
+            "Error while parsing text fst at line {}, column {} : invalid weight {:?}",
+            line_number,
+            column,
+            value
+        ),
+    }
+}
+
+/// Parses a single line of an FST in text format. Returns `None` for a blank line.
+pub(crate) fn parse_text_fst_line<W: SerializableSemiring>(
+    line: &str,
+    line_number: usize,
+) -> Result<Option<RowParsed<W>>> {
+    let fields = split_fields(line);
+    let row = match fields.len() {
+        0 => return Ok(None),
+        1 => RowParsed::FinalState(FinalState::new(
+            parse_field(fields[0], line_number, "state")?,
+            None,
+        )),
+        2 => {
+            let state = parse_field(fields[0], line_number, "state")?;
+            if fields[1].1 == "Infinity" {
+                RowParsed::InfinityFinalState(state)
+            } else {
+                RowParsed::FinalState(FinalState::new(
+                    state,
+                    Some(parse_weight(fields[1], line_number)?),
+                ))
+            }
+        }
+        4 | 5 => RowParsed::Transition(Transition::new(
+            parse_field(fields[0], line_number, "state")?,
+            parse_field(fields[2], line_number, "ilabel")?,
+            parse_field(fields[3], line_number, "olabel")?,
+            fields
+                .get(4)
+                .map(|f| parse_weight(*f, line_number))
+                .transpose()?,
+            parse_field(fields[1], line_number, "nextstate")?,
+        )),
+        n => bail!(
+            "Error while parsing text fst at line {} : expected 1, 2, 4 or 5 fields, found {}",
+            line_number,
+            n
+        ),
+    };
+    Ok(Some(row))
+}
+
+/// Calls `f` on each row of an FST in text format read from `reader`, one line at a time.
+pub(crate) fn for_each_row_parsed<W, R, F>(mut reader: R, mut f: F) -> Result<()>
+where
+    W: SerializableSemiring,
+    R: BufRead,
+    F: FnMut(RowParsed<W>) -> Result<()>,
+{
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        line_number += 1;
+        let n = reader
+            .read_line(&mut line)
+            .with_context(|| format!("Error while reading text fst at line {}", line_number))?;
+        if n == 0 {
+            return Ok(());
+        }
+        if let Some(row) = parse_text_fst_line(&line, line_number)? {
+            f(row)?;
+        }
+    }
+}
+
+/// Builds an FST from its text format read line by line, without storing the parsed rows.
+pub(crate) fn read_text_fst<W, F, R>(reader: R) -> Result<F>
+where
+    W: SerializableSemiring,
+    F: MutableFst<W>,
+    R: BufRead,
+{
+    fn add_states_until<W: SerializableSemiring, F: MutableFst<W>>(fst: &mut F, s: StateId) {
+        let n = s as usize + 1;
+        if fst.num_states() < n {
+            fst.add_states(n - fst.num_states());
+        }
+    }
+
+    let mut fst = F::new();
+    let mut first_row = true;
+    for_each_row_parsed(reader, |row: RowParsed<W>| {
+        if first_row {
+            let start_state = match &row {
+                RowParsed::Transition(t) => t.state,
+                RowParsed::FinalState(f) => f.state,
+                RowParsed::InfinityFinalState(s) => *s,
+            };
+            add_states_until(&mut fst, start_state);
+            fst.set_start(start_state)?;
+            first_row = false;
+        }
+        match row {
+            RowParsed::Transition(t) => {
+                add_states_until(&mut fst, std::cmp::max(t.state, t.nextstate));
+                fst.add_tr(
+                    t.state,
+                    Tr::new(
+                        t.ilabel,
+                        t.olabel,
+                        t.weight.unwrap_or_else(W::one),
+                        t.nextstate,
+                    ),
+                )?;
+            }
+            RowParsed::FinalState(f) => {
+                add_states_until(&mut fst, f.state);
+                fst.set_final(f.state, f.weight.unwrap_or_else(W::one))?;
+            }
+            RowParsed::InfinityFinalState(_) => {}
+        }
+        Ok(())
+    })?;
+    fst.compute_and_update_properties_all()?;
+    Ok(fst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fst_impls::{ConstFst, VectorFst};
+    use crate::fst_traits::{CoreFst, SerializableFst};
+    use crate::parsers::text_fst::ParsedTextFst;
+    use crate::semirings::{Semiring, TropicalWeight};
+
+    static FST_TEXT: &str = "0\t1\t32\t32\n1\t2\t45\t45\n2\t3\t18\t18\t0.25\n3\t4\t45\t45\n4\t5\t5\t5\t0.31\n3\t0.67\n5\n";
+
+    #[test]
+    fn test_parse_text_fst_line() -> Result<()> {
+        assert_eq!(
+            parse_text_fst_line::<TropicalWeight>("0 \t1  2\t3\t0.5\r\n", 1)?,
+            Some(RowParsed::Transition(Transition::new(
+                0,
+                2,
+                3,
+                Some(TropicalWeight::new(0.5)),
+                1
+            )))
+        );
+        assert_eq!(
+            parse_text_fst_line::<TropicalWeight>("3\n", 1)?,
+            Some(RowParsed::FinalState(FinalState::new(3, None)))
+        );
+        assert_eq!(
+            parse_text_fst_line::<TropicalWeight>("3\tInfinity\n", 1)?,
+            Some(RowParsed::InfinityFinalState(3))
+        );
+        assert_eq!(parse_text_fst_line::<TropicalWeight>(" \n", 1)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_text_fst_line_errors() {
+        let err = |s: &str| {
+            VectorFst::<TropicalWeight>::from_text_string(s)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err("0\t1\t2\t2\n1\t2\tx\t2\n"),
+            "Error while parsing text fst at line 2, column 5 : invalid ilabel \"x\""
+        );
+        assert_eq!(
+            err("0\t1\t2\t2\t0.3a\n"),
+            "Error while parsing text fst at line 1, column 9 : invalid weight \"0.3a\""
+        );
+        assert_eq!(
+            err("0\t1\t2\n"),
+            "Error while parsing text fst at line 1 : expected 1, 2, 4 or 5 fields, found 3"
+        );
+    }
+
+    #[test]
+    fn test_read_text_fst_same_as_parsed_text_fst() -> Result<()> {
+        for text in &[
+            FST_TEXT,
+            "0\t2\t0\t0\n1\n2\t1\t12\t25\n",
+            "0\t1\t12\t25\t0.3\n1\tInfinity\n0\t0\n",
+            "3\tInfinity\n0\t1\t12\t25\n",
+            "",
+        ] {
+            let streamed: VectorFst<TropicalWeight> = read_text_fst(text.as_bytes())?;
+            let parsed = VectorFst::from_parsed_fst_text(ParsedTextFst::from_string(text)?)?;
+            assert_eq!(streamed, parsed);
+            assert_eq!(streamed.properties(), parsed.properties());
+
+            let parsed_const: ConstFst<TropicalWeight> =
+                SerializableFst::read_text_from(text.as_bytes())?;
+            assert_eq!(parsed_const, ConstFst::from(parsed));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_text_to_read_text_from() -> Result<()> {
+        let fst = VectorFst::<TropicalWeight>::from_text_string(FST_TEXT)?;
+        let mut buffer = vec![];
+        fst.write_text_to(&mut buffer)?;
+        assert_eq!(String::from_utf8(buffer.clone())?, fst.text()?);
+        let fst2 = VectorFst::read_text_from(buffer.as_slice())?;
+        assert_eq!(fst, fst2);
+        assert_eq!(fst2.final_weight(5)?, Some(TropicalWeight::one()));
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-mod nom_parser;
+mod line_parser;
 mod parsed_text_fst;
 
+pub(crate) use self::line_parser::read_text_fst;
 pub use self::parsed_text_fst::{FinalState, ParsedTextFst, Transition};
@@ -1,9 +1,10 @@
-use std::fs::read_to_string;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 use anyhow::Result;
 
-use crate::parsers::text_fst::nom_parser::vec_rows_parsed;
+use crate::parsers::text_fst::line_parser::for_each_row_parsed;
 use crate::semirings::SerializableSemiring;
 use crate::{Label, StateId};
 
@@ -73,7 +74,7 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     ///
     /// Lines may occur in any order except initial state must be first line.
     /// Unspecified weights default to 1.0 (for the Semiring).
-    /// All the values are separated by a tabulation (`\t`).
+    /// The values are separated by tabulations (`\t`) or spaces.
     ///
     /// ## Example:
     /// ```text
@@ -85,31 +86,29 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     /// 3   0.67
     /// ```
     pub fn from_string(fst_string: &str) -> Result<Self> {
-        let (_, vec_rows_parsed) =
-            vec_rows_parsed(fst_string).map_err(|_| format_err!("Error while parsing text fst"))?;
-
-        Ok(Self::from_vec_rows_parsed(vec_rows_parsed))
+        Self::from_reader(fst_string.as_bytes())
     }
 
-    fn from_vec_rows_parsed(v: Vec<RowParsed<W>>) -> Self {
-        let mut parsed_fst = Self {
-            start_state: v.first().map(|v| match v {
-                RowParsed::Transition(t) => t.state,
-                RowParsed::FinalState(f) => f.state,
-                RowParsed::InfinityFinalState(g) => *g,
-            }),
-            ..Default::default()
-        };
-
-        for row_parsed in v.into_iter() {
-            match row_parsed {
+    /// Loads an FST in text format from a `BufRead`, parsing it one line at a time.
+    /// A malformed line is reported with its line and column numbers.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut parsed_fst = Self::default();
+        for_each_row_parsed(reader, |row| {
+            if parsed_fst.start_state.is_none() {
+                parsed_fst.start_state = Some(match &row {
+                    RowParsed::Transition(t) => t.state,
+                    RowParsed::FinalState(f) => f.state,
+                    RowParsed::InfinityFinalState(g) => *g,
+                });
+            }
+            match row {
                 RowParsed::Transition(t) => parsed_fst.transitions.push(t),
                 RowParsed::FinalState(f) => parsed_fst.final_states.push(f),
                 RowParsed::InfinityFinalState(_) => {}
             };
-        }
-
-        parsed_fst
+            Ok(())
+        })?;
+        Ok(parsed_fst)
     }
 
     /// Loads an FST from a serialized file in text format usually called `At&T FSM format`.
@@ -123,7 +122,7 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     ///
     /// Lines may occur in any order except initial state must be first line.
     /// Unspecified weights default to 1.0 (for the Semiring).
-    /// All the values are separated by a tabulation (`\t`).
+    /// The values are separated by tabulations (`\t`) or spaces.
     ///
     /// ## Example:
     /// ```text
@@ -135,8 +134,8 @@ impl<W: SerializableSemiring> ParsedTextFst<W> {
     /// 3   0.67
     /// ```
     pub fn from_path<P: AsRef<Path>>(path_fst_text: P) -> Result<Self> {
-        let fst_string = read_to_string(path_fst_text)?;
-        Self::from_string(&fst_string)
+        let file = File::open(path_fst_text)?;
+        Self::from_reader(BufReader::new(file))
     }
 
     pub fn start(&self) -> Option<StateId> {
@@ -259,4 +258,13 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_text_fst_malformed_line() {
+        let err = ParsedTextFst::<TropicalWeight>::from_string("0\t1\t2\t2\n1\tx\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error while parsing text fst at line 2, column 3 : invalid weight \"x\""
+        );
+    }
 }
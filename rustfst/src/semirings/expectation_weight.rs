@@ -0,0 +1,363 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::io::Write;
+
+use anyhow::Result;
+use nom::IResult;
+
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::{
+    DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
+    WeaklyDivisibleSemiring, WeightMinus, WeightQuantize,
+};
+#[cfg(test)]
+use crate::semirings::{LogWeight, PowerWeight, ProbabilityWeight, SignedLogWeight};
+
+/// Weights that can be multiplied by a scalar weight of type `W`. This is what
+/// is needed for the second component of an `ExpectationWeight<W, Self>`.
+///
+/// The multiplication must be commutative: scaling on the left or on the right
+/// gives the same result.
+pub trait ExpectationModule<W: Semiring>: Semiring {
+    fn scale(&self, scalar: &W) -> Result<Self>;
+}
+
+impl<W: Semiring> ExpectationModule<W> for W {
+    fn scale(&self, scalar: &W) -> Result<Self> {
+        scalar.times(self)
+    }
+}
+
+/// Expectation semiring: W * V where V is a module over W.
+///
+/// Plus is component-wise and times is `(a1, a2) * (b1, b2) = (a1 * b1, a1 * b2 + a2 * b1)`.
+/// With the probability of a path in the first component and the probability
+/// times the value of a feature in the second, the shortest distance gives
+/// in one pass both the total probability and the unnormalized expected feature value.
+/// Pushing the weights can make the second component negative : use a `SignedLogWeight` for it.
+///
+/// For more information : `https://www.aclweb.org/anthology/P02-1001.pdf`
+#[derive(Debug, Eq, PartialOrd, PartialEq, Clone, Default, Hash)]
+pub struct ExpectationWeight<W, V>
+where
+    W: Semiring,
+    V: ExpectationModule<W>,
+{
+    pub(crate) weight: (W, V),
+}
+
+impl<W, V> AsRef<Self> for ExpectationWeight<W, V>
+where
+    W: Semiring,
+    V: ExpectationModule<W>,
+{
+    fn as_ref(&self) -> &ExpectationWeight<W, V> {
+        self
+    }
+}
+
+impl<W, V> Semiring for ExpectationWeight<W, V>
+where
+    W: Semiring<ReverseWeight = W>,
+    V: ExpectationModule<W, ReverseWeight = V>,
+{
+    type Type = (W, V);
+    type ReverseWeight = ExpectationWeight<W, V>;
+
+    fn zero() -> Self {
+        Self {
+            weight: (W::zero(), V::zero()),
+        }
+    }
+
+    fn one() -> Self {
+        Self {
+            weight: (W::one(), V::zero()),
+        }
+    }
+
+    fn new(weight: <Self as Semiring>::Type) -> Self {
+        Self { weight }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        self.weight.0.plus_assign(&rhs.borrow().weight.0)?;
+        self.weight.1.plus_assign(&rhs.borrow().weight.1)?;
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let (w1, v1) = &self.weight;
+        let (w2, v2) = &rhs.borrow().weight;
+        let v = v2.scale(w1)?.plus(v1.scale(w2)?)?;
+        let w = w1.times(w2)?;
+        self.weight = (w, v);
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        self.value1().approx_equal(rhs.borrow().value1(), delta)
+            && self.value2().approx_equal(rhs.borrow().value2(), delta)
+    }
+
+    fn value(&self) -> &<Self as Semiring>::Type {
+        &self.weight
+    }
+
+    fn take_value(self) -> <Self as Semiring>::Type {
+        self.weight
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.set_value1(value.0);
+        self.set_value2(value.1);
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(self.clone())
+    }
+
+    fn properties() -> SemiringProperties {
+        W::properties()
+            & V::properties()
+            & (SemiringProperties::LEFT_SEMIRING
+                | SemiringProperties::RIGHT_SEMIRING
+                | SemiringProperties::COMMUTATIVE)
+    }
+}
+
+impl<W, V> ReverseBack<ExpectationWeight<W, V>> for ExpectationWeight<W, V>
+where
+    W: Semiring,
+    V: ExpectationModule<W>,
+{
+    fn reverse_back(&self) -> Result<ExpectationWeight<W, V>> {
+        Ok(self.clone())
+    }
+}
+
+impl<W, V> ExpectationWeight<W, V>
+where
+    W: Semiring,
+    V: ExpectationModule<W>,
+{
+    pub fn value1(&self) -> &W {
+        &self.weight.0
+    }
+
+    pub fn value2(&self) -> &V {
+        &self.weight.1
+    }
+
+    pub fn set_value1(&mut self, new_weight: W) {
+        self.weight.0 = new_weight;
+    }
+
+    pub fn set_value2(&mut self, new_weight: V) {
+        self.weight.1 = new_weight;
+    }
+}
+
+impl<W, V> From<(W, V)> for ExpectationWeight<W, V>
+where
+    W: Semiring,
+    V: ExpectationModule<W>,
+{
+    fn from(t: (W, V)) -> Self {
+        Self { weight: t }
+    }
+}
+
+impl<W, V> WeaklyDivisibleSemiring for ExpectationWeight<W, V>
+where
+    W: WeaklyDivisibleSemiring<ReverseWeight = W>,
+    V: ExpectationModule<W, ReverseWeight = V> + WeightMinus,
+{
+    /// `(a1, a2) / (b1, b2) = (a1 / b1, (a2 - b2 * a1 / b1) / b1)`.
+    ///
+    /// The second component may be negative, so the division fails if `V`
+    /// can't represent the result.
+    fn divide_assign(&mut self, rhs: &Self, divide_type: DivideType) -> Result<()> {
+        let (a1, a2) = &self.weight;
+        let (b1, b2) = &rhs.weight;
+        let w = a1.divide(b1, divide_type)?;
+        let inv_b1 = W::one().divide(b1, divide_type)?;
+        let v = a2.minus(&b2.scale(&w)?)?.scale(&inv_b1)?;
+        self.weight = (w, v);
+        Ok(())
+    }
+}
+
+impl<W, V> WeightQuantize for ExpectationWeight<W, V>
+where
+    W: WeightQuantize<ReverseWeight = W>,
+    V: ExpectationModule<W, ReverseWeight = V> + WeightQuantize,
+{
+    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+        self.set_value1(self.value1().quantize(delta)?);
+        self.set_value2(self.value2().quantize(delta)?);
+        Ok(())
+    }
+}
+
+impl<W, V> fmt::Display for ExpectationWeight<W, V>
+where
+    W: SerializableSemiring,
+    V: ExpectationModule<W> + SerializableSemiring,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.value1(), self.value2())?;
+        Ok(())
+    }
+}
+
+impl<W, V> SerializableSemiring for ExpectationWeight<W, V>
+where
+    W: SerializableSemiring<ReverseWeight = W>,
+    V: ExpectationModule<W, ReverseWeight = V> + SerializableSemiring,
+{
+    fn weight_type() -> String {
+        format!("expectation_{}_{}", W::weight_type(), V::weight_type())
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, weight_1) = W::parse_binary(i)?;
+        let (i, weight_2) = V::parse_binary(i)?;
+        Ok((i, Self::new((weight_1, weight_2))))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        self.value1().write_binary(file)?;
+        self.value2().write_binary(file)?;
+        Ok(())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (i, weight_1) = W::parse_text(i)?;
+        let (i, _) = nom::bytes::complete::tag(",")(i)?;
+        let (i, weight_2) = V::parse_text(i)?;
+        Ok((i, Self::new((weight_1, weight_2))))
+    }
+}
+
+test_semiring_serializable!(
+    tests_expectation_weight_serializable,
+    ExpectationWeight::<LogWeight, LogWeight>,
+    ExpectationWeight::new((LogWeight::new(0.2), LogWeight::new(1.7)))
+    ExpectationWeight::<LogWeight, LogWeight>::one()
+    ExpectationWeight::<LogWeight, LogWeight>::zero()
+);
+
+test_semiring_serializable!(
+    tests_expectation_weight_power_serializable,
+    ExpectationWeight::<LogWeight, PowerWeight<LogWeight, 2>>,
+    ExpectationWeight::new((
+        LogWeight::new(0.2),
+        PowerWeight::new([LogWeight::new(1.7), LogWeight::new(3.0)])
+    ))
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{
+        push_weights, shortest_distance_with_config, ReweightType, ShortestDistanceConfig,
+    };
+    use crate::fst_impls::VectorFst;
+    use crate::fst_traits::{CoreFst, MutableFst};
+    use crate::Tr;
+
+    type ExpectedWeight = ExpectationWeight<ProbabilityWeight, ProbabilityWeight>;
+
+    fn expected(p: f32, feature: f32) -> ExpectedWeight {
+        ExpectationWeight::new((
+            ProbabilityWeight::new(p),
+            ProbabilityWeight::new(p * feature),
+        ))
+    }
+
+    /// Same as `expected` in the log semiring, with a second component that can be negative.
+    fn signed_expected(p: f32, feature: f32) -> ExpectationWeight<LogWeight, SignedLogWeight> {
+        ExpectationWeight::new((
+            LogWeight::new(-p.ln()),
+            SignedLogWeight::from_sign_and_value(true, -(p * feature).ln()),
+        ))
+    }
+
+    fn build_lattice() -> Result<VectorFst<ExpectedWeight>> {
+        build_lattice_with(expected)
+    }
+
+    /// Two paths: `1 2` with probability 0.3 and a feature value of 1 + 2,
+    /// `1 3` with probability 0.7 and a feature value of 1 + 4.
+    fn build_lattice_with<W: Semiring>(expected: fn(f32, f32) -> W) -> Result<VectorFst<W>> {
+        let mut fst = VectorFst::new();
+        fst.add_states(3);
+        fst.set_start(0)?;
+        fst.add_tr(0, Tr::new(1, 1, expected(1.0, 1.0), 1))?;
+        fst.add_tr(1, Tr::new(2, 2, expected(0.3, 2.0), 2))?;
+        fst.add_tr(1, Tr::new(3, 3, expected(0.7, 4.0), 2))?;
+        fst.set_final(2, W::one())?;
+        Ok(fst)
+    }
+
+    fn total_weight<W: Semiring>(fst: &VectorFst<W>) -> Result<W> {
+        let distance = shortest_distance_with_config(fst, true, ShortestDistanceConfig::default())?;
+        Ok(distance[fst.start().unwrap() as usize].clone())
+    }
+
+    #[test]
+    fn test_expectation_weight_operations() -> Result<()> {
+        let w1 = expected(0.5, 2.0);
+        let w2 = expected(0.2, 3.0);
+        assert!(w1.plus(&w2)?.approx_equal(
+            ExpectationWeight::new((ProbabilityWeight::new(0.7), ProbabilityWeight::new(1.6))),
+            1e-6
+        ));
+        // Features add up along a path.
+        assert!(w1.times(&w2)?.approx_equal(expected(0.1, 5.0), 1e-6));
+        assert_eq!(w1.times(ExpectedWeight::one())?, w1);
+        assert_eq!(w1.plus(ExpectedWeight::zero())?, w1);
+        assert!(w1
+            .times(&w2)?
+            .divide(&w2, DivideType::DivideAny)?
+            .approx_equal(&w1, 1e-6));
+        assert_eq!(
+            ExpectationWeight::<LogWeight, LogWeight>::weight_type(),
+            "expectation_log_log"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_expectation_weight_shortest_distance() -> Result<()> {
+        let fst = build_lattice()?;
+        // 0.3 * 3 + 0.7 * 5
+        assert!(total_weight(&fst)?.approx_equal(
+            ExpectationWeight::new((ProbabilityWeight::new(1.0), ProbabilityWeight::new(4.4))),
+            1e-5
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_expectation_weight_push_weights() -> Result<()> {
+        let mut fst = build_lattice_with(signed_expected)?;
+        let total = total_weight(&fst)?;
+        push_weights(&mut fst, ReweightType::ReweightToInitial)?;
+        assert!(total_weight(&fst)?.approx_equal(&total, 1e-5));
+
+        // The pushed expectations are negative, which a `ProbabilityWeight` can't represent.
+        let mut fst = build_lattice()?;
+        assert!(push_weights(&mut fst, ReweightType::ReweightToInitial).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_expectation_weight_log_divide_negative() {
+        let w1 = ExpectationWeight::new((LogWeight::new(0.0), LogWeight::new(3.0)));
+        let w2 = ExpectationWeight::new((LogWeight::new(0.0), LogWeight::new(1.0)));
+        // The expectation of w1 / w2 would be negative.
+        assert!(w1.divide(&w2, DivideType::DivideAny).is_err());
+    }
+}
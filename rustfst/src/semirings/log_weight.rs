@@ -16,18 +16,19 @@ use crate::parsers::parse_bin_f32;
 use crate::parsers::write_bin_f32;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
-    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, DivideType, MappableSemiring, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightMinus, WeightQuantize,
 };
 use crate::KDELTA;
 
 /// Log semiring: (log(e^-x + e^-y), +, inf, 0).
 #[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+#[repr(transparent)]
 pub struct LogWeight {
     value: OrderedFloat<f32>,
 }
 
-fn ln_pos_exp(x: f32) -> f32 {
+pub(crate) fn ln_pos_exp(x: f32) -> f32 {
     ((-x).exp()).ln_1p()
 }
 
@@ -139,6 +140,27 @@ impl WeaklyDivisibleSemiring for LogWeight {
     }
 }
 
+impl WeightMinus for LogWeight {
+    /// Fails if `rhs` is larger than `self` in the probability domain.
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()> {
+        let f1 = self.value.0;
+        let f2 = rhs.value.0;
+        if f2.eq(&f32::INFINITY) {
+            return Ok(());
+        }
+        if f1 > f2 {
+            bail!(
+                "LogWeight : can't subtract {} from {}, the result would be negative",
+                f2,
+                f1
+            );
+        }
+        // -log(e^-f1 - e^-f2) = f1 - log(1 - e^-(f2 - f1))
+        self.value.0 = f1 - (-(f1 - f2).exp_m1()).ln();
+        Ok(())
+    }
+}
+
 impl_quantize_f32!(LogWeight);
 
 partial_eq_and_hash_f32!(LogWeight);
@@ -165,6 +187,9 @@ impl SerializableSemiring for LogWeight {
     }
 }
 
+// Safety: the weight is a transparent f32 serialized as its little-endian bytes.
+unsafe impl MappableSemiring for LogWeight {}
+
 test_semiring_serializable!(
     tests_log_weight_serializable,
     LogWeight,
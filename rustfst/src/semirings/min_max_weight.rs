@@ -0,0 +1,196 @@
+use std::borrow::Borrow;
+use std::f32;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use anyhow::Result;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
+use nom::number::complete::float;
+use nom::IResult;
+use ordered_float::OrderedFloat;
+
+use crate::parsers::nom_utils::NomCustomError;
+use crate::parsers::parse_bin_f32;
+use crate::parsers::write_bin_f32;
+use crate::semirings::semiring::SerializableSemiring;
+use crate::semirings::utils_float::float_approx_equal;
+use crate::semirings::{
+    CompleteSemiring, DivideType, MappableSemiring, ReverseBack, Semiring, SemiringProperties,
+    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+};
+use crate::KDELTA;
+
+/// Min-max (or bottleneck) semiring: (min, max, inf, -inf).
+#[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+#[repr(transparent)]
+pub struct MinMaxWeight {
+    value: OrderedFloat<f32>,
+}
+
+impl Semiring for MinMaxWeight {
+    type Type = f32;
+    type ReverseWeight = MinMaxWeight;
+
+    fn zero() -> Self {
+        Self {
+            value: OrderedFloat(f32::INFINITY),
+        }
+    }
+
+    fn one() -> Self {
+        Self {
+            value: OrderedFloat(f32::NEG_INFINITY),
+        }
+    }
+
+    fn new(value: <Self as Semiring>::Type) -> Self {
+        MinMaxWeight {
+            value: OrderedFloat(value),
+        }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        if rhs.borrow().value < self.value {
+            self.value = rhs.borrow().value;
+        }
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        if rhs.borrow().value > self.value {
+            self.value = rhs.borrow().value;
+        }
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        float_approx_equal(self.value.0, rhs.borrow().value.0, delta)
+    }
+
+    fn value(&self) -> &Self::Type {
+        &self.value.0
+    }
+
+    fn take_value(self) -> Self::Type {
+        self.value.0
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.value.0 = value
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(*self)
+    }
+
+    fn properties() -> SemiringProperties {
+        SemiringProperties::LEFT_SEMIRING
+            | SemiringProperties::RIGHT_SEMIRING
+            | SemiringProperties::COMMUTATIVE
+            | SemiringProperties::PATH
+            | SemiringProperties::IDEMPOTENT
+    }
+}
+
+impl ReverseBack<MinMaxWeight> for MinMaxWeight {
+    fn reverse_back(&self) -> Result<MinMaxWeight> {
+        Ok(*self)
+    }
+}
+
+impl AsRef<MinMaxWeight> for MinMaxWeight {
+    fn as_ref(&self) -> &MinMaxWeight {
+        self
+    }
+}
+
+display_semiring!(MinMaxWeight);
+
+impl CompleteSemiring for MinMaxWeight {}
+
+impl StarSemiring for MinMaxWeight {
+    fn closure(&self) -> Self {
+        Self::one()
+    }
+}
+
+impl WeaklyDivisibleSemiring for MinMaxWeight {
+    /// The division is only defined when `self >= rhs`, the result is then `self`.
+    fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
+        if self.value < rhs.value {
+            bail!(
+                "MinMaxWeight : can't divide {} by {}, the dividend must be at least the divisor",
+                self.value,
+                rhs.value
+            );
+        }
+        Ok(())
+    }
+}
+
+impl_quantize_f32!(MinMaxWeight);
+
+partial_eq_and_hash_f32!(MinMaxWeight);
+
+impl SerializableSemiring for MinMaxWeight {
+    fn weight_type() -> String {
+        "minmax".to_string()
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, weight) = parse_bin_f32(i)?;
+        Ok((i, Self::new(weight)))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        write_bin_f32(file, *self.value())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        // Both infinities, the zero and the one of the semiring, are matched before `float`.
+        let (i, f) = alt((
+            map(tag_no_case("infinity"), |_| f32::INFINITY),
+            map(tag_no_case("-infinity"), |_| f32::NEG_INFINITY),
+            map(tag_no_case("-inf"), |_| f32::NEG_INFINITY),
+            float,
+        ))(i)?;
+        Ok((i, Self::new(f)))
+    }
+}
+
+// Safety: the weight is a transparent f32 serialized as its little-endian bytes.
+unsafe impl MappableSemiring for MinMaxWeight {}
+
+test_semiring_serializable!(
+    tests_min_max_weight_serializable,
+    MinMaxWeight,
+    MinMaxWeight::one() MinMaxWeight::zero() MinMaxWeight::new(0.3) MinMaxWeight::new(0.5) MinMaxWeight::new(0.0) MinMaxWeight::new(-1.2)
+);
+
+impl From<f32> for MinMaxWeight {
+    fn from(f: f32) -> Self {
+        Self::new(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_weight_operations() -> Result<()> {
+        let w1 = MinMaxWeight::new(1.0);
+        let w2 = MinMaxWeight::new(3.0);
+        assert_eq!(w1.plus(w2)?, w1);
+        assert_eq!(w1.times(w2)?, w2);
+        assert_eq!(w1.times(MinMaxWeight::one())?, w1);
+        assert_eq!(w1.plus(MinMaxWeight::zero())?, w1);
+        assert_eq!(w1.times(MinMaxWeight::zero())?, MinMaxWeight::zero());
+        assert_eq!(w2.divide(&w1, DivideType::DivideAny)?, w2);
+        assert!(w1.divide(&w2, DivideType::DivideAny).is_err());
+        assert_eq!(w2.closure(), MinMaxWeight::one());
+        Ok(())
+    }
+}
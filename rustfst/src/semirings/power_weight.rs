@@ -1,63 +1,59 @@
-#![allow(unused)]
-
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt;
-use std::fmt::Debug;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::io::Write;
 
-use anyhow::Result;
+use std::convert::TryInto;
 
-use generic_array::ArrayLength;
-use generic_array::GenericArray;
+use anyhow::Result;
+use nom::bytes::complete::tag;
+use nom::IResult;
 
+use crate::parsers::nom_utils::NomCustomError;
 use crate::semirings::{
-    DivideType, Semiring, SemiringProperties, WeaklyDivisibleSemiring, WeightQuantize,
+    DivideType, ExpectationModule, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
+    WeaklyDivisibleSemiring, WeightMinus, WeightQuantize,
 };
+#[cfg(test)]
+use crate::semirings::{LogWeight, TropicalWeight};
 
-/// Cartesian power semiring: W ^ n.
-pub struct PowerWeight<W, N>
-where
-    W: Semiring,
-    N: ArrayLength<W>,
-{
-    weights: GenericArray<W, N>,
+fn array_from_vec<W: Semiring, const N: usize>(weights: Vec<W>) -> [W; N] {
+    weights
+        .try_into()
+        .unwrap_or_else(|v: Vec<W>| panic!("Expected {} weights, got {}", N, v.len()))
 }
 
-impl<W, N> fmt::Display for PowerWeight<W, N>
+/// Cartesian power semiring: W ^ n.
+pub struct PowerWeight<W, const N: usize>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.weights.as_slice().fmt(f)
-    }
+    weights: [W; N],
 }
 
-impl<W, N> fmt::Debug for PowerWeight<W, N>
+impl<W, const N: usize> fmt::Debug for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.weights.as_slice().fmt(f)
+        self.weights[..].fmt(f)
     }
 }
 
-impl<W, N> Hash for PowerWeight<W, N>
+impl<W, const N: usize> Hash for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.weights.as_slice().hash(state);
+        self.weights[..].hash(state);
     }
 }
 
-impl<W, N> Clone for PowerWeight<W, N>
+impl<W, const N: usize> Clone for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn clone(&self) -> Self {
         PowerWeight {
@@ -66,129 +62,303 @@ where
     }
 }
 
-impl<W, N> PartialOrd for PowerWeight<W, N>
+impl<W, const N: usize> PartialOrd for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.weights.partial_cmp(&other.weights)
+        self.weights[..].partial_cmp(&other.weights[..])
     }
 }
 
-impl<W, N> PartialEq for PowerWeight<W, N>
+impl<W, const N: usize> PartialEq for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.weights.eq(&other.weights)
+        self.weights[..].eq(&other.weights[..])
     }
 }
 
-impl<W, N> AsRef<Self> for PowerWeight<W, N>
+impl<W, const N: usize> AsRef<Self> for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
 {
     fn as_ref(&self) -> &PowerWeight<W, N> {
         self
     }
 }
 
-impl<W, N> Eq for PowerWeight<W, N>
+impl<W, const N: usize> Eq for PowerWeight<W, N> where W: Semiring {}
+
+impl<W, const N: usize> Semiring for PowerWeight<W, N>
 where
     W: Semiring,
-    N: ArrayLength<W>,
-{
-}
-
-//impl<W, N> Semiring for PowerWeight<W, N>
-//where
-//    W: Semiring,
-//    N: ArrayLength<W>,
-//{
-//    type Type = GenericArray<W, N>;
-//    type ReverseSemiring<P> = PowerWeight<W::ReverseSemiring, P>;
-//
-//    fn zero() -> Self {
-//        Self {
-//            weights: GenericArray::clone_from_slice(&[W::zero()]),
-//        }
-//    }
-//
-//    fn one() -> Self {
-//        Self {
-//            weights: GenericArray::clone_from_slice(&[W::one()]),
-//        }
-//    }
-//
-//    fn new(value: <Self as Semiring>::Type) -> Self {
-//        Self { weights: value }
-//    }
-//
-//    fn plus_assign<P: AsRef<Self>>(&mut self, rhs: P) -> Result<()> {
-//        for i in 0..self.weights.len() {
-//            self.weights[i].plus_assign(&rhs.as_ref().weights[i])?;
-//        }
-//        Ok(())
-//    }
-//
-//    fn times_assign<P: AsRef<Self>>(&mut self, rhs: P) -> Result<()> {
-//        for i in 0..self.weights.len() {
-//            self.weights[i].times_assign(&rhs.as_ref().weights[i])?;
-//        }
-//        Ok(())
-//    }
-//
-//    fn value(&self) -> <Self as Semiring>::Type {
-//        self.weights.clone()
-//    }
-//
-//    fn set_value(&mut self, value: <Self as Semiring>::Type) {
-//        self.weights = value;
-//    }
-//
-//    fn reverse(&self) -> Self::ReverseSemiring {
-//        let mut rw = Vec::with_capacity(self.weights.len());
-//        for i in 0..self.weights.len() {
-//            rw.push(self.weights[i].reverse());
-//        }
-//        PowerWeight::new(GenericArray::clone_from(rw))
-//    }
-//
-//    fn properties() -> SemiringProperties {
-//        W::properties()
-//            & (SemiringProperties::LEFT_SEMIRING
-//                | SemiringProperties::RIGHT_SEMIRING
-//                | SemiringProperties::COMMUTATIVE
-//                | SemiringProperties::IDEMPOTENT)
-//    }
-//}
-//
-//impl<W, N> WeaklyDivisibleSemiring for PowerWeight<W, N>
-//where
-//    W: WeaklyDivisibleSemiring,
-//    N: ArrayLength<W>,
-//{
-//    fn divide(&self, rhs: &Self, divide_type: DivideType) -> Result<Self> {
-//        let mut mul = self.clone();
-//        for i in 0..self.weights.len() {
-//            mul.weights[i] = self.weights[i].divide(&rhs.weights[i], divide_type)?;
-//        }
-//        Ok(mul)
-//    }
-//}
-//
-//impl<W, N> WeightQuantize for PowerWeight<W, N>
-//where
-//    W: WeightQuantize,
-//    N: ArrayLength<W>,
-//{
-//    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
-//        for i in 0..self.weights.len() {
-//            unsafe { self.weights.get_unchecked_mut(i).quantize_assign(delta)? };
-//        }
-//        Ok(())
-//    }
-//}
+{
+    type Type = [W; N];
+    type ReverseWeight = PowerWeight<W::ReverseWeight, N>;
+
+    fn zero() -> Self {
+        Self {
+            weights: array_from_vec(vec![W::zero(); N]),
+        }
+    }
+
+    fn one() -> Self {
+        Self {
+            weights: array_from_vec(vec![W::one(); N]),
+        }
+    }
+
+    fn new(weights: <Self as Semiring>::Type) -> Self {
+        Self { weights }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        for (w, w_rhs) in self.weights.iter_mut().zip(rhs.borrow().weights.iter()) {
+            w.plus_assign(w_rhs)?;
+        }
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        for (w, w_rhs) in self.weights.iter_mut().zip(rhs.borrow().weights.iter()) {
+            w.times_assign(w_rhs)?;
+        }
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        self.weights
+            .iter()
+            .zip(rhs.borrow().weights.iter())
+            .all(|(w, w_rhs)| w.approx_equal(w_rhs, delta))
+    }
+
+    fn value(&self) -> &<Self as Semiring>::Type {
+        &self.weights
+    }
+
+    fn take_value(self) -> <Self as Semiring>::Type {
+        self.weights
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.weights = value;
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        let weights = self
+            .weights
+            .iter()
+            .map(|w| w.reverse())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PowerWeight::new(array_from_vec(weights)))
+    }
+
+    fn properties() -> SemiringProperties {
+        W::properties()
+            & (SemiringProperties::LEFT_SEMIRING
+                | SemiringProperties::RIGHT_SEMIRING
+                | SemiringProperties::COMMUTATIVE
+                | SemiringProperties::IDEMPOTENT)
+    }
+}
+
+impl<W, const N: usize> ReverseBack<PowerWeight<W, N>>
+    for <PowerWeight<W, N> as Semiring>::ReverseWeight
+where
+    W: Semiring,
+{
+    fn reverse_back(&self) -> Result<PowerWeight<W, N>> {
+        let weights = self
+            .weights
+            .iter()
+            .map(|w| w.reverse_back())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PowerWeight::new(array_from_vec(weights)))
+    }
+}
+
+impl<W, const N: usize> PowerWeight<W, N>
+where
+    W: Semiring,
+{
+    /// Weight of the `i`-th component.
+    pub fn value_at(&self, i: usize) -> &W {
+        &self.weights[i]
+    }
+
+    /// Set the weight of the `i`-th component.
+    pub fn set_value_at(&mut self, i: usize, weight: W) {
+        self.weights[i] = weight;
+    }
+}
+
+impl<W, const N: usize> WeaklyDivisibleSemiring for PowerWeight<W, N>
+where
+    W: WeaklyDivisibleSemiring,
+{
+    fn divide_assign(&mut self, rhs: &Self, divide_type: DivideType) -> Result<()> {
+        for (w, w_rhs) in self.weights.iter_mut().zip(rhs.weights.iter()) {
+            w.divide_assign(w_rhs, divide_type)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W, const N: usize> WeightMinus for PowerWeight<W, N>
+where
+    W: WeightMinus,
+{
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()> {
+        for (w, w_rhs) in self.weights.iter_mut().zip(rhs.weights.iter()) {
+            w.minus_assign(w_rhs)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W, const N: usize> ExpectationModule<W> for PowerWeight<W, N>
+where
+    W: Semiring,
+{
+    fn scale(&self, scalar: &W) -> Result<Self> {
+        let mut res = self.clone();
+        for w in res.weights.iter_mut() {
+            *w = scalar.times(&*w)?;
+        }
+        Ok(res)
+    }
+}
+
+impl<W, const N: usize> WeightQuantize for PowerWeight<W, N>
+where
+    W: WeightQuantize,
+{
+    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+        for w in self.weights.iter_mut() {
+            w.quantize_assign(delta)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W, const N: usize> fmt::Display for PowerWeight<W, N>
+where
+    W: SerializableSemiring,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, w) in self.weights.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W, const N: usize> SerializableSemiring for PowerWeight<W, N>
+where
+    W: SerializableSemiring,
+{
+    fn weight_type() -> String {
+        format!("{}_^{}", W::weight_type(), N)
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let mut i = i;
+        let mut weights = Vec::with_capacity(N);
+        for _ in 0..N {
+            let (next, w) = W::parse_binary(i)?;
+            weights.push(w);
+            i = next;
+        }
+        Ok((i, Self::new(array_from_vec(weights))))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        for w in self.weights.iter() {
+            w.write_binary(file)?;
+        }
+        Ok(())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let mut i = i;
+        let mut weights = Vec::with_capacity(N);
+        for idx in 0..N {
+            if idx > 0 {
+                let (next, _) = tag(",")(i)?;
+                i = next;
+            }
+            let (next, w) = W::parse_text(i)?;
+            weights.push(w);
+            i = next;
+        }
+        Ok((i, Self::new(array_from_vec(weights))))
+    }
+}
+
+test_semiring_serializable!(
+    tests_power_weight_serializable,
+    PowerWeight::<TropicalWeight, 3>,
+    PowerWeight::new([TropicalWeight::new(0.2), TropicalWeight::new(1.7), TropicalWeight::new(3.0)])
+    PowerWeight::<TropicalWeight, 3>::one()
+    PowerWeight::<TropicalWeight, 3>::zero()
+);
+
+test_semiring_serializable!(
+    tests_power_weight_log_serializable,
+    PowerWeight::<LogWeight, 3>,
+    PowerWeight::new([
+        LogWeight::new(0.2),
+        LogWeight::new(1.7),
+        LogWeight::new(3.0)
+    ])
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_weight_operations() -> Result<()> {
+        let w1 = PowerWeight::<TropicalWeight, 3>::new([
+            TropicalWeight::new(1.0),
+            TropicalWeight::new(5.0),
+            TropicalWeight::new(2.0),
+        ]);
+        let w2 = PowerWeight::<TropicalWeight, 3>::new([
+            TropicalWeight::new(3.0),
+            TropicalWeight::new(4.0),
+            TropicalWeight::new(2.0),
+        ]);
+        assert_eq!(
+            w1.plus(&w2)?.value().as_slice(),
+            &[
+                TropicalWeight::new(1.0),
+                TropicalWeight::new(4.0),
+                TropicalWeight::new(2.0)
+            ]
+        );
+        assert_eq!(
+            w1.times(&w2)?.value().as_slice(),
+            &[
+                TropicalWeight::new(4.0),
+                TropicalWeight::new(9.0),
+                TropicalWeight::new(4.0)
+            ]
+        );
+        assert_eq!(w1.times(PowerWeight::one())?, w1);
+        assert_eq!(w1.plus(PowerWeight::zero())?, w1);
+        assert_eq!(w1.times(&w2)?.divide(&w2, DivideType::DivideAny)?, w1);
+        assert_eq!(
+            PowerWeight::<TropicalWeight, 3>::weight_type(),
+            "tropical_^3"
+        );
+        assert_eq!(format!("{}", w1), "1,5,2");
+        Ok(())
+    }
+}
@@ -13,13 +13,14 @@ use crate::parsers::parse_bin_f32;
 use crate::parsers::write_bin_f32;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
-    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, DivideType, MappableSemiring, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, WeaklyDivisibleSemiring, WeightMinus, WeightQuantize,
 };
 use crate::KDELTA;
 
 /// Probability semiring: (x, +, 0.0, 1.0).
 #[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+#[repr(transparent)]
 pub struct ProbabilityWeight {
     value: OrderedFloat<f32>,
 }
@@ -84,7 +85,7 @@ impl Semiring for ProbabilityWeight {
 
 impl ReverseBack<ProbabilityWeight> for ProbabilityWeight {
     fn reverse_back(&self) -> Result<ProbabilityWeight> {
-        unimplemented!()
+        Ok(*self)
     }
 }
 
@@ -118,6 +119,9 @@ impl SerializableSemiring for ProbabilityWeight {
     }
 }
 
+// Safety: the weight is a transparent f32 serialized as its little-endian bytes.
+unsafe impl MappableSemiring for ProbabilityWeight {}
+
 impl StarSemiring for ProbabilityWeight {
     fn closure(&self) -> Self {
         Self::new(1.0 / (1.0 - self.value.0))
@@ -135,6 +139,21 @@ impl WeaklyDivisibleSemiring for ProbabilityWeight {
     }
 }
 
+impl WeightMinus for ProbabilityWeight {
+    /// Fails if `rhs` is larger than `self`.
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()> {
+        if self.value.0 < rhs.value.0 {
+            bail!(
+                "ProbabilityWeight : can't subtract {} from {}, the result would be negative",
+                rhs.value.0,
+                self.value.0
+            );
+        }
+        self.value.0 -= rhs.value.0;
+        Ok(())
+    }
+}
+
 impl_quantize_f32!(ProbabilityWeight);
 
 partial_eq_and_hash_f32!(ProbabilityWeight);
@@ -150,3 +169,18 @@ impl From<f32> for ProbabilityWeight {
         Self::new(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probability_weight_minus() -> Result<()> {
+        let w1 = ProbabilityWeight::new(0.5);
+        let w2 = ProbabilityWeight::new(0.2);
+        assert!((*w1.minus(&w2)?.value() - 0.3).abs() < 1e-6);
+        assert!(w1.minus(&w1)?.is_zero());
+        assert!(w2.minus(&w1).is_err());
+        Ok(())
+    }
+}
@@ -129,6 +129,18 @@ pub trait WeightQuantize: Semiring {
     }
 }
 
+/// Semirings in which `a - b` can be computed. The subtraction fails when
+/// the result can't be represented in the semiring, e.g a negative probability
+/// in the log semiring.
+pub trait WeightMinus: Semiring {
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()>;
+    fn minus(&self, rhs: &Self) -> Result<Self> {
+        let mut w = self.clone();
+        w.minus_assign(rhs)?;
+        Ok(w)
+    }
+}
+
 macro_rules! impl_quantize_f32 {
     ($semiring: ident) => {
         impl WeightQuantize for $semiring {
@@ -187,3 +199,13 @@ pub trait SerializableSemiring: Semiring + Display {
         Ok(())
     }
 }
+
+/// Semirings whose binary serialization is exactly their in-memory representation. Their values
+/// can be read in place from a buffer (e.g. a memory-mapped file) without being parsed.
+///
+/// # Safety
+///
+/// The type must be `#[repr(transparent)]` or `#[repr(C)]`, `write_binary` must write the
+/// little-endian bytes of its in-memory representation and every bit pattern of that size must
+/// be a valid value.
+pub unsafe trait MappableSemiring: SerializableSemiring + Copy + Send {}
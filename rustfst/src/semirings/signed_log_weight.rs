@@ -0,0 +1,334 @@
+use std::borrow::Borrow;
+use std::f32;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+
+use anyhow::Result;
+use nom::bytes::complete::tag;
+use nom::IResult;
+
+use crate::parsers::nom_utils::NomCustomError;
+use crate::semirings::log_weight::ln_pos_exp;
+use crate::semirings::{
+    DivideType, ExpectationModule, LogWeight, ReverseBack, Semiring, SemiringProperties,
+    SerializableSemiring, StarSemiring, TropicalWeight, WeaklyDivisibleSemiring, WeightMinus,
+    WeightQuantize,
+};
+
+/// Signed log semiring: (log(e^-x + e^-y), +, inf, 0) extended with a sign,
+/// which allows subtraction in the log domain.
+///
+/// As in OpenFst, the weight is a pair made of the sign, stored as a
+/// `TropicalWeight` equal to `1.0` or `-1.0`, and of the absolute value in the log domain.
+#[derive(Clone, Debug, PartialOrd, Copy)]
+pub struct SignedLogWeight {
+    weight: (TropicalWeight, LogWeight),
+}
+
+fn ln_neg_exp(x: f32) -> f32 {
+    (-(-x).exp()).ln_1p()
+}
+
+fn sign(positive: bool) -> TropicalWeight {
+    TropicalWeight::new(if positive { 1.0 } else { -1.0 })
+}
+
+impl SignedLogWeight {
+    /// Weight equal to `e^-value` if `positive`, `-e^-value` otherwise.
+    pub fn from_sign_and_value(positive: bool, value: f32) -> Self {
+        Self {
+            weight: (sign(positive), LogWeight::new(value)),
+        }
+    }
+
+    pub fn is_positive(&self) -> bool {
+        *self.weight.0.value() > 0.0
+    }
+
+    pub fn value1(&self) -> &TropicalWeight {
+        &self.weight.0
+    }
+
+    pub fn value2(&self) -> &LogWeight {
+        &self.weight.1
+    }
+
+    pub fn set_value1(&mut self, new_weight: TropicalWeight) {
+        self.weight.0 = new_weight;
+    }
+
+    pub fn set_value2(&mut self, new_weight: LogWeight) {
+        self.weight.1 = new_weight;
+    }
+
+    fn log_value(&self) -> f32 {
+        *self.weight.1.value()
+    }
+
+    fn negate(&self) -> Self {
+        Self::from_sign_and_value(!self.is_positive(), self.log_value())
+    }
+}
+
+impl Semiring for SignedLogWeight {
+    type Type = (TropicalWeight, LogWeight);
+    type ReverseWeight = SignedLogWeight;
+
+    fn zero() -> Self {
+        Self::from_sign_and_value(true, f32::INFINITY)
+    }
+
+    fn one() -> Self {
+        Self::from_sign_and_value(true, 0.0)
+    }
+
+    fn new(weight: <Self as Semiring>::Type) -> Self {
+        Self { weight }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let rhs = rhs.borrow();
+        let s1 = self.is_positive();
+        let s2 = rhs.is_positive();
+        let f1 = self.log_value();
+        let f2 = rhs.log_value();
+        *self = if f1.eq(&f32::INFINITY) {
+            *rhs
+        } else if f2.eq(&f32::INFINITY) {
+            *self
+        } else if f1 == f2 {
+            if s1 == s2 {
+                Self::from_sign_and_value(s1, f2 - f32::consts::LN_2)
+            } else {
+                Self::zero()
+            }
+        } else if f1 > f2 {
+            if s1 == s2 {
+                Self::from_sign_and_value(s1, f2 - ln_pos_exp(f1 - f2))
+            } else {
+                Self::from_sign_and_value(s2, f2 - ln_neg_exp(f1 - f2))
+            }
+        } else if s1 == s2 {
+            Self::from_sign_and_value(s1, f1 - ln_pos_exp(f2 - f1))
+        } else {
+            Self::from_sign_and_value(s1, f1 - ln_neg_exp(f2 - f1))
+        };
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        let rhs = rhs.borrow();
+        let positive = self.is_positive() == rhs.is_positive();
+        self.weight.0 = sign(positive);
+        self.weight.1.times_assign(rhs.weight.1)?;
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        let rhs = rhs.borrow();
+        if self.is_zero() && rhs.is_zero() {
+            return true;
+        }
+        self.is_positive() == rhs.is_positive() && self.weight.1.approx_equal(rhs.weight.1, delta)
+    }
+
+    fn value(&self) -> &Self::Type {
+        &self.weight
+    }
+
+    fn take_value(self) -> Self::Type {
+        self.weight
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        self.weight = value;
+    }
+
+    fn is_zero(&self) -> bool {
+        self.log_value().eq(&f32::INFINITY)
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        Ok(*self)
+    }
+
+    fn properties() -> SemiringProperties {
+        SemiringProperties::LEFT_SEMIRING
+            | SemiringProperties::RIGHT_SEMIRING
+            | SemiringProperties::COMMUTATIVE
+    }
+}
+
+impl PartialEq for SignedLogWeight {
+    /// Zero is equal to zero whatever its sign.
+    fn eq(&self, other: &Self) -> bool {
+        (self.is_zero() && other.is_zero())
+            || (self.is_positive() == other.is_positive() && self.weight.1 == other.weight.1)
+    }
+}
+
+impl Eq for SignedLogWeight {}
+
+impl Hash for SignedLogWeight {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if !self.is_zero() {
+            self.is_positive().hash(state);
+        }
+        self.weight.1.hash(state);
+    }
+}
+
+impl Default for SignedLogWeight {
+    fn default() -> Self {
+        Self::one()
+    }
+}
+
+impl ReverseBack<SignedLogWeight> for SignedLogWeight {
+    fn reverse_back(&self) -> Result<SignedLogWeight> {
+        Ok(*self)
+    }
+}
+
+impl AsRef<SignedLogWeight> for SignedLogWeight {
+    fn as_ref(&self) -> &SignedLogWeight {
+        self
+    }
+}
+
+impl StarSemiring for SignedLogWeight {
+    /// `1 / (1 - w)` when `w < 1`, infinity otherwise.
+    fn closure(&self) -> Self {
+        if self.is_positive() && self.log_value() <= 0.0 {
+            return Self::from_sign_and_value(true, f32::NEG_INFINITY);
+        }
+        let mut denominator = Self::one();
+        denominator.plus_assign(self.negate()).unwrap();
+        Self::from_sign_and_value(true, -denominator.log_value())
+    }
+}
+
+impl WeaklyDivisibleSemiring for SignedLogWeight {
+    fn divide_assign(&mut self, rhs: &Self, _divide_type: DivideType) -> Result<()> {
+        if rhs.is_zero() {
+            bail!("SignedLogWeight : division by zero");
+        }
+        let positive = self.is_positive() == rhs.is_positive();
+        self.weight.0 = sign(positive);
+        self.weight
+            .1
+            .divide_assign(&rhs.weight.1, DivideType::DivideAny)?;
+        Ok(())
+    }
+}
+
+impl WeightMinus for SignedLogWeight {
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()> {
+        self.plus_assign(rhs.negate())
+    }
+}
+
+impl ExpectationModule<LogWeight> for SignedLogWeight {
+    fn scale(&self, scalar: &LogWeight) -> Result<Self> {
+        self.times(Self::new((sign(true), *scalar)))
+    }
+}
+
+impl WeightQuantize for SignedLogWeight {
+    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+        self.weight.1.quantize_assign(delta)
+    }
+}
+
+impl fmt::Display for SignedLogWeight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.weight.0, self.weight.1)?;
+        Ok(())
+    }
+}
+
+impl SerializableSemiring for SignedLogWeight {
+    fn weight_type() -> String {
+        "signed_log".to_string()
+    }
+
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, weight_1) = TropicalWeight::parse_binary(i)?;
+        let (i, weight_2) = LogWeight::parse_binary(i)?;
+        Ok((i, Self::new((weight_1, weight_2))))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        self.weight.0.write_binary(file)?;
+        self.weight.1.write_binary(file)?;
+        Ok(())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (i, weight_1) = TropicalWeight::parse_text(i)?;
+        let (i, _) = tag(",")(i)?;
+        let (i, weight_2) = LogWeight::parse_text(i)?;
+        Ok((i, Self::new((weight_1, weight_2))))
+    }
+}
+
+test_semiring_serializable!(
+    tests_signed_log_weight_serializable,
+    SignedLogWeight,
+    SignedLogWeight::one() SignedLogWeight::zero() SignedLogWeight::from_sign_and_value(true, 0.3) SignedLogWeight::from_sign_and_value(false, 0.5) SignedLogWeight::from_sign_and_value(false, -1.2)
+);
+
+impl From<LogWeight> for SignedLogWeight {
+    fn from(w: LogWeight) -> Self {
+        Self::new((sign(true), w))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semirings::ExpectationWeight;
+
+    fn probability(w: &SignedLogWeight) -> f32 {
+        let p = (-w.log_value()).exp();
+        if w.is_positive() {
+            p
+        } else {
+            -p
+        }
+    }
+
+    fn from_probability(p: f32) -> SignedLogWeight {
+        SignedLogWeight::from_sign_and_value(p >= 0.0, -p.abs().ln())
+    }
+
+    #[test]
+    fn test_signed_log_weight_operations() -> Result<()> {
+        let w1 = from_probability(0.5);
+        let w2 = from_probability(-0.2);
+        assert!((probability(&w1.plus(w2)?) - 0.3).abs() < 1e-6);
+        assert!((probability(&w2.plus(w1)?) - 0.3).abs() < 1e-6);
+        assert!((probability(&w1.times(w2)?) + 0.1).abs() < 1e-6);
+        assert!((probability(&w2.minus(&w1)?) + 0.7).abs() < 1e-6);
+        assert!((probability(&w1.divide(&w2, DivideType::DivideAny)?) + 2.5).abs() < 1e-5);
+        assert!((probability(&w1.closure()) - 2.0).abs() < 1e-6);
+        assert!(w1.minus(&w1)?.is_zero());
+        assert_eq!(w1.negate().plus(w1)?, SignedLogWeight::zero());
+        assert_eq!(w1.times(SignedLogWeight::one())?, w1);
+        assert_eq!(w2.plus(SignedLogWeight::zero())?, w2);
+        assert_eq!(format!("{}", w2), format!("-1,{}", w2.value2()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_log_weight_expectation_divide() -> Result<()> {
+        // Contrary to LogWeight, the expectation of the quotient can be negative.
+        let w1 = ExpectationWeight::new((LogWeight::new(0.0), from_probability(0.1)));
+        let w2 = ExpectationWeight::new((LogWeight::new(0.0), from_probability(0.5)));
+        let quotient = w1.divide(&w2, DivideType::DivideAny)?;
+        assert!((probability(quotient.value2()) + 0.4).abs() < 1e-6);
+        assert!(quotient.times(&w2)?.approx_equal(&w1, 1e-6));
+        Ok(())
+    }
+}
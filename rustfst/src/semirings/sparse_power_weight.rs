@@ -0,0 +1,408 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Write;
+
+use anyhow::Result;
+use nom::bytes::complete::tag;
+use nom::combinator::map_res;
+use nom::multi::count;
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::parsers::nom_utils::{num, NomCustomError};
+use crate::parsers::{parse_bin_i32, parse_bin_i64, write_bin_i32, write_bin_i64};
+use crate::semirings::{
+    DivideType, ExpectationModule, ReverseBack, Semiring, SemiringProperties, SerializableSemiring,
+    WeaklyDivisibleSemiring, WeightMinus, WeightQuantize,
+};
+#[cfg(test)]
+use crate::semirings::{LogWeight, TropicalWeight};
+
+/// Key written in binary serialization when a sparse weight has no component.
+const NO_KEY: i32 = -1;
+
+/// Sparse cartesian power semiring: W ^ n where n is not fixed and most
+/// of the components are equal to a default value.
+///
+/// The weight is stored as a default value and a list of `(key, weight)`
+/// pairs sorted by key. Components equal to the default value are never stored.
+#[derive(Debug, Eq, PartialOrd, PartialEq, Clone, Hash)]
+pub struct SparsePowerWeight<W: Semiring> {
+    weight: (W, Vec<(usize, W)>),
+}
+
+impl<W: Semiring> AsRef<Self> for SparsePowerWeight<W> {
+    fn as_ref(&self) -> &SparsePowerWeight<W> {
+        self
+    }
+}
+
+impl<W: Semiring> SparsePowerWeight<W> {
+    /// Create a weight with all its components equal to `default_value`.
+    pub fn from_default(default_value: W) -> Self {
+        Self {
+            weight: (default_value, vec![]),
+        }
+    }
+
+    /// Default value of the components not stored explicitly.
+    pub fn default_value(&self) -> &W {
+        &self.weight.0
+    }
+
+    /// Components that differ from the default value, sorted by key.
+    pub fn entries(&self) -> &[(usize, W)] {
+        &self.weight.1
+    }
+
+    /// Weight of the component `key`.
+    pub fn value_at(&self, key: usize) -> &W {
+        match self.weight.1.binary_search_by_key(&key, |(k, _)| *k) {
+            Ok(idx) => &self.weight.1[idx].1,
+            Err(_) => &self.weight.0,
+        }
+    }
+
+    /// Set the weight of the component `key`.
+    pub fn set_value_at(&mut self, key: usize, value: W) {
+        let is_default = value == self.weight.0;
+        match self.weight.1.binary_search_by_key(&key, |(k, _)| *k) {
+            Ok(idx) if is_default => {
+                self.weight.1.remove(idx);
+            }
+            Ok(idx) => self.weight.1[idx].1 = value,
+            Err(_) if is_default => {}
+            Err(idx) => self.weight.1.insert(idx, (key, value)),
+        }
+    }
+
+    /// Apply `f` component-wise to two sparse weights. Keys missing in
+    /// one of the weights take its default value.
+    fn map_with<W2, F>(&self, rhs: &Self, f: F) -> Result<SparsePowerWeight<W2>>
+    where
+        W2: Semiring,
+        F: Fn(&W, &W) -> Result<W2>,
+    {
+        let (default1, entries1) = &self.weight;
+        let (default2, entries2) = &rhs.weight;
+        let default = f(default1, default2)?;
+        let mut entries = Vec::with_capacity(entries1.len().max(entries2.len()));
+        let mut it1 = entries1.iter().peekable();
+        let mut it2 = entries2.iter().peekable();
+        loop {
+            let (key, w) = match (it1.peek(), it2.peek()) {
+                (Some((k1, w1)), Some((k2, w2))) => match k1.cmp(k2) {
+                    Ordering::Less => {
+                        it1.next();
+                        (*k1, f(w1, default2)?)
+                    }
+                    Ordering::Greater => {
+                        it2.next();
+                        (*k2, f(default1, w2)?)
+                    }
+                    Ordering::Equal => {
+                        it1.next();
+                        it2.next();
+                        (*k1, f(w1, w2)?)
+                    }
+                },
+                (Some((k1, w1)), None) => {
+                    it1.next();
+                    (*k1, f(w1, default2)?)
+                }
+                (None, Some((k2, w2))) => {
+                    it2.next();
+                    (*k2, f(default1, w2)?)
+                }
+                (None, None) => break,
+            };
+            if w != default {
+                entries.push((key, w));
+            }
+        }
+        Ok(SparsePowerWeight {
+            weight: (default, entries),
+        })
+    }
+
+    fn map<W2, F>(&self, f: F) -> Result<SparsePowerWeight<W2>>
+    where
+        W2: Semiring,
+        F: Fn(&W) -> Result<W2>,
+    {
+        let default = f(&self.weight.0)?;
+        let mut entries = Vec::with_capacity(self.weight.1.len());
+        for (key, w) in self.weight.1.iter() {
+            let w = f(w)?;
+            if w != default {
+                entries.push((*key, w));
+            }
+        }
+        Ok(SparsePowerWeight {
+            weight: (default, entries),
+        })
+    }
+}
+
+impl<W: Semiring> Semiring for SparsePowerWeight<W> {
+    type Type = (W, Vec<(usize, W)>);
+    type ReverseWeight = SparsePowerWeight<W::ReverseWeight>;
+
+    fn zero() -> Self {
+        Self::from_default(W::zero())
+    }
+
+    fn one() -> Self {
+        Self::from_default(W::one())
+    }
+
+    /// The entries are sorted by key. Entries equal to the default value are
+    /// dropped and only the first entry of each key is kept.
+    fn new(value: <Self as Semiring>::Type) -> Self {
+        let (default, mut entries) = value;
+        entries.sort_by_key(|(k, _)| *k);
+        entries.dedup_by_key(|(k, _)| *k);
+        entries.retain(|(_, w)| *w != default);
+        Self {
+            weight: (default, entries),
+        }
+    }
+
+    fn plus_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        *self = self.map_with(rhs.borrow(), |w1, w2| w1.plus(w2))?;
+        Ok(())
+    }
+
+    fn times_assign<P: Borrow<Self>>(&mut self, rhs: P) -> Result<()> {
+        *self = self.map_with(rhs.borrow(), |w1, w2| w1.times(w2))?;
+        Ok(())
+    }
+
+    fn approx_equal<P: Borrow<Self>>(&self, rhs: P, delta: f32) -> bool {
+        let rhs = rhs.borrow();
+        self.default_value()
+            .approx_equal(rhs.default_value(), delta)
+            && self
+                .entries()
+                .iter()
+                .all(|(key, w)| w.approx_equal(rhs.value_at(*key), delta))
+            && rhs
+                .entries()
+                .iter()
+                .all(|(key, w)| self.value_at(*key).approx_equal(w, delta))
+    }
+
+    fn value(&self) -> &<Self as Semiring>::Type {
+        &self.weight
+    }
+
+    fn take_value(self) -> <Self as Semiring>::Type {
+        self.weight
+    }
+
+    fn set_value(&mut self, value: <Self as Semiring>::Type) {
+        *self = Self::new(value);
+    }
+
+    fn reverse(&self) -> Result<Self::ReverseWeight> {
+        self.map(|w| w.reverse())
+    }
+
+    fn properties() -> SemiringProperties {
+        W::properties()
+            & (SemiringProperties::LEFT_SEMIRING
+                | SemiringProperties::RIGHT_SEMIRING
+                | SemiringProperties::COMMUTATIVE
+                | SemiringProperties::IDEMPOTENT)
+    }
+}
+
+impl<W: Semiring> ReverseBack<SparsePowerWeight<W>>
+    for <SparsePowerWeight<W> as Semiring>::ReverseWeight
+{
+    fn reverse_back(&self) -> Result<SparsePowerWeight<W>> {
+        self.map(|w| w.reverse_back())
+    }
+}
+
+impl<W: WeaklyDivisibleSemiring> WeaklyDivisibleSemiring for SparsePowerWeight<W> {
+    fn divide_assign(&mut self, rhs: &Self, divide_type: DivideType) -> Result<()> {
+        *self = self.map_with(rhs, |w1, w2| w1.divide(w2, divide_type))?;
+        Ok(())
+    }
+}
+
+impl<W: WeightMinus> WeightMinus for SparsePowerWeight<W> {
+    fn minus_assign(&mut self, rhs: &Self) -> Result<()> {
+        *self = self.map_with(rhs, |w1, w2| w1.minus(w2))?;
+        Ok(())
+    }
+}
+
+impl<W: Semiring> ExpectationModule<W> for SparsePowerWeight<W> {
+    fn scale(&self, scalar: &W) -> Result<Self> {
+        self.map(|w| scalar.times(w))
+    }
+}
+
+impl<W: WeightQuantize> WeightQuantize for SparsePowerWeight<W> {
+    fn quantize_assign(&mut self, delta: f32) -> Result<()> {
+        *self = self.map(|w| w.quantize(delta))?;
+        Ok(())
+    }
+}
+
+impl<W: SerializableSemiring> fmt::Display for SparsePowerWeight<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.default_value())?;
+        for (key, w) in self.entries() {
+            write!(f, ",{},{}", key, w)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_bin_entry<W: SerializableSemiring>(
+    i: &[u8],
+) -> IResult<&[u8], (i32, W), NomCustomError<&[u8]>> {
+    let (i, key) = parse_bin_i32(i)?;
+    let (i, weight) = W::parse_binary(i)?;
+    Ok((i, (key, weight)))
+}
+
+impl<W: SerializableSemiring> SerializableSemiring for SparsePowerWeight<W> {
+    fn weight_type() -> String {
+        format!("{}_^n", W::weight_type())
+    }
+
+    /// Same layout as OpenFst : the default value, then the first component
+    /// (`-1` and zero if there is none) and finally the other components
+    /// preceded by their number.
+    fn parse_binary(i: &[u8]) -> IResult<&[u8], Self, NomCustomError<&[u8]>> {
+        let (i, default) = W::parse_binary(i)?;
+        let (i, first) = parse_bin_entry::<W>(i)?;
+        let (i, n) = map_res(parse_bin_i64, usize::try_from)(i)?;
+        let (i, rest) = count(parse_bin_entry::<W>, n)(i)?;
+        let entries = std::iter::once(first)
+            .chain(rest)
+            .filter(|(key, _)| *key != NO_KEY)
+            .map(|(key, w)| (key as usize, w))
+            .collect();
+        Ok((i, Self::new((default, entries))))
+    }
+
+    fn write_binary<F: Write>(&self, file: &mut F) -> Result<()> {
+        self.default_value().write_binary(file)?;
+        match self.entries().split_first() {
+            None => {
+                write_bin_i32(file, NO_KEY)?;
+                W::zero().write_binary(file)?;
+                write_bin_i64(file, 0)?;
+            }
+            Some(((key, w), rest)) => {
+                write_bin_i32(file, *key as i32)?;
+                w.write_binary(file)?;
+                write_bin_i64(file, rest.len() as i64)?;
+                for (key, w) in rest {
+                    write_bin_i32(file, *key as i32)?;
+                    w.write_binary(file)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_text(i: &str) -> IResult<&str, Self> {
+        let (mut i, default) = W::parse_text(i)?;
+        let mut entries = vec![];
+        while let Ok((next, (key, w))) = tuple((
+            preceded(tag(","), num::<usize>),
+            preceded(tag(","), W::parse_text),
+        ))(i)
+        {
+            entries.push((key, w));
+            i = next;
+        }
+        Ok((i, Self::new((default, entries))))
+    }
+}
+
+test_semiring_serializable!(
+    tests_sparse_power_weight_serializable,
+    SparsePowerWeight::<TropicalWeight>,
+    SparsePowerWeight::new((TropicalWeight::new(0.5), vec![(3, TropicalWeight::new(1.7)), (12, TropicalWeight::new(3.0))]))
+    SparsePowerWeight::new((TropicalWeight::one(), vec![(0, TropicalWeight::new(2.0))]))
+    SparsePowerWeight::<TropicalWeight>::one()
+    SparsePowerWeight::<TropicalWeight>::zero()
+);
+
+test_semiring_serializable!(
+    tests_sparse_power_weight_log_serializable,
+    SparsePowerWeight::<LogWeight>,
+    SparsePowerWeight::new((
+        LogWeight::zero(),
+        vec![(1, LogWeight::new(0.2)), (100_000, LogWeight::new(1.5))]
+    ))
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_power_weight_operations() -> Result<()> {
+        let w1 = SparsePowerWeight::new((
+            TropicalWeight::zero(),
+            vec![(1, TropicalWeight::new(1.0)), (5, TropicalWeight::new(2.0))],
+        ));
+        let w2 = SparsePowerWeight::new((
+            TropicalWeight::zero(),
+            vec![(5, TropicalWeight::new(3.0)), (7, TropicalWeight::new(4.0))],
+        ));
+
+        let sum = w1.plus(&w2)?;
+        assert_eq!(sum.value_at(1), &TropicalWeight::new(1.0));
+        assert_eq!(sum.value_at(5), &TropicalWeight::new(2.0));
+        assert_eq!(sum.value_at(7), &TropicalWeight::new(4.0));
+        assert_eq!(sum.value_at(1000), &TropicalWeight::zero());
+
+        // Only the key present in both weights survives the product.
+        let product = w1.times(&w2)?;
+        assert_eq!(product.entries(), &[(5, TropicalWeight::new(5.0))]);
+        assert_eq!(product.default_value(), &TropicalWeight::zero());
+
+        assert_eq!(w1.times(SparsePowerWeight::one())?, w1);
+        assert_eq!(w1.plus(SparsePowerWeight::zero())?, w1);
+        assert_eq!(
+            SparsePowerWeight::<TropicalWeight>::weight_type(),
+            "tropical_^n"
+        );
+        let w3 =
+            SparsePowerWeight::new((TropicalWeight::one(), vec![(2, TropicalWeight::new(3.0))]));
+        assert_eq!(format!("{}", w3), "0,2,3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_power_weight_set_value_at() {
+        let mut w = SparsePowerWeight::<TropicalWeight>::one();
+        w.set_value_at(4, TropicalWeight::new(2.0));
+        assert_eq!(w.entries(), &[(4, TropicalWeight::new(2.0))]);
+        w.set_value_at(4, TropicalWeight::one());
+        assert!(w.entries().is_empty());
+        assert_eq!(w, SparsePowerWeight::one());
+    }
+
+    #[test]
+    fn test_sparse_power_weight_parse_binary_negative_count() -> Result<()> {
+        let mut data = vec![];
+        TropicalWeight::zero().write_binary(&mut data)?;
+        write_bin_i32(&mut data, 1)?;
+        TropicalWeight::new(1.0).write_binary(&mut data)?;
+        write_bin_i64(&mut data, -1)?;
+        assert!(SparsePowerWeight::<TropicalWeight>::parse_binary(&data).is_err());
+        Ok(())
+    }
+}
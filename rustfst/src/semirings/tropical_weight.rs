@@ -17,13 +17,14 @@ use crate::parsers::write_bin_f32;
 use crate::semirings::semiring::SerializableSemiring;
 use crate::semirings::utils_float::float_approx_equal;
 use crate::semirings::{
-    CompleteSemiring, DivideType, ReverseBack, Semiring, SemiringProperties, StarSemiring,
-    WeaklyDivisibleSemiring, WeightQuantize,
+    CompleteSemiring, DivideType, MappableSemiring, ReverseBack, Semiring, SemiringProperties,
+    StarSemiring, WeaklyDivisibleSemiring, WeightQuantize,
 };
 use crate::KDELTA;
 
 /// Tropical semiring: (min, +, inf, 0).
 #[derive(Clone, Debug, PartialOrd, Default, Copy, Eq)]
+#[repr(transparent)]
 pub struct TropicalWeight {
     value: OrderedFloat<f32>,
 }
@@ -157,6 +158,9 @@ impl SerializableSemiring for TropicalWeight {
     }
 }
 
+// Safety: the weight is a transparent f32 serialized as its little-endian bytes.
+unsafe impl MappableSemiring for TropicalWeight {}
+
 test_semiring_serializable!(
     tests_tropical_weight_serializable,
     TropicalWeight,
@@ -18,6 +18,7 @@ use std::hash::BuildHasher;
 #[derive(Debug, Clone)]
 pub struct SymbolTable<H: BuildHasher = RandomState> {
     bimap: BiHashMapString<H>,
+    name: Option<String>,
 }
 
 impl Default for SymbolTable {
@@ -45,6 +46,7 @@ impl SymbolTable {
     pub fn empty() -> Self {
         SymbolTable {
             bimap: BiHashMapString::new(),
+            name: None,
         }
     }
 
@@ -57,7 +59,7 @@ impl SymbolTable {
             }
         }
 
-        Ok(SymbolTable { bimap })
+        Ok(SymbolTable { bimap, name: None })
     }
 
     pub fn from_text_string(symt_string: &str) -> Result<Self> {
@@ -65,9 +67,12 @@ impl SymbolTable {
         Self::from_parsed_symt_text(parsed_symt)
     }
 
+    /// Reads a `SymbolTable` from a text file. As in OpenFst, the table is named after the path.
     pub fn read_text<P: AsRef<Path>>(path_text_symt: P) -> Result<Self> {
-        let parsed_symt = ParsedTextSymt::from_path(path_text_symt)?;
-        Self::from_parsed_symt_text(parsed_symt)
+        let parsed_symt = ParsedTextSymt::from_path(path_text_symt.as_ref())?;
+        let mut symt = Self::from_parsed_symt_text(parsed_symt)?;
+        symt.set_name(path_text_symt.as_ref().to_string_lossy());
+        Ok(symt)
     }
 
     pub fn read<P: AsRef<Path>>(path_bin_symt: P) -> Result<Self> {
@@ -97,7 +102,16 @@ impl<H: BuildHasher> SymbolTable<H> {
     pub fn with_hasher(hasher_builder: H) -> Self {
         let mut bimap = BiHashMapString::with_hasher(hasher_builder);
         bimap.get_id_or_insert(EPS_SYMBOL);
-        Self { bimap }
+        Self { bimap, name: None }
+    }
+
+    /// Name of the `SymbolTable`, e.g. the one stored in the OpenFst binary format.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = Some(name.into());
     }
 
     pub fn is_empty(&self) -> bool {
@@ -299,6 +313,7 @@ impl<H: BuildHasher> fmt::Display for SymbolTable<H> {
     }
 }
 
+/// Two `SymbolTable`s are equal when they contain the same symbols, whatever their names.
 impl<H: BuildHasher> PartialEq for SymbolTable<H> {
     fn eq(&self, other: &Self) -> bool {
         self.bimap.eq(&other.bimap)
@@ -461,4 +476,20 @@ mod tests {
         assert_eq!(symt1.get_label("b"), Some(2));
         assert_eq!(symt1.get_label("c"), Some(3));
     }
+
+    #[test]
+    fn test_symt_name_binary_round_trip() -> Result<()> {
+        let mut symt = SymbolTable::new();
+        symt.add_symbol("a");
+        symt.set_name("letters");
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("symt.bin");
+        symt.write(&path)?;
+        let read_symt = SymbolTable::read(&path)?;
+
+        assert_eq!(read_symt.name(), Some("letters"));
+        assert_eq!(read_symt, symt);
+        Ok(())
+    }
 }
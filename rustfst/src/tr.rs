@@ -2,7 +2,10 @@ use crate::semirings::SerializableSemiring;
 use crate::{Label, StateId};
 
 /// Structure representing a transition from a state to another state in a FST.
+///
+/// The layout follows the order of the fields, which is the one of the OpenFst binary format.
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
+#[repr(C)]
 pub struct Tr<W> {
     /// Input label.
     pub ilabel: Label,